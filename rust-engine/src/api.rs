@@ -1,14 +1,32 @@
-use crate::buffers::EntityBufferLayout;
-use crate::buffers::plan_layout;
-use crate::constants::SOLAR_ARRAY_LOCAL_MAX_ENERGY_PER_LEVEL;
+use crate::buffers::{capacity_with_spare, plan_layout_with_capacity, relocate_data, EntityBufferLayout, EntityCounts};
+use crate::constants::{
+    DRONE_MAX_INTEGRITY, DRONE_STATE_DISABLED, DRONE_STATE_IDLE, DRONE_STATE_RETURNING,
+    DRONE_STATE_TO_ASTEROID, LOGISTICS_DROPOFF_OVERHEAD, LOGISTICS_HAULER_CAPACITY,
+    LOGISTICS_HAULER_SPEED, LOGISTICS_PICKUP_OVERHEAD, SOLAR_ARRAY_LOCAL_MAX_ENERGY_PER_LEVEL,
+};
 use crate::error::SimulationError;
+use crate::loadouts::{loadout_multipliers, LoadoutMultipliers};
+use crate::recipes;
 use crate::modifiers::get_resource_modifiers;
 use crate::rng::Mulberry32;
-use crate::schema::{Modules, Resources, SimulationSnapshot, StoreSettings, RefineProcessSnapshot};
-use crate::buffers::MAX_REFINE_SLOTS;
-use crate::constants::{FACTORY_REFINE_TIME, FACTORY_ENERGY_PER_REFINE};
+use crate::schema::{DroneLoadout, DroneOrder, FactorySnapshot, HaulerConfig, HaulerUsage, Modules, Resources, SimulationSnapshot, StoreSettings, RefineProcessSnapshot};
+use crate::spatial::{distance, SpatialGrid, ASTEROID_GRID_CELL_SIZE};
+use crate::buffers::{FACTORY_RESOURCES, FACTORY_RESOURCE_STRIDE, FACTORY_UPGRADE_STRIDE, MAX_REFINE_SLOTS};
+use crate::constants::FACTORY_ENERGY_PER_REFINE;
+use crate::systems::asteroids::{generate_asteroid, HazardRoll, ASTEROID_FIELD_TARGET};
 use crate::systems::drone_ai::{self, AsteroidMetadata};
+use crate::systems::factory_activity::FACTORY_DEFAULT_YAW;
 use crate::systems::fleet;
+use crate::systems::balance;
+use crate::systems::contracts::{self, ContractEvent};
+use crate::systems::haulers;
+use crate::systems::hazards::{self, HazardEvent};
+use crate::systems::orders::{self, DroneOrderEvent};
+use crate::systems::logistics::{
+    compute_warehouse_capacity, HAULER_MODES, HAULER_PRIORITY_MAX, WAREHOUSE_NODE_ID,
+};
+use crate::systems::market;
+use crate::systems::research;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::cmp;
@@ -36,6 +54,27 @@ pub struct OfflineResult {
     pub snapshot_json: String,
 }
 
+/// What a prestige reset converted and rebuilt.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PrestigeSummary {
+    /// Bars consumed by the conversion.
+    pub bars_converted: f32,
+    /// Cores granted by this prestige.
+    pub cores_gained: i32,
+    /// Core balance after the prestige.
+    pub total_cores: i32,
+    /// Factories torn down before the starter factory was rebuilt.
+    pub factories_reset: usize,
+    /// Drones removed before the starting drone was respawned.
+    pub drones_reset: usize,
+    /// Asteroids in the regenerated field.
+    pub asteroids_generated: usize,
+    /// Game time of the run that just ended.
+    pub run_time: f32,
+    /// Number of prestiges including this one.
+    pub lifetime_prestiges: u32,
+}
+
 /// Commands accepted by the simulation engine to modify state.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
@@ -47,12 +86,14 @@ pub enum SimulationCommand {
     /// Overwrites the global settings.
     SetSettings(StoreSettings),
 
-    /// Purchases a new module level, deducting costs.
+    /// Purchases a new module level, deducting costs. `costVariant: "credits"` pays in credits.
     BuyModule {
         #[serde(rename = "moduleType")]
         module_type: String,
         #[serde(rename = "factoryId")]
         factory_id: Option<String>,
+        #[serde(default, rename = "costVariant")]
+        cost_variant: Option<String>,
     },
 
     /// Performs a prestige reset, converting bars to cores.
@@ -75,6 +116,16 @@ pub enum SimulationCommand {
         count: i32,
     },
 
+    /// Edits a factory's hauler resource filters, mode and priority; omitted fields are kept.
+    SetHaulerConfig {
+        #[serde(rename = "factoryId")]
+        factory_id: String,
+        #[serde(rename = "resourceFilters")]
+        resource_filters: Option<Vec<String>>,
+        mode: Option<String>,
+        priority: Option<i32>,
+    },
+
     /// Imports a full game state from a JSON string.
     ImportPayload {
         #[serde(rename = "snapshotJson")]
@@ -92,6 +143,129 @@ pub enum SimulationCommand {
         #[serde(rename = "asteroidId")]
         asteroid_id: String,
     },
+
+    /// Builds a new factory at an RNG-chosen position, paying metals and crystals.
+    BuildFactory,
+
+    /// Removes a factory, re-homing its drones and in-flight logistics.
+    DecommissionFactory {
+        #[serde(rename = "factoryId")]
+        factory_id: String,
+    },
+
+    /// Buys the next level of a specialization tech once its resource spend unlocks it.
+    PurchaseSpecTech {
+        #[serde(rename = "techId")]
+        tech_id: String,
+    },
+
+    /// Buys the next tier of a prestige investment.
+    InvestPrestige {
+        #[serde(rename = "investmentId")]
+        investment_id: String,
+    },
+
+    /// Fits an equipment tier into one of a drone's slots, paying that tier's price.
+    EquipDrone {
+        #[serde(rename = "droneId")]
+        drone_id: String,
+        slot: String,
+        tier: u32,
+    },
+
+    /// Empties one of a drone's equipment slots; nothing is refunded.
+    UnequipDrone {
+        #[serde(rename = "droneId")]
+        drone_id: String,
+        slot: String,
+    },
+
+    /// Gives a drone a manual order, replacing any order it already had.
+    OrderDrone {
+        #[serde(rename = "droneId")]
+        drone_id: String,
+        order: DroneOrder,
+    },
+
+    /// Cancels a drone's manual order and returns it to automatic targeting.
+    ClearDroneOrder {
+        #[serde(rename = "droneId")]
+        drone_id: String,
+    },
+
+    /// Toggles refinery overdrive: faster refining that builds heat much faster.
+    SetRefineryOverdrive {
+        #[serde(rename = "factoryId")]
+        factory_id: String,
+        enabled: bool,
+    },
+
+    /// Picks the recipe a refine slot runs. A batch already in the slot is scrapped and its
+    /// unrefined inputs go back into factory storage.
+    SetRefineRecipe {
+        #[serde(rename = "factoryId")]
+        factory_id: String,
+        slot: u32,
+        recipe: String,
+    },
+
+    /// Sells resources for credits, from a factory's stock or, without `factoryId`, the warehouse.
+    Sell {
+        resource: String,
+        amount: f32,
+        #[serde(default, rename = "factoryId")]
+        factory_id: Option<String>,
+    },
+
+    /// Buys resources into the warehouse with credits.
+    Buy { resource: String, amount: f32 },
+
+    /// Sets a standing order that sells warehouse stock above `reserve`; `null` cancels it.
+    SetAutoSell { resource: String, reserve: Option<f32> },
+
+    /// Takes an open contract offer; its deadline starts counting from now.
+    AcceptContract {
+        #[serde(rename = "contractId")]
+        contract_id: String,
+    },
+
+    /// Drops an active contract without reward, or withdraws an open offer.
+    AbandonContract {
+        #[serde(rename = "contractId")]
+        contract_id: String,
+    },
+
+    /// Starts a research node, paying its point cost up front.
+    StartResearch {
+        #[serde(rename = "nodeId")]
+        node_id: String,
+    },
+
+    /// Hands warehouse crystals to the labs in exchange for research points.
+    FundResearch { crystals: f32 },
+
+    /// Makes a factory the drone's owner; the drone returns there from now on.
+    ReassignDrone {
+        #[serde(rename = "droneId")]
+        drone_id: String,
+        #[serde(rename = "factoryId")]
+        factory_id: String,
+    },
+
+    /// Releases a drone assigned by command; it keeps its owner but may be rebalanced again.
+    UnassignDrone {
+        #[serde(rename = "droneId")]
+        drone_id: String,
+    },
+
+    /// Moves up to `count` drones owned by one factory to another, idle drones first.
+    RebalanceDrones {
+        #[serde(rename = "fromFactoryId")]
+        from_factory_id: String,
+        #[serde(rename = "toFactoryId")]
+        to_factory_id: String,
+        count: u32,
+    },
 }
 
 /// The core game state managed by the Rust engine.
@@ -103,6 +277,10 @@ pub struct GameState {
     pub layout: EntityBufferLayout,
     game_time: f32,
     logistics_tick: f32,
+    /// Time since the last drone auto-balancer pass.
+    balance_tick: f32,
+    /// Time since the last market tick.
+    market_tick: f32,
     /// The linear memory buffer containing entity data (SoA layout).
     pub data: Vec<u32>,
     entity_id_counter: u32,
@@ -112,6 +290,17 @@ pub struct GameState {
     asteroid_id_to_index: BTreeMap<String, usize>,
     asteroid_index_to_id: Vec<String>,
     asteroid_metadata: Vec<AsteroidMetadata>,
+    /// Buckets asteroid indices by position for drone target queries.
+    asteroid_grid: SpatialGrid,
+    /// Bumped whenever `data` is reallocated or an entity group changes size; JS must re-read
+    /// the layout and recreate its typed views when it changes.
+    generation: u32,
+    /// Hazard phase changes not yet collected by the host.
+    hazard_events: Vec<HazardEvent>,
+    /// Drone orders completed, lost or cancelled but not yet collected by the host.
+    order_events: Vec<DroneOrderEvent>,
+    /// Contracts completed, expired or abandoned but not yet collected by the host.
+    contract_events: Vec<ContractEvent>,
 }
 
 impl GameState {
@@ -119,6 +308,51 @@ impl GameState {
         &self.drone_index_to_id
     }
 
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns and clears the hazard events raised since the last call.
+    pub fn drain_hazard_events(&mut self) -> Vec<HazardEvent> {
+        std::mem::take(&mut self.hazard_events)
+    }
+
+    /// Returns and clears the drone order events raised since the last call.
+    pub fn drain_order_events(&mut self) -> Vec<DroneOrderEvent> {
+        std::mem::take(&mut self.order_events)
+    }
+
+    /// Returns and clears the contract events raised since the last call.
+    pub fn drain_contract_events(&mut self) -> Vec<ContractEvent> {
+        std::mem::take(&mut self.contract_events)
+    }
+
+    /// Current sell price of every tradeable good, including goods still at their base price.
+    pub fn market_prices(&self) -> BTreeMap<String, f32> {
+        market::MARKET_GOODS
+            .iter()
+            .map(|good| (good.resource.to_string(), market::current_price(&self.snapshot.market, good)))
+            .collect()
+    }
+
+    /// Busy haulers and utilization of every factory the logistics system has counted.
+    pub fn hauler_usage(&self) -> BTreeMap<String, HaulerUsage> {
+        self.snapshot
+            .factories
+            .iter()
+            .filter_map(|factory| {
+                let state = factory.logistics_state.as_ref()?;
+                Some((
+                    factory.id.clone(),
+                    HaulerUsage {
+                        busy: state.haulers_busy,
+                        utilization: state.hauler_utilization,
+                    },
+                ))
+            })
+            .collect()
+    }
+
     pub fn asteroid_ids(&self) -> &[String] {
         &self.asteroid_index_to_id
     }
@@ -169,12 +403,16 @@ impl GameState {
         format!("{}-{:x}", prefix, self.entity_id_counter)
     }
 
-    fn rekey_respawned_asteroids(&mut self, respawned_indices: &[usize]) {
+    fn rekey_respawned_asteroids(
+        &mut self,
+        respawned_indices: &[usize],
+        respawned_hazards: &[(usize, HazardRoll)],
+    ) {
         if respawned_indices.is_empty() {
             return;
         }
 
-        let mut updates: Vec<(usize, String, f32)> = Vec::new();
+        let mut updates: Vec<(usize, String, f32, Value)> = Vec::new();
 
         for &idx in respawned_indices {
             if idx >= self.asteroid_index_to_id.len() {
@@ -188,21 +426,35 @@ impl GameState {
                 .get(idx)
                 .map(|m| m.gravity_multiplier)
                 .unwrap_or(1.0);
+            let roll = respawned_hazards.iter().find(|(i, _)| *i == idx).map(|(_, roll)| roll);
+            let biome = self
+                .asteroid_metadata
+                .get(idx)
+                .and_then(|m| m.fracture.as_ref())
+                .map(|fracture| crate::systems::asteroids::biome_json(fracture, roll))
+                .unwrap_or(Value::Null);
 
             self.asteroid_index_to_id[idx] = new_id.clone();
             self.asteroid_id_to_index.remove(&old_id);
+            self.snapshot.asteroid_hazards.retain(|h| h.asteroid_id != old_id);
+            if let Some(roll) = roll {
+                self.snapshot
+                    .asteroid_hazards
+                    .push(hazards::new_hazard(&new_id, roll));
+            }
             self.asteroid_id_to_index.insert(new_id.clone(), idx);
-            updates.push((idx, new_id, gravity));
+            updates.push((idx, new_id, gravity, biome));
         }
 
         let Some(asteroids) = asteroid_array_mut(&mut self.snapshot.extra) else {
             return;
         };
 
-        for (idx, new_id, gravity) in updates {
+        for (idx, new_id, gravity, biome) in updates {
             if let Some(Value::Object(obj)) = asteroids.get_mut(idx) {
                 obj.insert("id".to_string(), Value::String(new_id));
                 obj.insert("gravityMultiplier".to_string(), serde_json::Value::from(gravity));
+                obj.insert("biome".to_string(), biome);
 
                 // TS spawn sets regions to null; Rust keeps metadata regions separately.
                 obj.insert("regions".to_string(), Value::Null);
@@ -210,6 +462,434 @@ impl GameState {
         }
     }
 
+    /// Replaces fractured asteroids' hazards with those of their new regions. Hazards dropped
+    /// mid-storm report an end so the host does not show them running forever.
+    fn rehazard_fractured_asteroids(&mut self, fractured_indices: &[usize]) {
+        for &idx in fractured_indices {
+            let (Some(asteroid_id), Some(meta)) =
+                (self.asteroid_index_to_id.get(idx), self.asteroid_metadata.get(idx))
+            else {
+                continue;
+            };
+            let game_time = self.game_time;
+            let events = &mut self.hazard_events;
+            self.snapshot.asteroid_hazards.retain(|hazard| {
+                if &hazard.asteroid_id != asteroid_id {
+                    return true;
+                }
+                if hazard.active {
+                    events.push(HazardEvent {
+                        asteroid_id: hazard.asteroid_id.clone(),
+                        kind: hazard.kind.clone(),
+                        severity: hazard.severity.clone(),
+                        phase: "ended".to_string(),
+                        game_time,
+                    });
+                }
+                false
+            });
+            let seed = meta.fracture.as_ref().map(|f| f.seed).unwrap_or(0);
+            self.snapshot
+                .asteroid_hazards
+                .extend(hazards::region_hazards(asteroid_id, &meta.regions, seed));
+        }
+    }
+
+    /// Seeds hazards for asteroids the snapshot carries none for, such as fields spawned in TS.
+    fn seed_missing_hazards(&mut self) {
+        let Some(asteroids) = asteroid_array(&self.snapshot.extra) else {
+            return;
+        };
+        let mut seeded = Vec::new();
+        for (idx, asteroid_id) in self.asteroid_index_to_id.iter().enumerate() {
+            if self.snapshot.asteroid_hazards.iter().any(|h| &h.asteroid_id == asteroid_id) {
+                continue;
+            }
+            let (Some(asteroid), Some(meta)) = (asteroids.get(idx), self.asteroid_metadata.get(idx)) else {
+                continue;
+            };
+            seeded.extend(hazards::seed_hazards(asteroid_id, asteroid, &meta.regions));
+        }
+        self.snapshot.asteroid_hazards.extend(seeded);
+    }
+
+    /// Writes fractured asteroids' regions, blended gravity and profile back to their JSON.
+    fn write_fractured_asteroids(&mut self, fractured_indices: &[usize]) {
+        let profile_offset = self.layout.asteroids.resource_profile.offset_bytes / 4;
+        let Some(asteroids) = asteroid_array_mut(&mut self.snapshot.extra) else {
+            return;
+        };
+        for &idx in fractured_indices {
+            let (Some(meta), Some(Value::Object(obj))) =
+                (self.asteroid_metadata.get(idx), asteroids.get_mut(idx))
+            else {
+                continue;
+            };
+            let base = profile_offset + idx * 5;
+            let profile: Vec<f32> = (0..5).map(|j| f32::from_bits(self.data[base + j])).collect();
+            let regions: Vec<Value> = meta.regions.iter().map(|r| r.to_json()).collect();
+            obj.insert("regions".to_string(), Value::Array(regions));
+            obj.insert("gravityMultiplier".to_string(), Value::from(meta.gravity_multiplier));
+            obj.insert(
+                "resourceProfile".to_string(),
+                serde_json::json!({
+                    "ore": profile[0],
+                    "ice": profile[1],
+                    "metals": profile[2],
+                    "crystals": profile[3],
+                    "organics": profile[4],
+                }),
+            );
+            obj.insert(
+                "dominantResource".to_string(),
+                Value::from(crate::systems::asteroids::dominant_resource_of(&profile)),
+            );
+        }
+    }
+
+    /// Records damaged drones' hull integrity and which of them are disabled; undamaged drones
+    /// are left out.
+    fn sync_drone_integrity_to_snapshot(&mut self) {
+        let (Some(integrity), Some(states)) = (
+            self.layout.drones.integrity.as_f32_slice(&self.data),
+            self.layout.drones.states.as_f32_slice(&self.data),
+        ) else {
+            return;
+        };
+        self.snapshot.drone_integrity = self
+            .drone_index_to_id
+            .iter()
+            .zip(integrity)
+            .filter(|(_, &value)| value < DRONE_MAX_INTEGRITY)
+            .map(|(id, &value)| (id.clone(), value))
+            .collect();
+        self.snapshot.disabled_drones = self
+            .drone_index_to_id
+            .iter()
+            .zip(states)
+            .filter(|(_, &state)| state == DRONE_STATE_DISABLED)
+            .map(|(id, _)| id.clone())
+            .collect();
+    }
+
+    /// Copies the live fracture countdowns into the asteroid JSON before export.
+    fn sync_fracture_timers_to_snapshot(&mut self) {
+        let Some(asteroids) = asteroid_array_mut(&mut self.snapshot.extra) else {
+            return;
+        };
+        for (meta, asteroid) in self.asteroid_metadata.iter().zip(asteroids.iter_mut()) {
+            if let (Some(state), Value::Object(obj)) = (&meta.fracture, asteroid) {
+                let hazard = obj.get("biome").and_then(|b| b.get("hazard")).cloned();
+                let mut biome = crate::systems::asteroids::fracture_json(state);
+                if let Some(hazard) = hazard {
+                    biome["hazard"] = hazard;
+                }
+                obj.insert("biome".to_string(), biome);
+            }
+        }
+    }
+}
+
+impl GameState {
+    fn bump_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Grows section capacities when `needed` exceeds the reserved slots.
+    /// This is the only path (besides loading a snapshot) that reallocates `data`.
+    fn ensure_capacity(&mut self, needed: EntityCounts) -> Result<(), SimulationError> {
+        let capacity = self.layout.capacities();
+        if needed.drones <= capacity.drones
+            && needed.asteroids <= capacity.asteroids
+            && needed.factories <= capacity.factories
+            && needed.haulers <= capacity.haulers
+        {
+            return Ok(());
+        }
+
+        let grown = EntityCounts::new(
+            grow_capacity(capacity.drones, needed.drones),
+            grow_capacity(capacity.asteroids, needed.asteroids),
+            grow_capacity(capacity.factories, needed.factories),
+            grow_capacity(capacity.haulers, needed.haulers),
+        );
+        let layout = plan_layout_with_capacity(self.layout.counts(), grown)?;
+        let mut data = vec![0; layout.total_size_bytes.div_ceil(4)];
+        relocate_data(&self.layout, &self.data, &layout, &mut data);
+        self.layout = layout;
+        self.data = data;
+        self.bump_generation();
+        Ok(())
+    }
+
+    /// Adds a drone in the next free slot, keeping existing buffer state and the RNG intact.
+    pub fn add_drone(&mut self, owner_factory_id: Option<&str>) -> Result<String, SimulationError> {
+        let mut needed = self.layout.counts();
+        needed.drones += 1;
+        self.ensure_capacity(needed)?;
+
+        let index = self.layout.drones.count();
+        for section in self.layout.drones.sections_mut() {
+            section.set_entity_count(index + 1);
+        }
+        self.bump_generation();
+        for section in self.layout.drones.sections() {
+            section.clear_entity(&mut self.data, index);
+        }
+
+        let drone_id = self.next_entity_id("drone");
+        self.drone_id_to_index.insert(drone_id.clone(), index);
+        self.drone_index_to_id.push(drone_id.clone());
+
+        let owner_idx = owner_factory_id.and_then(|id| self.factory_id_to_index.get(id).copied());
+        if let (Some(owner_id), Some(_)) = (owner_factory_id, owner_idx) {
+            self.snapshot
+                .drone_owners
+                .insert(drone_id.clone(), Some(owner_id.to_string()));
+        }
+
+        let layout = &self.layout;
+        if layout.factories.count() > 0 {
+            let src = layout.factories.positions.offset_bytes / 4 + owner_idx.unwrap_or(0) * 3;
+            let dst = layout.drones.positions.offset_bytes / 4 + index * 3;
+            self.data.copy_within(src..src + 3, dst);
+        }
+        let owner_encoded = owner_idx.map(|idx| idx as f32).unwrap_or(-1.0);
+        self.data[layout.drones.owner_factory_index.offset_bytes / 4 + index] = owner_encoded.to_bits();
+        for section in [
+            &layout.drones.target_factory_index,
+            &layout.drones.target_asteroid_index,
+            &layout.drones.target_region_index,
+        ] {
+            self.data[section.offset_bytes / 4 + index] = (-1.0f32).to_bits();
+        }
+        self.data[layout.drones.integrity.offset_bytes / 4 + index] = DRONE_MAX_INTEGRITY.to_bits();
+
+        self.seed_drone_stats();
+        Ok(drone_id)
+    }
+
+    /// Removes a drone, shifting later drones down one slot. Returns false for unknown ids.
+    pub fn remove_drone(&mut self, drone_id: &str) -> bool {
+        let Some(index) = self.drone_id_to_index.remove(drone_id) else {
+            return false;
+        };
+
+        for section in self.layout.drones.sections() {
+            section.remove_entity(&mut self.data, index);
+        }
+        let count = self.layout.drones.count().saturating_sub(1);
+        for section in self.layout.drones.sections_mut() {
+            section.set_entity_count(count);
+        }
+        self.bump_generation();
+
+        if index < self.drone_index_to_id.len() {
+            self.drone_index_to_id.remove(index);
+        }
+        for idx in self.drone_id_to_index.values_mut() {
+            if *idx > index {
+                *idx -= 1;
+            }
+        }
+
+        self.snapshot.drone_flights.retain(|flight| flight.drone_id != drone_id);
+        self.snapshot.drone_owners.remove(drone_id);
+        self.snapshot.drone_loadouts.remove(drone_id);
+        self.snapshot.drone_integrity.remove(drone_id);
+        self.snapshot.disabled_drones.remove(drone_id);
+        self.snapshot.assigned_drones.remove(drone_id);
+        self.snapshot.drone_orders.remove(drone_id);
+        for factory in self.snapshot.factories.iter_mut() {
+            factory.queued_drones.retain(|id| id != drone_id);
+        }
+        true
+    }
+
+    /// Adds or removes trailing drones until the live count matches the drone bay level.
+    fn sync_drone_slots_to_bay(&mut self) -> Result<(), SimulationError> {
+        let target = cmp::max(1, self.snapshot.modules.drone_bay as usize);
+        while self.layout.drones.count() < target {
+            self.add_drone(None)?;
+        }
+        while self.layout.drones.count() > target {
+            let Some(drone_id) = self.drone_index_to_id.last().cloned() else {
+                break;
+            };
+            self.remove_drone(&drone_id);
+        }
+        Ok(())
+    }
+
+    /// Appends a serialized asteroid to the field and its buffer slot.
+    pub fn add_asteroid(&mut self, asteroid: Value) -> Result<usize, SimulationError> {
+        let asteroid_id = asteroid
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|id| id.to_string())
+            .ok_or(SimulationError::MissingField("asteroid.id"))?;
+
+        let mut needed = self.layout.counts();
+        needed.asteroids += 1;
+        self.ensure_capacity(needed)?;
+
+        let index = self.layout.asteroids.count();
+        for section in self.layout.asteroids.sections_mut() {
+            section.set_entity_count(index + 1);
+        }
+        self.bump_generation();
+        for section in self.layout.asteroids.sections() {
+            section.clear_entity(&mut self.data, index);
+        }
+        write_asteroid_slot(&self.layout, &mut self.data, index, &asteroid);
+        let pos_offset = self.layout.asteroids.positions.offset_bytes / 4 + index * 3;
+        self.asteroid_grid.update(
+            index,
+            [
+                f32::from_bits(self.data[pos_offset]),
+                f32::from_bits(self.data[pos_offset + 1]),
+                f32::from_bits(self.data[pos_offset + 2]),
+            ],
+        );
+
+        let metadata = drone_ai::asteroid_metadata_from_value(&asteroid);
+        if !self.snapshot.asteroid_hazards.iter().any(|h| h.asteroid_id == asteroid_id) {
+            let seeded = hazards::seed_hazards(&asteroid_id, &asteroid, &metadata.regions);
+            self.snapshot.asteroid_hazards.extend(seeded);
+        }
+        self.asteroid_metadata.push(metadata);
+        self.asteroid_id_to_index.insert(asteroid_id.clone(), index);
+        self.asteroid_index_to_id.push(asteroid_id);
+
+        match asteroid_array_mut(&mut self.snapshot.extra) {
+            Some(asteroids) => asteroids.push(asteroid),
+            None => {
+                self.snapshot
+                    .extra
+                    .insert("asteroids".to_string(), Value::Array(vec![asteroid]));
+            }
+        }
+        Ok(index)
+    }
+
+    /// Removes an asteroid, shifting later asteroids down and retargeting drones that pointed at it.
+    pub fn remove_asteroid(&mut self, asteroid_id: &str) -> bool {
+        let Some(index) = self.asteroid_id_to_index.remove(asteroid_id) else {
+            return false;
+        };
+
+        for section in self.layout.asteroids.sections() {
+            section.remove_entity(&mut self.data, index);
+        }
+        let count = self.layout.asteroids.count().saturating_sub(1);
+        for section in self.layout.asteroids.sections_mut() {
+            section.set_entity_count(count);
+        }
+        self.bump_generation();
+
+        if index < self.asteroid_index_to_id.len() {
+            self.asteroid_index_to_id.remove(index);
+        }
+        if index < self.asteroid_metadata.len() {
+            self.asteroid_metadata.remove(index);
+        }
+        self.asteroid_grid.remove(index);
+        for idx in self.asteroid_id_to_index.values_mut() {
+            if *idx > index {
+                *idx -= 1;
+            }
+        }
+        if let Some(asteroids) = asteroid_array_mut(&mut self.snapshot.extra) {
+            asteroids.retain(|a| a.get("id").and_then(|v| v.as_str()) != Some(asteroid_id));
+        }
+        self.snapshot
+            .asteroid_hazards
+            .retain(|h| h.asteroid_id != asteroid_id);
+
+        let target_base = self.layout.drones.target_asteroid_index.offset_bytes / 4;
+        let region_base = self.layout.drones.target_region_index.offset_bytes / 4;
+        for i in 0..self.layout.drones.count() {
+            let target = f32::from_bits(self.data[target_base + i]);
+            if target < 0.0 {
+                continue;
+            }
+            let target = target as usize;
+            if target == index {
+                self.data[target_base + i] = (-1.0f32).to_bits();
+                self.data[region_base + i] = (-1.0f32).to_bits();
+            } else if target > index {
+                self.data[target_base + i] = ((target - 1) as f32).to_bits();
+            }
+        }
+        true
+    }
+
+    /// Appends a factory to the snapshot and its buffer slot.
+    pub fn add_factory(&mut self, factory: FactorySnapshot) -> Result<usize, SimulationError> {
+        let mut needed = self.layout.counts();
+        needed.factories += 1;
+        self.ensure_capacity(needed)?;
+
+        let index = self.layout.factories.count();
+        for section in self.layout.factories.sections_mut() {
+            section.set_entity_count(index + 1);
+        }
+        self.bump_generation();
+        for section in self.layout.factories.sections() {
+            section.clear_entity(&mut self.data, index);
+        }
+        write_factory_slot(&self.layout, &mut self.data, index, &factory);
+
+        self.factory_id_to_index.insert(factory.id.clone(), index);
+        self.snapshot.factories.push(factory);
+        Ok(index)
+    }
+
+    /// Removes a factory, shifting later factories down and remapping drone factory indices.
+    /// Callers are responsible for re-homing drones and logistics that reference the factory id.
+    pub fn remove_factory(&mut self, factory_id: &str) -> bool {
+        let Some(index) = self.factory_id_to_index.remove(factory_id) else {
+            return false;
+        };
+
+        for section in self.layout.factories.sections() {
+            section.remove_entity(&mut self.data, index);
+        }
+        let count = self.layout.factories.count().saturating_sub(1);
+        for section in self.layout.factories.sections_mut() {
+            section.set_entity_count(count);
+        }
+        self.bump_generation();
+
+        if index < self.snapshot.factories.len() {
+            self.snapshot.factories.remove(index);
+        }
+        for idx in self.factory_id_to_index.values_mut() {
+            if *idx > index {
+                *idx -= 1;
+            }
+        }
+
+        for section in [
+            &self.layout.drones.owner_factory_index,
+            &self.layout.drones.target_factory_index,
+        ] {
+            let base = section.offset_bytes / 4;
+            for i in 0..section.length {
+                let value = f32::from_bits(self.data[base + i]);
+                if value < 0.0 {
+                    continue;
+                }
+                let value = value as usize;
+                if value == index {
+                    self.data[base + i] = (-1.0f32).to_bits();
+                } else if value > index {
+                    self.data[base + i] = ((value - 1) as f32).to_bits();
+                }
+            }
+        }
+        true
+    }
 }
 
 impl GameState {
@@ -226,11 +906,9 @@ impl GameState {
         let factory_count = snapshot.factories.len();
         let asteroid_count = asteroid_count(&snapshot);
 
-        let layout = plan_layout(
-            total_drone_count,
-            asteroid_count,
-            factory_count,
-        )?;
+        let hauler_count = hauler_count(&snapshot);
+        let counts = EntityCounts::new(total_drone_count, asteroid_count, factory_count, hauler_count);
+        let layout = plan_layout_with_capacity(counts, counts.with_spare())?;
 
         // Ensure size is multiple of 4
         let size_u32 = layout.total_size_bytes.div_ceil(4);
@@ -290,6 +968,8 @@ impl GameState {
             rng: Mulberry32::new(rng_seed),
             layout,
             logistics_tick: 0.0,
+            balance_tick: 0.0,
+            market_tick: 0.0,
             data,
             entity_id_counter: 0,
             drone_id_to_index,
@@ -298,6 +978,11 @@ impl GameState {
             asteroid_id_to_index,
             asteroid_index_to_id,
             asteroid_metadata,
+            asteroid_grid: SpatialGrid::new(ASTEROID_GRID_CELL_SIZE),
+            generation: 0,
+            hazard_events: Vec::new(),
+            order_events: Vec::new(),
+            contract_events: Vec::new(),
         };
 
         state.entity_id_counter = derive_entity_id_counter(&state.snapshot);
@@ -306,7 +991,7 @@ impl GameState {
         // Even when asteroids are provided explicitly in the snapshot, we must advance the RNG
         // so that subsequent random decisions (targets, paths, biomes) consume the same sequence.
         burn_rng_for_asteroids(&mut state.rng, asteroid_count);
-        state.initialize_data_from_snapshot();
+        state.initialize_data_from_snapshot()?;
         Ok(state)
     }
 
@@ -324,13 +1009,12 @@ impl GameState {
         let factory_count = snapshot.factories.len();
         let asteroid_count = asteroid_count(&snapshot);
 
-        self.layout = plan_layout(
-            total_drone_count,
-            asteroid_count,
-            factory_count,
-        )?;
+        let hauler_count = hauler_count(&snapshot);
+        let counts = EntityCounts::new(total_drone_count, asteroid_count, factory_count, hauler_count);
+        self.layout = plan_layout_with_capacity(counts, counts.with_spare())?;
         let size_u32 = self.layout.total_size_bytes.div_ceil(4);
         self.data = vec![0; size_u32];
+        self.bump_generation();
         self.rng = Mulberry32::new(snapshot.rng_seed.unwrap_or(1));
 
         self.drone_id_to_index.clear();
@@ -377,6 +1061,9 @@ impl GameState {
 
         self.game_time = snapshot.game_time;
         self.snapshot = snapshot;
+        self.hazard_events.clear();
+        self.order_events.clear();
+        self.contract_events.clear();
         self.drone_index_to_id = build_drone_index_to_id(&self.drone_id_to_index, total_drone_count);
         self.asteroid_index_to_id = asteroid_index_to_id;
         self.asteroid_metadata =
@@ -385,13 +1072,15 @@ impl GameState {
         self.entity_id_counter = derive_entity_id_counter(&self.snapshot);
 
         burn_rng_for_asteroids(&mut self.rng, asteroid_count);
-        self.initialize_data_from_snapshot();
+        self.initialize_data_from_snapshot()?;
         Ok(())
     }
 
-    fn initialize_data_from_snapshot(&mut self) {
+    fn initialize_data_from_snapshot(&mut self) -> Result<(), SimulationError> {
         // Initialize globals
         self.sync_globals_to_buffer();
+        self.sync_haulers()?;
+        self.seed_missing_hazards();
 
         // Initialize factories
         let factories = &self.snapshot.factories;
@@ -401,46 +1090,7 @@ impl GameState {
 
         for factory in factories.iter() {
             if let Some(&index) = factory_map.get(&factory.id) {
-                let offset = layout.factories.positions.offset_bytes / 4 + index * 3;
-                data[offset] = factory.position[0].to_bits();
-                data[offset + 1] = factory.position[1].to_bits();
-                data[offset + 2] = factory.position[2].to_bits();
-
-                let offset = layout.factories.resources.offset_bytes / 4 + index * 7;
-                data[offset] = factory.resources.ore.to_bits();
-                data[offset + 1] = factory.resources.ice.to_bits();
-                data[offset + 2] = factory.resources.metals.to_bits();
-                data[offset + 3] = factory.resources.crystals.to_bits();
-                data[offset + 4] = factory.resources.organics.to_bits();
-                data[offset + 5] = factory.resources.bars.to_bits();
-                data[offset + 6] = factory.resources.credits.to_bits();
-
-                let offset = layout.factories.energy.offset_bytes / 4 + index;
-                data[offset] = factory.energy.to_bits();
-
-                let offset = layout.factories.max_energy.offset_bytes / 4 + index;
-                data[offset] = factory.energy_capacity.to_bits();
-
-                let offset = layout.factories.upgrades.offset_bytes / 4 + index * 5;
-                data[offset] = (factory.upgrades.docking as f32).to_bits();
-                data[offset + 1] = (factory.upgrades.refine as f32).to_bits();
-                data[offset + 2] = (factory.upgrades.storage as f32).to_bits();
-                data[offset + 3] = (factory.upgrades.energy as f32).to_bits();
-                data[offset + 4] = (factory.upgrades.solar as f32).to_bits();
-
-                let haulers_offset = layout.factories.haulers_assigned.offset_bytes / 4 + index;
-                data[haulers_offset] = (factory.haulers_assigned.unwrap_or(0) as f32).to_bits();
-
-                // Initialize refinery state slots from factory.active_refines if provided
-                let ref_idx_base = layout.factories.refinery_state.offset_bytes / 4 + index * (MAX_REFINE_SLOTS * 4);
-                let slots_limit = factory.refine_slots as usize;
-                for (s, proc) in factory.active_refines.iter().enumerate().take(slots_limit.min(MAX_REFINE_SLOTS)) {
-                    let slot_offset = ref_idx_base + s * 4;
-                    data[slot_offset] = (1.0f32).to_bits(); // active
-                    data[slot_offset + 1] = proc.amount.to_bits();
-                    data[slot_offset + 2] = proc.progress.to_bits();
-                    data[slot_offset + 3] = proc.speed_multiplier.to_bits();
-                }
+                write_factory_slot(layout, data, index, factory);
             }
         }
 
@@ -486,6 +1136,18 @@ impl GameState {
 
              let offset = layout.drones.target_asteroid_index.offset_bytes / 4 + index;
              data[offset] = (-1.0f32).to_bits();
+
+             let integrity = self
+                 .snapshot
+                 .drone_integrity
+                 .get(drone_id)
+                 .copied()
+                 .unwrap_or(DRONE_MAX_INTEGRITY)
+                 .clamp(0.0, DRONE_MAX_INTEGRITY);
+             data[layout.drones.integrity.offset_bytes / 4 + index] = integrity.to_bits();
+             if integrity <= 0.0 || self.snapshot.disabled_drones.contains(drone_id) {
+                 data[layout.drones.states.offset_bytes / 4 + index] = DRONE_STATE_DISABLED.to_bits();
+             }
         }
 
         // Initialize drones (flights)
@@ -504,7 +1166,7 @@ impl GameState {
                     "idle" => 0.0,
                     "toAsteroid" => 1.0,
                     "mining" => 2.0,
-                    "returning" => 3.0,
+                    "returning" | "queued" => 3.0,
                     "unloading" => 4.0,
                     _ => 0.0,
                 };
@@ -574,60 +1236,52 @@ impl GameState {
             for asteroid in asteroids {
                 if let Some(id) = asteroid.get("id").and_then(|v| v.as_str()) {
                     if let Some(&index) = asteroid_map.get(id) {
-                        if let Some(pos) = asteroid.get("position").and_then(|v| v.as_array()) {
-                            if pos.len() >= 3 {
-                                let offset = layout.asteroids.positions.offset_bytes / 4 + index * 3;
-                                data[offset] = (pos[0].as_f64().unwrap_or(0.0) as f32).to_bits();
-                                data[offset + 1] = (pos[1].as_f64().unwrap_or(0.0) as f32).to_bits();
-                                data[offset + 2] = (pos[2].as_f64().unwrap_or(0.0) as f32).to_bits();
-                            }
-                        }
-
-                        if let Some(ore) = asteroid.get("oreRemaining").and_then(|v| v.as_f64()) {
-                            let offset = layout.asteroids.ore_remaining.offset_bytes / 4 + index;
-                            data[offset] = (ore as f32).to_bits();
-                        }
-
-                        if let Some(max_ore) = asteroid.get("maxOre").and_then(|v| v.as_f64()) {
-                            let offset = layout.asteroids.max_ore.offset_bytes / 4 + index;
-                            data[offset] = (max_ore as f32).to_bits();
-                        }
-
-                        // Initialize resource profile
-                        if let Some(profile) = asteroid.get("resourceProfile").and_then(|v| v.as_object()) {
-                            let base_offset = layout.asteroids.resource_profile.offset_bytes / 4 + index * 5;
-                            let ore = profile.get("ore").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                            let ice = profile.get("ice").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                            let metals = profile.get("metals").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                            let crystals = profile.get("crystals").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                            let organics = profile.get("organics").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-
-                            data[base_offset] = ore.to_bits();
-                            data[base_offset + 1] = ice.to_bits();
-                            data[base_offset + 2] = metals.to_bits();
-                            data[base_offset + 3] = crystals.to_bits();
-                            data[base_offset + 4] = organics.to_bits();
-                        } else {
-                            // Default to 100% ore if missing
-                            let base_offset = layout.asteroids.resource_profile.offset_bytes / 4 + index * 5;
-                            data[base_offset] = (1.0f32).to_bits();
-                            data[base_offset + 1] = (0.0f32).to_bits();
-                            data[base_offset + 2] = (0.0f32).to_bits();
-                            data[base_offset + 3] = (0.0f32).to_bits();
-                            data[base_offset + 4] = (0.0f32).to_bits();
-                        }
+                        write_asteroid_slot(layout, data, index, asteroid);
                     }
                 }
             }
         }
 
-        // Seed drone stats (capacity, battery, mining) to match TS fleet system defaults
+        self.seed_drone_stats();
+        self.rebuild_asteroid_grid();
+        Ok(())
+    }
+
+    /// Re-buckets every asteroid from the position buffer.
+    fn rebuild_asteroid_grid(&mut self) {
+        let positions = self
+            .layout
+            .asteroids
+            .positions
+            .as_f32_slice(&self.data)
+            .unwrap_or(&[]);
+        self.asteroid_grid.rebuild(positions);
+    }
+
+    /// Equipment multipliers per drone index, for the fleet and AI systems.
+    fn drone_loadout_multipliers(&self) -> Vec<LoadoutMultipliers> {
+        self.drone_index_to_id
+            .iter()
+            .map(|id| {
+                self.snapshot
+                    .drone_loadouts
+                    .get(id)
+                    .map(loadout_multipliers)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Seeds drone stats (capacity, battery, mining) to match TS fleet system defaults.
+    fn seed_drone_stats(&mut self) {
         let modifiers = get_resource_modifiers(
             &self.snapshot.resources,
             self.snapshot.prestige.cores,
             self.snapshot.prestige_investments.as_ref(),
             self.snapshot.spec_techs.as_ref(),
+            &self.snapshot.research.completed,
         );
+        let drone_loadouts = self.drone_loadout_multipliers();
 
         unsafe {
             let data_ptr = self.data.as_mut_ptr();
@@ -641,6 +1295,7 @@ impl GameState {
             let drone_max_battery = get_slice_mut(&self.layout.drones.max_battery);
             let drone_capacity = get_slice_mut(&self.layout.drones.capacity);
             let drone_mining_rate = get_slice_mut(&self.layout.drones.mining_rate);
+            let drone_speed = get_slice_mut(&self.layout.drones.speed);
             let drone_cargo = get_slice_mut(&self.layout.drones.cargo);
             let drone_cargo_profile = get_slice_mut(&self.layout.drones.cargo_profile);
 
@@ -649,8 +1304,10 @@ impl GameState {
                 drone_max_battery,
                 drone_capacity,
                 drone_mining_rate,
+                drone_speed,
                 drone_cargo,
                 drone_cargo_profile,
+                &drone_loadouts,
                 &self.snapshot.modules,
                 &modifiers,
             );
@@ -660,6 +1317,8 @@ impl GameState {
     /// Serializes the current internal state back to a JSON snapshot string.
     pub fn export_snapshot_str(&mut self) -> Result<String, SimulationError> {
         self.sync_data_to_snapshot();
+        self.sync_fracture_timers_to_snapshot();
+        self.sync_drone_integrity_to_snapshot();
         serde_json::to_string(&self.snapshot).map_err(SimulationError::parse)
     }
 
@@ -676,7 +1335,7 @@ impl GameState {
             if i >= self.layout.factories.resources.length { break; }
 
             // Resources
-            let offset = self.layout.factories.resources.offset_bytes / 4 + i * 7;
+            let offset = self.layout.factories.resources.offset_bytes / 4 + i * FACTORY_RESOURCE_STRIDE;
             factory.resources.ore = f32::from_bits(self.data[offset]);
             factory.resources.ice = f32::from_bits(self.data[offset + 1]);
             factory.resources.metals = f32::from_bits(self.data[offset + 2]);
@@ -684,6 +1343,8 @@ impl GameState {
             factory.resources.organics = f32::from_bits(self.data[offset + 4]);
             factory.resources.bars = f32::from_bits(self.data[offset + 5]);
             factory.resources.credits = f32::from_bits(self.data[offset + 6]);
+            factory.resources.alloys = f32::from_bits(self.data[offset + 7]);
+            factory.resources.coolant = f32::from_bits(self.data[offset + 8]);
 
             // Energy
             let offset = self.layout.factories.energy.offset_bytes / 4 + i;
@@ -693,12 +1354,20 @@ impl GameState {
             factory.energy_capacity = f32::from_bits(self.data[offset]);
 
             // Upgrades
-            let offset = self.layout.factories.upgrades.offset_bytes / 4 + i * 5;
+            let offset = self.layout.factories.upgrades.offset_bytes / 4 + i * FACTORY_UPGRADE_STRIDE;
             factory.upgrades.docking = f32::from_bits(self.data[offset]) as i32;
             factory.upgrades.refine = f32::from_bits(self.data[offset + 1]) as i32;
             factory.upgrades.storage = f32::from_bits(self.data[offset + 2]) as i32;
             factory.upgrades.energy = f32::from_bits(self.data[offset + 3]) as i32;
             factory.upgrades.solar = f32::from_bits(self.data[offset + 4]) as i32;
+            factory.upgrades.unload = f32::from_bits(self.data[offset + 5]) as i32;
+            factory.upgrades.cooler = f32::from_bits(self.data[offset + 6]) as i32;
+
+            // Heat
+            let offset = self.layout.factories.heat.offset_bytes / 4 + i;
+            factory.heat = f32::from_bits(self.data[offset]);
+            let offset = self.layout.factories.cooling.offset_bytes / 4 + i;
+            factory.cooling = f32::from_bits(self.data[offset]) > 0.5;
 
             // Haulers
             let offset = self.layout.factories.haulers_assigned.offset_bytes / 4 + i;
@@ -706,12 +1375,17 @@ impl GameState {
 
             // Sync active refines from refinery_state buffer into snapshot.active_refines
             let ref_base = self.layout.factories.refinery_state.offset_bytes / 4 + i * (MAX_REFINE_SLOTS * 4);
+            let recipe_base = self.layout.factories.refine_recipes.offset_bytes / 4 + i * MAX_REFINE_SLOTS;
             let mut new_refines: Vec<RefineProcessSnapshot> = Vec::new();
             let slots_limit = factory.refine_slots as usize;
+            factory.refine_recipes = (0..slots_limit.min(MAX_REFINE_SLOTS))
+                .map(|s| recipes::recipe_at(f32::from_bits(self.data[recipe_base + s])).id.to_string())
+                .collect();
             for s in 0..slots_limit.min(MAX_REFINE_SLOTS) {
                 let slot_offset = ref_base + s * 4;
                 let active = f32::from_bits(self.data[slot_offset]);
                 if active > 0.5 {
+                    let recipe = recipes::recipe_at(f32::from_bits(self.data[recipe_base + s]));
                     let amount = f32::from_bits(self.data[slot_offset + 1]);
                     let progress = f32::from_bits(self.data[slot_offset + 2]);
                     let speed_multiplier = f32::from_bits(self.data[slot_offset + 3]);
@@ -719,11 +1393,15 @@ impl GameState {
                     let existing = factory.active_refines.get(s).cloned();
                     let id = existing.as_ref().map(|e| e.id.clone()).unwrap_or_else(|| format!("refine-{}-{}", factory.id, s));
                     let ore_type = existing.as_ref().map(|e| e.ore_type.clone()).unwrap_or_else(|| "ore".to_string());
-                    let time_total = existing.as_ref().map(|e| e.time_total).unwrap_or(FACTORY_REFINE_TIME);
-                    let energy_required = existing.as_ref().map(|e| e.energy_required).unwrap_or(FACTORY_ENERGY_PER_REFINE);
+                    let time_total = recipe.refine_time;
+                    let energy_required = existing
+                        .as_ref()
+                        .map(|e| e.energy_required)
+                        .unwrap_or(FACTORY_ENERGY_PER_REFINE * recipe.energy_multiplier);
                     new_refines.push(RefineProcessSnapshot {
                         id,
                         ore_type,
+                        recipe: recipe.id.to_string(),
                         amount,
                         progress,
                         time_total,
@@ -811,15 +1489,6 @@ impl GameState {
         }
     }
 
-    /// Rebuilds the internal state (layout and buffers) from the current snapshot.
-    /// This is used when the number of entities changes (e.g. buying modules).
-    fn rebuild_state(&mut self) -> Result<(), SimulationError> {
-        self.sync_data_to_snapshot();
-        let new_state = GameState::from_snapshot(self.snapshot.clone())?;
-        *self = new_state;
-        Ok(())
-    }
-
     /// Advances the simulation by dt seconds.
     /// Runs all systems (refinery, movement, power, mining, unload, AI).
     pub fn step(&mut self, dt: f32) -> TickResult {
@@ -837,8 +1506,11 @@ impl GameState {
             self.snapshot.prestige.cores,
             self.snapshot.prestige_investments.as_ref(),
             self.snapshot.spec_techs.as_ref(),
+            &self.snapshot.research.completed,
         );
         let sink_bonuses = crate::sinks::get_sink_bonuses(&self.snapshot);
+        let drone_loadouts = self.drone_loadout_multipliers();
+        let mut warehouse_deliveries = BTreeMap::new();
 
         // SAFETY: All buffer sections are validated during layout planning.
         // The unsafe helper creates non-overlapping slices for each system call.
@@ -860,6 +1532,7 @@ impl GameState {
                 let drone_max_battery = get_slice_mut(&self.layout.drones.max_battery);
                 let drone_capacity = get_slice_mut(&self.layout.drones.capacity);
                 let drone_mining_rate = get_slice_mut(&self.layout.drones.mining_rate);
+                let drone_speed = get_slice_mut(&self.layout.drones.speed);
                 let drone_cargo = get_slice_mut(&self.layout.drones.cargo);
                 let drone_cargo_profile = get_slice_mut(&self.layout.drones.cargo_profile);
 
@@ -868,8 +1541,10 @@ impl GameState {
                     drone_max_battery,
                     drone_capacity,
                     drone_mining_rate,
+                    drone_speed,
                     drone_cargo,
                     drone_cargo_profile,
+                    &drone_loadouts,
                     &self.snapshot.modules,
                     &modifiers,
                 );
@@ -892,6 +1567,7 @@ impl GameState {
                 let asteroid_resource_profile = get_slice_mut(&self.layout.asteroids.resource_profile);
 
                 let mut respawned_indices: Vec<usize> = Vec::new();
+                let mut respawned_hazards: Vec<(usize, HazardRoll)> = Vec::new();
 
                 crate::systems::asteroids::sys_asteroids(
                     asteroid_positions,
@@ -900,6 +1576,8 @@ impl GameState {
                     asteroid_resource_profile,
                     &mut self.asteroid_metadata,
                     &mut respawned_indices,
+                    &mut respawned_hazards,
+                    &mut self.asteroid_grid,
                     &mut self.rng,
                     &sink_bonuses,
                     self.snapshot.modules.scanner,
@@ -907,7 +1585,7 @@ impl GameState {
                 );
 
                 if !respawned_indices.is_empty() {
-                    self.rekey_respawned_asteroids(&respawned_indices);
+                    self.rekey_respawned_asteroids(&respawned_indices, &respawned_hazards);
 
                     let drone_target_asteroid_index =
                         get_slice_mut(&self.layout.drones.target_asteroid_index);
@@ -928,6 +1606,92 @@ impl GameState {
                 }
             }
 
+            // Fracture System (biome countdowns split asteroids into regions)
+            {
+                let asteroid_positions = get_slice_mut(&self.layout.asteroids.positions);
+                let asteroid_resource_profile = get_slice_mut(&self.layout.asteroids.resource_profile);
+                let mut fractured_indices: Vec<usize> = Vec::new();
+
+                crate::systems::asteroids::sys_fracture(
+                    &mut self.asteroid_metadata,
+                    &self.asteroid_index_to_id,
+                    asteroid_resource_profile,
+                    &mut fractured_indices,
+                    &mut self.rng,
+                    dt,
+                );
+
+                if !fractured_indices.is_empty() {
+                    let mut drones = drone_ai::DroneTargetBuffers {
+                        id_to_index: &self.drone_id_to_index,
+                        positions: get_slice_mut(&self.layout.drones.positions),
+                        states: get_slice_mut(&self.layout.drones.states),
+                        target_asteroid_index: get_slice_mut(
+                            &self.layout.drones.target_asteroid_index,
+                        ),
+                        target_region_index: get_slice_mut(&self.layout.drones.target_region_index),
+                    };
+
+                    for &idx in &fractured_indices {
+                        drone_ai::reassign_fractured_drones(
+                            idx,
+                            &self.asteroid_index_to_id[idx],
+                            &self.asteroid_metadata[idx],
+                            asteroid_positions,
+                            &mut self.snapshot.drone_flights,
+                            &mut drones,
+                            &mut self.rng,
+                        );
+                    }
+                    self.rehazard_fractured_asteroids(&fractured_indices);
+                    self.write_fractured_asteroids(&fractured_indices);
+                }
+            }
+
+            // Hazard System (phase timers; effects feed movement, mining and drones below)
+            hazards::sys_hazards(
+                &mut self.snapshot.asteroid_hazards,
+                &mut self.hazard_events,
+                self.game_time,
+                dt,
+            );
+            let hazard_effects = hazards::asteroid_hazard_effects(
+                &self.snapshot.asteroid_hazards,
+                &self.asteroid_id_to_index,
+                self.layout.asteroids.count(),
+            );
+
+            // Drone Balancer (optional; shifts one drone toward an under-served factory per pass)
+            if self.snapshot.settings.auto_balance_drones {
+                self.balance_tick += dt;
+                if self.balance_tick >= balance::DRONE_BALANCE_INTERVAL {
+                    self.balance_tick -= balance::DRONE_BALANCE_INTERVAL;
+                    let docking: Vec<i32> =
+                        self.snapshot.factories.iter().map(|f| f.docking_capacity).collect();
+                    let transfer = balance::sys_drone_balance(
+                        get_slice_mut(&self.layout.drones.states),
+                        get_slice_mut(&self.layout.drones.owner_factory_index),
+                        &docking,
+                    );
+                    if let Some((drone_index, factory_index)) = transfer {
+                        self.reassign_drone(drone_index, factory_index);
+                    }
+                }
+            }
+
+            // Drone Orders (drop orders whose asteroid or factory is gone before drones act on them)
+            {
+                let asteroid_ore = get_slice_mut(&self.layout.asteroids.ore_remaining);
+                orders::sys_drone_orders(
+                    &mut self.snapshot.drone_orders,
+                    &mut self.order_events,
+                    &self.asteroid_id_to_index,
+                    asteroid_ore,
+                    &self.factory_id_to_index,
+                    self.game_time,
+                );
+            }
+
             // Drone AI System (assign new flights/targets before movement)
             {
                 let drone_states = get_slice_mut(&self.layout.drones.states);
@@ -944,6 +1708,7 @@ impl GameState {
                 let drone_max_battery = get_slice_mut(&self.layout.drones.max_battery);
                 let drone_capacity = get_slice_mut(&self.layout.drones.capacity);
                 let drone_mining_rate = get_slice_mut(&self.layout.drones.mining_rate);
+                let mut fulfilled_orders: Vec<String> = Vec::new();
 
                 crate::systems::drone_ai::sys_drone_ai(
                     &mut self.snapshot.drone_flights,
@@ -958,13 +1723,19 @@ impl GameState {
                     drone_target_factory_index,
                     drone_target_region_index,
                     drone_owner_factory_index,
+                    &drone_loadouts,
+                    &self.snapshot.drone_orders,
+                    &mut fulfilled_orders,
+                    &self.snapshot.assigned_drones,
                     &self.drone_index_to_id,
                     &self.drone_id_to_index,
                     &self.factory_id_to_index,
                     &self.asteroid_index_to_id,
+                    &self.asteroid_id_to_index,
                     &mut self.snapshot.factories,
                     factory_positions,
                     asteroid_positions,
+                    &self.asteroid_grid,
                     &self.asteroid_metadata,
                     asteroid_ore,
                     &mut self.rng,
@@ -972,11 +1743,31 @@ impl GameState {
                     &self.snapshot.modules,
                     &sink_bonuses,
                 );
+
+                for drone_id in fulfilled_orders {
+                    if let Some(order) = self.snapshot.drone_orders.remove(&drone_id) {
+                        orders::push_order_event(
+                            &mut self.order_events,
+                            &drone_id,
+                            &order,
+                            "completed",
+                            self.game_time,
+                        );
+                    }
+                }
             }
 
             // Movement System (process flights started by AI)
             {
+                hazards::delay_hazard_flights(
+                    &mut self.snapshot.drone_flights,
+                    &hazard_effects,
+                    &self.asteroid_id_to_index,
+                    dt,
+                );
+
                 let drone_positions = get_slice_mut(&self.layout.drones.positions);
+                let drone_velocities = get_slice_mut(&self.layout.drones.velocities);
                 let drone_battery = get_slice_mut(&self.layout.drones.battery);
                 let drone_states = get_slice_mut(&self.layout.drones.states);
                 let drone_target_asteroid_index = get_slice_mut(&self.layout.drones.target_asteroid_index);
@@ -989,6 +1780,7 @@ impl GameState {
                     &self.factory_id_to_index,
                     &self.asteroid_id_to_index,
                     drone_positions,
+                    drone_velocities,
                     drone_states,
                     drone_battery,
                     drone_target_asteroid_index,
@@ -998,6 +1790,16 @@ impl GameState {
                     self.snapshot.settings.throttle_floor,
                     modifiers.energy_drain_multiplier,
                 );
+
+                orders::complete_arrived_recalls(
+                    &mut self.snapshot.drone_orders,
+                    &mut self.order_events,
+                    &self.drone_id_to_index,
+                    &self.factory_id_to_index,
+                    drone_states,
+                    drone_target_factory_index,
+                    self.game_time,
+                );
             }
 
             // Mining System
@@ -1010,8 +1812,11 @@ impl GameState {
                 let drone_mining_rate = get_slice_mut(&self.layout.drones.mining_rate);
                 let drone_battery = get_slice_mut(&self.layout.drones.battery);
                 let drone_max_battery = get_slice_mut(&self.layout.drones.max_battery);
+                let drone_integrity = get_slice_mut(&self.layout.drones.integrity);
                 let asteroid_ore_remaining = get_slice_mut(&self.layout.asteroids.ore_remaining);
                 let asteroid_resource_profile = get_slice_mut(&self.layout.asteroids.resource_profile);
+                let asteroid_yield_multiplier: Vec<f32> =
+                    hazard_effects.iter().map(|e| e.yield_multiplier).collect();
 
                 crate::systems::mining::sys_mining(
                     drone_states,
@@ -1022,8 +1827,10 @@ impl GameState {
                     drone_mining_rate,
                     drone_battery,
                     drone_max_battery,
+                    drone_integrity,
                     asteroid_ore_remaining,
                     asteroid_resource_profile,
+                    &asteroid_yield_multiplier,
                     dt,
                     self.snapshot.settings.throttle_floor,
                     modifiers.energy_drain_multiplier,
@@ -1031,6 +1838,81 @@ impl GameState {
                 );
             }
 
+            // Hazard effects on drones working inside an active hazard
+            {
+                let drone_states = get_slice_mut(&self.layout.drones.states);
+                let drone_target_asteroid_index = get_slice_mut(&self.layout.drones.target_asteroid_index);
+                let drone_battery = get_slice_mut(&self.layout.drones.battery);
+                let drone_cargo = get_slice_mut(&self.layout.drones.cargo);
+                let drone_cargo_profile = get_slice_mut(&self.layout.drones.cargo_profile);
+
+                hazards::sys_hazard_effects(
+                    &hazard_effects,
+                    drone_states,
+                    drone_target_asteroid_index,
+                    drone_battery,
+                    drone_cargo,
+                    drone_cargo_profile,
+                    dt,
+                );
+            }
+
+            // Wear System (hull damage; drones at zero are disabled and towed home)
+            {
+                let drone_states = get_slice_mut(&self.layout.drones.states);
+                let drone_target_asteroid_index = get_slice_mut(&self.layout.drones.target_asteroid_index);
+                let drone_target_region_index = get_slice_mut(&self.layout.drones.target_region_index);
+                let drone_target_factory_index = get_slice_mut(&self.layout.drones.target_factory_index);
+                let drone_owner_factory_index = get_slice_mut(&self.layout.drones.owner_factory_index);
+                let drone_battery = get_slice_mut(&self.layout.drones.battery);
+                let drone_max_battery = get_slice_mut(&self.layout.drones.max_battery);
+                let drone_integrity = get_slice_mut(&self.layout.drones.integrity);
+                let drone_cargo = get_slice_mut(&self.layout.drones.cargo);
+                let drone_cargo_profile = get_slice_mut(&self.layout.drones.cargo_profile);
+                let drone_positions = get_slice_mut(&self.layout.drones.positions);
+                let drone_velocities = get_slice_mut(&self.layout.drones.velocities);
+                let factory_positions = get_slice_mut(&self.layout.factories.positions);
+                let mut disabled_indices: Vec<usize> = Vec::new();
+
+                crate::systems::wear::sys_wear(
+                    &mut crate::systems::wear::DroneWearBuffers {
+                        states: drone_states,
+                        target_asteroid_index: drone_target_asteroid_index,
+                        target_region_index: drone_target_region_index,
+                        battery: drone_battery,
+                        max_battery: drone_max_battery,
+                        integrity: drone_integrity,
+                    },
+                    &self.asteroid_metadata,
+                    &hazard_effects,
+                    &mut disabled_indices,
+                    dt,
+                );
+
+                for &idx in &disabled_indices {
+                    let Some(drone_id) = self.drone_index_to_id.get(idx) else {
+                        continue;
+                    };
+                    self.snapshot.drone_flights.retain(|f| &f.drone_id != drone_id);
+                    for factory in self.snapshot.factories.iter_mut() {
+                        factory.queued_drones.retain(|id| id != drone_id);
+                    }
+                    drone_cargo[idx] = 0.0;
+                    drone_cargo_profile[idx * 5..idx * 5 + 5].fill(0.0);
+                    drone_velocities[idx * 3..idx * 3 + 3].fill(0.0);
+                    drone_target_asteroid_index[idx] = -1.0;
+                    drone_target_region_index[idx] = -1.0;
+                    drone_target_factory_index[idx] = -1.0;
+                    let owner = drone_owner_factory_index[idx];
+                    let home = if owner >= 0.0 { owner as usize } else { 0 };
+                    if let Some(pos) = factory_positions.get(home * 3..home * 3 + 3) {
+                        drone_positions[idx * 3..idx * 3 + 3].copy_from_slice(pos);
+                        // Unowned drones are repaired wherever they were towed.
+                        drone_target_factory_index[idx] = home as f32;
+                    }
+                }
+            }
+
             // Unload System
             {
                 let drone_states = get_slice_mut(&self.layout.drones.states);
@@ -1042,6 +1924,7 @@ impl GameState {
                 let drone_positions = get_slice_mut(&self.layout.drones.positions);
                 let factory_positions = get_slice_mut(&self.layout.factories.positions);
                 let factory_resources = get_slice_mut(&self.layout.factories.resources);
+                let factory_upgrades = get_slice_mut(&self.layout.factories.upgrades);
 
                 crate::systems::unload::sys_unload(
                     drone_states,
@@ -1053,9 +1936,11 @@ impl GameState {
                     drone_positions,
                     factory_positions,
                     factory_resources,
+                    factory_upgrades,
                     &mut self.snapshot.resources,
                     &mut self.snapshot.factories,
                     &self.drone_index_to_id,
+                    &mut warehouse_deliveries,
                     dt,
                 );
             }
@@ -1071,6 +1956,8 @@ impl GameState {
                 let drone_owner_factory_index = get_slice_mut(&self.layout.drones.owner_factory_index);
                 let drone_target_factory_index = get_slice_mut(&self.layout.drones.target_factory_index);
                 let drone_charging = get_slice_mut(&self.layout.drones.charging);
+                let drone_integrity = get_slice_mut(&self.layout.drones.integrity);
+                let factory_resources = get_slice_mut(&self.layout.factories.resources);
 
                 crate::systems::power::sys_power(
                     &mut self.snapshot.resources,
@@ -1084,6 +1971,8 @@ impl GameState {
                     drone_owner_factory_index,
                     drone_target_factory_index,
                     drone_charging,
+                    drone_integrity,
+                    factory_resources,
                     &self.drone_id_to_index,
                     &self.factory_id_to_index,
                     dt,
@@ -1096,8 +1985,12 @@ impl GameState {
             {
                 let resources = get_slice_mut(&self.layout.factories.resources);
                 let refinery_state = get_slice_mut(&self.layout.factories.refinery_state);
+                let refine_recipes = get_slice_mut(&self.layout.factories.refine_recipes);
                 let haulers_assigned = get_slice_mut(&self.layout.factories.haulers_assigned);
                 let energy = get_slice_mut(&self.layout.factories.energy);
+                let heat = get_slice_mut(&self.layout.factories.heat);
+                let cooling = get_slice_mut(&self.layout.factories.cooling);
+                let factory_upgrades = get_slice_mut(&self.layout.factories.upgrades);
 
                 let idle_energy_per_sec: Vec<f32> = self
                     .snapshot
@@ -1132,17 +2025,23 @@ impl GameState {
                         (f.energy_capacity + bonus) * modifiers.energy_storage_multiplier
                     })
                     .collect();
+                let overdrive: Vec<bool> = self.snapshot.factories.iter().map(|f| f.overdrive).collect();
 
                 crate::systems::refinery::sys_refinery(
                     resources,
                     refinery_state,
+                    refine_recipes,
                     haulers_assigned,
                     energy,
+                    heat,
+                    cooling,
                     &idle_energy_per_sec,
                     &energy_per_refine,
                     &refine_slots,
                     &storage_capacity,
                     &effective_energy_capacity,
+                    factory_upgrades,
+                    &overdrive,
                     dt,
                     modifiers.energy_drain_multiplier,
                     modifiers.storage_capacity_multiplier,
@@ -1150,10 +2049,44 @@ impl GameState {
                     modifiers.refinery_yield_multiplier,
                 );
             }
+
+            // Factory Activity System (activity level + heading for rendering)
+            {
+                let factory_orientations = get_slice_mut(&self.layout.factories.orientations);
+                let factory_activity = get_slice_mut(&self.layout.factories.activity);
+                let factory_positions = get_slice_mut(&self.layout.factories.positions);
+                let refinery_state = get_slice_mut(&self.layout.factories.refinery_state);
+                let drones = crate::systems::factory_activity::DroneActivityBuffers {
+                    positions: get_slice_mut(&self.layout.drones.positions),
+                    states: get_slice_mut(&self.layout.drones.states),
+                    charging: get_slice_mut(&self.layout.drones.charging),
+                    owner_factory_index: get_slice_mut(&self.layout.drones.owner_factory_index),
+                    target_factory_index: get_slice_mut(&self.layout.drones.target_factory_index),
+                };
+
+                crate::systems::factory_activity::sys_factory_activity(
+                    factory_orientations,
+                    factory_activity,
+                    factory_positions,
+                    refinery_state,
+                    &self.snapshot.factories,
+                    &drones,
+                    dt,
+                );
+            }
         }
 
         self.sync_data_to_snapshot();
 
+        // Market (price drift + standing sell orders)
+        self.market_tick += dt;
+        if self.market_tick >= market::MARKET_TICK_INTERVAL {
+            self.market_tick -= market::MARKET_TICK_INTERVAL;
+            market::sys_market(&mut self.snapshot.market, &mut self.snapshot.resources, &mut self.rng);
+        }
+
+        research::sys_research(&mut self.snapshot.research, &self.snapshot.modules, dt);
+
         // Logistics System
         if let Some(logistics_queues) = &mut self.snapshot.logistics_queues {
             self.logistics_tick += dt;
@@ -1162,7 +2095,7 @@ impl GameState {
                 self.logistics_tick -= 2.0;
             }
 
-            crate::systems::logistics::sys_logistics(
+            let delivered = crate::systems::logistics::sys_logistics(
                 logistics_queues,
                 &mut self.snapshot.factories,
                 &mut self.snapshot.resources,
@@ -1171,12 +2104,29 @@ impl GameState {
                 self.game_time,
                 run_scheduler,
             );
+            for (resource, amount) in delivered {
+                *warehouse_deliveries.entry(resource).or_insert(0.0) += amount;
+            }
         }
 
+        contracts::sys_contracts(
+            &mut self.snapshot.contracts,
+            &mut self.contract_events,
+            &warehouse_deliveries,
+            &mut self.snapshot.resources,
+            &mut self.snapshot.prestige,
+            &mut self.rng,
+            self.game_time,
+        );
+
         for i in 0..self.snapshot.factories.len() {
             self.sync_factory_to_buffer(i);
         }
 
+        if let Err(err) = self.sync_haulers() {
+            // A tick cannot fail; the haulers that fit are drawn and the rest wait for space.
+            crate::parity_debug::warn(&format!("hauler buffer full: {err}"));
+        }
         self.sync_globals_to_buffer();
 
         TickResult {
@@ -1199,11 +2149,15 @@ impl GameState {
             SimulationCommand::SetSettings(settings) => {
                 self.snapshot.settings = settings;
             }
-            SimulationCommand::BuyModule { module_type, factory_id: _ } => {
-                self.handle_buy_module(&module_type)?;
+            SimulationCommand::BuyModule {
+                module_type,
+                factory_id: _,
+                cost_variant,
+            } => {
+                self.handle_buy_module(&module_type, cost_variant.as_deref())?;
             }
             SimulationCommand::DoPrestige => {
-                self.handle_prestige()?;
+                self.prestige()?;
             }
             SimulationCommand::PurchaseFactoryUpgrade {
                 factory_id,
@@ -1215,6 +2169,14 @@ impl GameState {
             SimulationCommand::AssignHauler { factory_id, count } => {
                 self.handle_assign_hauler(&factory_id, count)?;
             }
+            SimulationCommand::SetHaulerConfig {
+                factory_id,
+                resource_filters,
+                mode,
+                priority,
+            } => {
+                self.handle_set_hauler_config(&factory_id, resource_filters, mode, priority);
+            }
             SimulationCommand::ImportPayload { snapshot_json } => {
                 self.load_snapshot_str(&snapshot_json)?;
             }
@@ -1224,7 +2186,127 @@ impl GameState {
             SimulationCommand::RecycleAsteroid { asteroid_id } => {
                 self.handle_recycle_asteroid(&asteroid_id)?;
             }
+            SimulationCommand::BuildFactory => {
+                self.handle_build_factory()?;
+            }
+            SimulationCommand::DecommissionFactory { factory_id } => {
+                self.handle_decommission_factory(&factory_id)?;
+            }
+            SimulationCommand::PurchaseSpecTech { tech_id } => {
+                self.handle_purchase_spec_tech(&tech_id)?;
+            }
+            SimulationCommand::InvestPrestige { investment_id } => {
+                self.handle_invest_prestige(&investment_id)?;
+            }
+            SimulationCommand::EquipDrone {
+                drone_id,
+                slot,
+                tier,
+            } => {
+                self.handle_equip_drone(&drone_id, &slot, tier);
+            }
+            SimulationCommand::UnequipDrone { drone_id, slot } => {
+                self.handle_equip_drone(&drone_id, &slot, 0);
+            }
+            SimulationCommand::OrderDrone { drone_id, order } => {
+                self.handle_order_drone(&drone_id, order);
+            }
+            SimulationCommand::SetRefineryOverdrive { factory_id, enabled } => {
+                if let Some(&factory_index) = self.factory_id_to_index.get(&factory_id) {
+                    if let Some(factory) = self.snapshot.factories.get_mut(factory_index) {
+                        factory.overdrive = enabled;
+                    }
+                }
+            }
+            SimulationCommand::SetRefineRecipe {
+                factory_id,
+                slot,
+                recipe,
+            } => {
+                self.handle_set_refine_recipe(&factory_id, slot as usize, &recipe);
+            }
+            SimulationCommand::Sell {
+                resource,
+                amount,
+                factory_id,
+            } => {
+                self.handle_sell(&resource, amount, factory_id.as_deref());
+            }
+            SimulationCommand::Buy { resource, amount } => {
+                self.handle_buy(&resource, amount);
+            }
+            SimulationCommand::SetAutoSell { resource, reserve } => match reserve {
+                Some(reserve)
+                    if market::market_good(&resource).is_some()
+                        && market::warehouse_stock_mut(&mut self.snapshot.resources, &resource)
+                            .is_some() =>
+                {
+                    self.snapshot.market.auto_sell.insert(resource, reserve.max(0.0));
+                }
+                Some(_) => {}
+                None => {
+                    self.snapshot.market.auto_sell.remove(&resource);
+                }
+            },
+            SimulationCommand::AcceptContract { contract_id } => {
+                contracts::accept_contract(&mut self.snapshot.contracts, &contract_id, self.game_time);
+            }
+            SimulationCommand::AbandonContract { contract_id } => {
+                let books = &mut self.snapshot.contracts;
+                if let Some(position) = books.active.iter().position(|c| c.id == contract_id) {
+                    let contract = books.active.remove(position);
+                    contracts::push_contract_event(
+                        &mut self.contract_events,
+                        &contract,
+                        "abandoned",
+                        self.game_time,
+                    );
+                } else {
+                    books.offers.retain(|offer| offer.id != contract_id);
+                }
+            }
+            SimulationCommand::StartResearch { node_id } => {
+                research::start_research(&mut self.snapshot.research, &node_id);
+            }
+            SimulationCommand::FundResearch { crystals } => {
+                let spent = crystals.max(0.0).min(self.snapshot.resources.crystals);
+                self.snapshot.resources.crystals -= spent;
+                self.snapshot.research.points += spent * research::RESEARCH_POINTS_PER_CRYSTAL;
+            }
+            SimulationCommand::ReassignDrone {
+                drone_id,
+                factory_id,
+            } => {
+                if let (Some(&drone_index), Some(&factory_index)) = (
+                    self.drone_id_to_index.get(&drone_id),
+                    self.factory_id_to_index.get(&factory_id),
+                ) {
+                    self.reassign_drone(drone_index, factory_index);
+                }
+            }
+            SimulationCommand::UnassignDrone { drone_id } => {
+                self.snapshot.assigned_drones.remove(&drone_id);
+            }
+            SimulationCommand::RebalanceDrones {
+                from_factory_id,
+                to_factory_id,
+                count,
+            } => {
+                self.handle_rebalance_drones(&from_factory_id, &to_factory_id, count as usize);
+            }
+            SimulationCommand::ClearDroneOrder { drone_id } => {
+                if let Some(order) = self.snapshot.drone_orders.remove(&drone_id) {
+                    orders::push_order_event(
+                        &mut self.order_events,
+                        &drone_id,
+                        &order,
+                        "cleared",
+                        self.game_time,
+                    );
+                }
+            }
         }
+        self.sync_haulers()?;
         self.sync_globals_to_buffer();
         Ok(())
     }
@@ -1259,6 +2341,7 @@ impl GameState {
                 self.snapshot.prestige.cores,
                 self.snapshot.prestige_investments.as_ref(),
                 self.snapshot.spec_techs.as_ref(),
+                &self.snapshot.research.completed,
             );
             let ore_before = self.snapshot.resources.ore;
             let bars_before = self.snapshot.resources.bars;
@@ -1280,6 +2363,19 @@ impl GameState {
             let _ore_delta = ore_before - self.snapshot.resources.ore;
             elapsed += dt;
             steps += 1;
+
+            research::sys_research(&mut self.snapshot.research, &self.snapshot.modules, dt);
+
+            // Nothing is hauled while away, but deadlines still pass and offers still post.
+            contracts::sys_contracts(
+                &mut self.snapshot.contracts,
+                &mut self.contract_events,
+                &BTreeMap::new(),
+                &mut self.snapshot.resources,
+                &mut self.snapshot.prestige,
+                &mut self.rng,
+                self.game_time + elapsed,
+            );
         }
 
         self.game_time += elapsed;
@@ -1323,6 +2419,18 @@ impl GameState {
             .expect("factory resources buffer should be valid")
     }
 
+    /// Accessor for hauler positions buffer (for WASM interop).
+    pub fn get_hauler_positions_mut(&mut self) -> &mut [f32] {
+        self.layout.haulers.positions.as_f32_slice_mut(&mut self.data)
+            .expect("hauler positions buffer should be valid")
+    }
+
+    /// Accessor for hauler cargo buffer (for WASM interop).
+    pub fn get_hauler_cargo_mut(&mut self) -> &mut [f32] {
+        self.layout.haulers.cargo.as_f32_slice_mut(&mut self.data)
+            .expect("hauler cargo buffer should be valid")
+    }
+
     /// Accessor for factory energy buffer (for WASM interop).
     pub fn get_factory_energy_mut(&mut self) -> &mut [f32] {
         self.layout.factories.energy.as_f32_slice_mut(&mut self.data)
@@ -1360,7 +2468,69 @@ impl GameState {
         (bars / 1000.0).powf(0.6).floor() as i32
     }
 
-    fn handle_buy_module(&mut self, module_type: &str) -> Result<(), SimulationError> {
+    fn handle_sell(&mut self, resource: &str, amount: f32, factory_id: Option<&str>) {
+        let Some(good) = market::market_good(resource) else {
+            return;
+        };
+        let factory_idx = match factory_id {
+            Some(id) => match self.factory_id_to_index.get(id) {
+                Some(&idx) if idx < self.snapshot.factories.len() => Some(idx),
+                _ => return,
+            },
+            None => None,
+        };
+        let stock = match factory_idx {
+            Some(idx) => factory_resource_mut(&mut self.snapshot.factories[idx].resources, resource),
+            None => market::warehouse_stock_mut(&mut self.snapshot.resources, resource),
+        };
+        let Some(stock) = stock else {
+            return;
+        };
+        let sold = amount.min(*stock);
+        if sold <= 0.0 {
+            return;
+        }
+        *stock -= sold;
+        self.snapshot.resources.credits += market::sell(&mut self.snapshot.market, good, sold);
+        if let Some(idx) = factory_idx {
+            self.sync_factory_to_buffer(idx);
+        }
+    }
+
+    fn handle_buy(&mut self, resource: &str, amount: f32) {
+        let Some(good) = market::market_good(resource) else {
+            return;
+        };
+        // Bought goods land in the warehouse, so only goods it can hold are for sale, and only
+        // as much as fits.
+        let Some(&mut stock) = market::warehouse_stock_mut(&mut self.snapshot.resources, resource)
+        else {
+            return;
+        };
+        let modifiers = get_resource_modifiers(
+            &self.snapshot.resources,
+            self.snapshot.prestige.cores,
+            self.snapshot.prestige_investments.as_ref(),
+            self.snapshot.spec_techs.as_ref(),
+            &self.snapshot.research.completed,
+        );
+        let space = compute_warehouse_capacity(&self.snapshot.modules, &modifiers) - stock;
+        let amount = amount.min(space.max(0.0));
+        let credits = self.snapshot.resources.credits;
+        let Some(cost) = market::buy(&mut self.snapshot.market, good, amount, credits) else {
+            return;
+        };
+        self.snapshot.resources.credits -= cost;
+        if let Some(stock) = market::warehouse_stock_mut(&mut self.snapshot.resources, resource) {
+            *stock += amount;
+        }
+    }
+
+    fn handle_buy_module(
+        &mut self,
+        module_type: &str,
+        cost_variant: Option<&str>,
+    ) -> Result<(), SimulationError> {
         let (current_level, base_cost) = match module_type {
             "droneBay" => (self.snapshot.modules.drone_bay, 4.0),
             "refinery" => (self.snapshot.modules.refinery, 8.0),
@@ -1370,23 +2540,38 @@ impl GameState {
             "haulerDepot" => (self.snapshot.modules.hauler_depot, 60.0),
             "logisticsHub" => (self.snapshot.modules.logistics_hub, 80.0),
             "routingProtocol" => (self.snapshot.modules.routing_protocol, 100.0),
+            "lab" => (self.snapshot.modules.lab, 30.0),
+            "dataCore" => (self.snapshot.modules.data_core, 120.0),
             _ => return Ok(()), // Unknown module, ignore
         };
+        if !research::module_unlocked(module_type, &self.snapshot.research.completed) {
+            return Ok(()); // Not researched yet
+        }
 
         let cost = Self::calculate_exponential_cost(
             base_cost,
             crate::constants::UPGRADE_GROWTH,
             current_level,
         );
-        if self.snapshot.resources.bars < cost {
-            return Ok(()); // Not enough bars
+        let wallet = match cost_variant {
+            None => &mut self.snapshot.resources.bars,
+            Some("credits") => &mut self.snapshot.resources.credits,
+            Some(_) => return Ok(()), // Unknown cost variant
+        };
+        let cost = if cost_variant.is_some() {
+            cost * crate::constants::CREDITS_PER_BAR_COST
+        } else {
+            cost
+        };
+        if *wallet < cost {
+            return Ok(()); // Can't afford it
         }
 
-        self.snapshot.resources.bars -= cost;
+        *wallet -= cost;
         match module_type {
             "droneBay" => {
                 self.snapshot.modules.drone_bay += 1;
-                self.rebuild_state()?;
+                self.sync_drone_slots_to_bay()?;
             }
             "refinery" => self.snapshot.modules.refinery += 1,
             "storage" => self.snapshot.modules.storage += 1,
@@ -1395,35 +2580,40 @@ impl GameState {
             "haulerDepot" => self.snapshot.modules.hauler_depot += 1,
             "logisticsHub" => self.snapshot.modules.logistics_hub += 1,
             "routingProtocol" => self.snapshot.modules.routing_protocol += 1,
+            "lab" => self.snapshot.modules.lab += 1,
+            "dataCore" => self.snapshot.modules.data_core += 1,
             _ => {}
         }
         Ok(())
     }
 
     fn handle_spawn_drone(&mut self, factory_id: &str) -> Result<(), SimulationError> {
-        // 1. Increase capacity if needed (or assume command implies capacity increase/force)
-        // To be safe, we increment drone_bay.
+        // The spawned drone occupies a new drone bay slot.
         self.snapshot.modules.drone_bay += 1;
+        self.add_drone(Some(factory_id))?;
+        self.sync_drone_slots_to_bay()
 
-        // 2. Assign owner in snapshot
-        let drone_id = format!("drone-spawned-{}", self.rng.next_u32());
-        self.snapshot.drone_owners.insert(drone_id, Some(factory_id.to_string()));
-
-        // 3. Rebuild state
-        self.rebuild_state()?;
-        Ok(())
     }
 
-    fn handle_prestige(&mut self) -> Result<(), SimulationError> {
-        let threshold = crate::constants::PRESTIGE_THRESHOLD;
-
-        if self.snapshot.resources.bars < threshold {
-            return Ok(());
+    /// Converts bars into prestige cores and restarts the run: resources, modules, spec tech,
+    /// factories, drones, the asteroid field, logistics and the clock are reset, while cores,
+    /// prestige investments and lifetime stats carry over. Returns `None` below the threshold.
+    pub fn prestige(&mut self) -> Result<Option<PrestigeSummary>, SimulationError> {
+        let bars = self.snapshot.resources.bars;
+        if bars < crate::constants::PRESTIGE_THRESHOLD {
+            return Ok(None);
         }
 
-        let gain = Self::compute_prestige_gain(self.snapshot.resources.bars);
-
+        let gain = Self::compute_prestige_gain(bars);
         self.snapshot.prestige.cores += gain;
+        let run_time = self.game_time;
+
+        let stats = self.snapshot.lifetime_stats.get_or_insert_with(Default::default);
+        stats.prestiges += 1;
+        stats.cores_earned += gain;
+        stats.bars_converted += bars;
+        stats.game_time += run_time;
+        let lifetime_prestiges = stats.prestiges;
 
         // Reset resources to initial state
         self.snapshot.resources = Resources {
@@ -1447,17 +2637,106 @@ impl GameState {
             hauler_depot: 0,
             logistics_hub: 0,
             routing_protocol: 0,
+            lab: 0,
+            data_core: 0,
         };
 
-        // Clear drone flights and owners
+        // Spec tech is per-run; prestige investments are not.
+        self.snapshot.spec_techs = Some(serde_json::json!({
+            "oreMagnet": 0,
+            "crystalResonance": 0,
+            "biotechFarming": 0,
+            "cryoPreservation": 0,
+        }));
+        self.snapshot.spec_tech_spent = Some(serde_json::json!({
+            "metals": 0,
+            "crystals": 0,
+            "organics": 0,
+            "ice": 0,
+        }));
+
+        // Clear drone flights and owners, then drop every drone
+        let drones_reset = self.drone_index_to_id.len();
         self.snapshot.drone_flights.clear();
         self.snapshot.drone_owners.clear();
+        for drone_id in self.drone_index_to_id.clone() {
+            self.remove_drone(&drone_id);
+        }
 
-        // Note: Factory reset is handled by TypeScript layer since
-        // factory creation involves position randomization
+        // Rebuild the starter factory under the id of the run's first factory
+        let factory_ids: Vec<String> = self.snapshot.factories.iter().map(|f| f.id.clone()).collect();
+        let factories_reset = factory_ids.len();
+        for factory_id in &factory_ids {
+            self.remove_factory(factory_id);
+        }
+        let starter_id = factory_ids.first().cloned().unwrap_or_else(|| "factory-0".to_string());
+        self.add_factory(crate::factories::create_starter_factory(starter_id.clone()))?;
+        self.snapshot.selected_factory_id = Some(starter_id.clone());
+        self.snapshot.logistics_queues = Some(Default::default());
+        self.logistics_tick = 0.0;
+        self.balance_tick = 0.0;
+        self.market_tick = 0.0;
+        self.snapshot.contracts = Default::default();
+        self.snapshot.research = Default::default();
+        self.snapshot.market = Default::default();
+
+        self.game_time = 0.0;
+        self.snapshot.game_time = 0.0;
+
+        // Reseed from the current stream so the new field is deterministic per save.
+        let seed = self.rng.next_u32().max(1);
+        self.snapshot.rng_seed = Some(seed);
+        self.rng = Mulberry32::new(seed);
+        let asteroids_generated = self.regenerate_asteroid_field()?;
+
+        // The rebuilt starter drone belongs to the rebuilt starter factory.
+        self.add_drone(Some(&starter_id))?;
+        self.sync_drone_slots_to_bay()?;
+        self.sync_haulers()?;
+        self.sync_globals_to_buffer();
 
-        self.rebuild_state()?;
-        Ok(())
+        Ok(Some(PrestigeSummary {
+            bars_converted: bars,
+            cores_gained: gain,
+            total_cores: self.snapshot.prestige.cores,
+            factories_reset,
+            drones_reset,
+            asteroids_generated,
+            run_time,
+            lifetime_prestiges,
+        }))
+    }
+
+    /// Reseeds the RNG and builds the initial asteroid field in Rust, replacing any existing one.
+    /// Leaves the RNG where `from_snapshot` would after burning draws for the same field, so a
+    /// game started here continues identically to one whose field came from TS.
+    pub fn generate_asteroid_field(&mut self, seed: u32) -> Result<usize, SimulationError> {
+        let seed = seed.max(1);
+        self.rng = Mulberry32::new(seed);
+        self.snapshot.rng_seed = Some(seed);
+        self.snapshot.asteroid_hazards.clear();
+        let count = self.regenerate_asteroid_field()?;
+        self.sync_globals_to_buffer();
+        Ok(count)
+    }
+
+    /// Replaces the asteroid field with a freshly generated one sized by the spawn bonus.
+    fn regenerate_asteroid_field(&mut self) -> Result<usize, SimulationError> {
+        for asteroid_id in self.asteroid_index_to_id.clone() {
+            self.remove_asteroid(&asteroid_id);
+        }
+
+        let sink_bonuses = crate::sinks::get_sink_bonuses(&self.snapshot);
+        let scanner_level = self.snapshot.modules.scanner;
+        let target = (ASTEROID_FIELD_TARGET as f32 * sink_bonuses.asteroid_spawn_multiplier)
+            .round()
+            .max(0.0) as usize;
+        for _ in 0..target {
+            let asteroid = generate_asteroid(&mut self.rng, &sink_bonuses, scanner_level);
+            let asteroid_id = self.next_entity_id("asteroid");
+            self.add_asteroid(asteroid.to_json(&asteroid_id))?;
+        }
+        Ok(target)
     }
 
     fn handle_factory_upgrade(
@@ -1482,8 +2761,15 @@ impl GameState {
             "storage" => factory.upgrades.storage,
             "energy" => factory.upgrades.energy,
             "solar" => factory.upgrades.solar,
+            "unload" => factory.upgrades.unload,
+            "cooler" => factory.upgrades.cooler,
             _ => return Ok(()), // Unknown upgrade
         };
+        if let Some(variant) = cost_variant {
+            if !research::upgrade_variant_unlocked(upgrade_type, variant, &self.snapshot.research.completed) {
+                return Ok(()); // Variant not researched yet
+            }
+        }
 
         let growth = crate::constants::FACTORY_UPGRADE_GROWTH;
         let mut cost_entries: Vec<(&str, f32)> = Vec::new();
@@ -1527,6 +2813,29 @@ impl GameState {
                     Self::calculate_exponential_cost(10.0, growth, current_level),
                 ));
             }
+            Some("ice") if upgrade_type == "cooler" => {
+                cost_entries.push((
+                    "ice",
+                    Self::calculate_exponential_cost(40.0, growth, current_level),
+                ));
+                cost_entries.push((
+                    "metals",
+                    Self::calculate_exponential_cost(10.0, growth, current_level),
+                ));
+            }
+            Some("alloys") if upgrade_type == "storage" || upgrade_type == "docking" => cost_entries.push((
+                "alloys",
+                Self::calculate_exponential_cost(6.0, growth, current_level),
+            )),
+            Some("coolant") if upgrade_type == "cooler" => cost_entries.push((
+                "coolant",
+                Self::calculate_exponential_cost(30.0, growth, current_level),
+            )),
+            Some("credits") => cost_entries.push((
+                "credits",
+                Self::calculate_exponential_cost(13.0, growth, current_level)
+                    * crate::constants::CREDITS_PER_BAR_COST,
+            )),
             Some(_) => return Ok(()), // Unknown variant for this upgrade
             _ => cost_entries.push((
                 "bars",
@@ -1542,6 +2851,10 @@ impl GameState {
                 "organics" => factory.resources.organics,
                 "ice" => factory.resources.ice,
                 "crystals" => factory.resources.crystals,
+                "alloys" => factory.resources.alloys,
+                "coolant" => factory.resources.coolant,
+                // Credits are a global currency, paid from the warehouse wallet.
+                "credits" => self.snapshot.resources.credits,
                 _ => 0.0,
             };
             if available < *cost {
@@ -1558,6 +2871,9 @@ impl GameState {
                 "organics" => factory.resources.organics -= cost,
                 "ice" => factory.resources.ice -= cost,
                 "crystals" => factory.resources.crystals -= cost,
+                "alloys" => factory.resources.alloys -= cost,
+                "coolant" => factory.resources.coolant -= cost,
+                "credits" => self.snapshot.resources.credits -= cost,
                 _ => {}
             }
         }
@@ -1585,6 +2901,12 @@ impl GameState {
                 factory.upgrades.solar += 1;
                 factory.energy_capacity += crate::constants::FACTORY_SOLAR_MAX_ENERGY_PER_LEVEL;
             }
+            "unload" => {
+                factory.upgrades.unload += 1;
+            }
+            "cooler" => {
+                factory.upgrades.cooler += 1;
+            }
             _ => {}
         }
 
@@ -1597,6 +2919,49 @@ impl GameState {
         Ok(())
     }
 
+    fn handle_set_refine_recipe(&mut self, factory_id: &str, slot: usize, recipe_id: &str) {
+        let Some(&factory_idx) = self.factory_id_to_index.get(factory_id) else {
+            return;
+        };
+        let Some(factory) = self.snapshot.factories.get(factory_idx) else {
+            return;
+        };
+        let Some(recipe_idx) = recipes::recipe_index(recipe_id) else {
+            return;
+        };
+        if slot >= (factory.refine_slots.max(0) as usize).min(MAX_REFINE_SLOTS) {
+            return;
+        }
+
+        let recipe_offset = self.layout.factories.refine_recipes.offset_bytes / 4
+            + factory_idx * MAX_REFINE_SLOTS
+            + slot;
+        let current = recipes::recipe_at(f32::from_bits(self.data[recipe_offset]));
+        if current.id == recipe_id {
+            return;
+        }
+
+        let slot_offset = self.layout.factories.refinery_state.offset_bytes / 4
+            + factory_idx * MAX_REFINE_SLOTS * 4
+            + slot * 4;
+        if f32::from_bits(self.data[slot_offset]) > 0.5 {
+            let amount = f32::from_bits(self.data[slot_offset + 1]);
+            let progress = f32::from_bits(self.data[slot_offset + 2]).clamp(0.0, 1.0);
+            let remaining = amount * (1.0 - progress);
+            let resources = &mut self.snapshot.factories[factory_idx].resources;
+            for &(resource, ratio) in current.inputs {
+                if let Some(stock) = factory_resource_mut(resources, resource) {
+                    *stock += remaining * ratio;
+                }
+            }
+            for value in &mut self.data[slot_offset..slot_offset + 4] {
+                *value = 0.0f32.to_bits();
+            }
+            self.sync_factory_to_buffer(factory_idx);
+        }
+        self.data[recipe_offset] = (recipe_idx as f32).to_bits();
+    }
+
     fn handle_assign_hauler(&mut self, factory_id: &str, count: i32) -> Result<(), SimulationError> {
         let factory_idx = match self.factory_id_to_index.get(factory_id) {
             Some(&idx) => idx,
@@ -1632,6 +2997,40 @@ impl GameState {
         Ok(())
     }
 
+    fn handle_set_hauler_config(
+        &mut self,
+        factory_id: &str,
+        resource_filters: Option<Vec<String>>,
+        mode: Option<String>,
+        priority: Option<i32>,
+    ) {
+        if mode.as_deref().is_some_and(|mode| !HAULER_MODES.contains(&mode)) {
+            return;
+        }
+        let Some(factory) = self.snapshot.factories.iter_mut().find(|f| f.id == factory_id) else {
+            return;
+        };
+
+        let config = factory.hauler_config.get_or_insert_with(|| HaulerConfig {
+            capacity: LOGISTICS_HAULER_CAPACITY,
+            speed: LOGISTICS_HAULER_SPEED,
+            pickup_overhead: LOGISTICS_PICKUP_OVERHEAD,
+            dropoff_overhead: LOGISTICS_DROPOFF_OVERHEAD,
+            resource_filters: Vec::new(),
+            mode: "auto".to_string(),
+            priority: 5,
+        });
+        if let Some(filters) = resource_filters {
+            config.resource_filters = filters;
+        }
+        if let Some(mode) = mode {
+            config.mode = mode;
+        }
+        if let Some(priority) = priority {
+            config.priority = priority.clamp(0, HAULER_PRIORITY_MAX);
+        }
+    }
+
     fn handle_recycle_asteroid(&mut self, asteroid_id: &str) -> Result<(), SimulationError> {
         if let Some(&idx) = self.asteroid_id_to_index.get(asteroid_id) {
             // Set ore remaining to 0
@@ -1658,50 +3057,579 @@ impl GameState {
         Ok(())
     }
 
-    fn sync_globals_to_buffer(&mut self) {
-        let offset = self.layout.globals.resources.offset_bytes / 4;
-        let r = &self.snapshot.resources;
-        self.data[offset] = r.ore.to_bits();
-        self.data[offset + 1] = r.ice.to_bits();
-        self.data[offset + 2] = r.metals.to_bits();
-        self.data[offset + 3] = r.crystals.to_bits();
-        self.data[offset + 4] = r.organics.to_bits();
-        self.data[offset + 5] = r.bars.to_bits();
-        self.data[offset + 6] = r.energy.to_bits();
-        self.data[offset + 7] = r.credits.to_bits();
-    }
-
-    fn sync_factory_to_buffer(&mut self, factory_idx: usize) {
-        if factory_idx >= self.snapshot.factories.len() {
+    fn add_spec_tech_spent(&mut self, resource: &str, amount: f32) {
+        if amount <= 0.0 {
             return;
         }
+        let spent = self
+            .snapshot
+            .spec_tech_spent
+            .get_or_insert_with(|| Value::Object(serde_json::Map::new()));
+        if let Some(map) = spent.as_object_mut() {
+            let current = map.get(resource).and_then(|v| v.as_f64()).unwrap_or(0.0);
+            map.insert(resource.to_string(), Value::from(current + amount as f64));
+        }
+    }
 
-        let factory = &self.snapshot.factories[factory_idx];
+    fn handle_purchase_spec_tech(&mut self, tech_id: &str) -> Result<(), SimulationError> {
+        let Some(definition) = crate::sinks::spec_tech_definition(tech_id) else {
+            return Ok(());
+        };
+        let level = crate::sinks::json_level(&self.snapshot.spec_techs, tech_id) as i64;
+        if level >= definition.max_level
+            || !crate::sinks::spec_tech_unlocked(&self.snapshot, definition)
+        {
+            return Ok(());
+        }
 
-        // Sync resources
-        let offset = self.layout.factories.resources.offset_bytes / 4 + factory_idx * 7;
-        self.data[offset] = factory.resources.ore.to_bits();
-        self.data[offset + 1] = factory.resources.ice.to_bits();
-        self.data[offset + 2] = factory.resources.metals.to_bits();
-        self.data[offset + 3] = factory.resources.crystals.to_bits();
-        self.data[offset + 4] = factory.resources.organics.to_bits();
-        self.data[offset + 5] = factory.resources.bars.to_bits();
-        self.data[offset + 6] = factory.resources.credits.to_bits();
+        let cost = crate::sinks::spec_tech_cost(definition, level);
+        let Some(available) = global_resource_mut(&mut self.snapshot.resources, definition.resource)
+        else {
+            return Ok(());
+        };
+        if *available < cost {
+            return Ok(());
+        }
+        *available -= cost;
 
-        // Sync energy
-        let offset = self.layout.factories.energy.offset_bytes / 4 + factory_idx;
-        self.data[offset] = factory.energy.to_bits();
+        set_json_level(&mut self.snapshot.spec_techs, tech_id, level + 1);
+        self.add_spec_tech_spent(definition.resource, cost);
+        Ok(())
+    }
+
+    /// Sets a drone's slot to `tier`, charging for any non-zero tier; 0 unequips.
+    fn handle_equip_drone(&mut self, drone_id: &str, slot: &str, tier: u32) {
+        let Some(definition) = crate::loadouts::equipment_definition(slot) else {
+            return;
+        };
+        if !self.drone_id_to_index.contains_key(drone_id) || tier > definition.max_tier {
+            return;
+        }
+        let current = self
+            .snapshot
+            .drone_loadouts
+            .get(drone_id)
+            .and_then(|loadout| crate::loadouts::slot_tier(loadout, slot))
+            .unwrap_or(0);
+        if current == tier {
+            return;
+        }
+
+        if tier > 0 {
+            let cost = crate::loadouts::equipment_cost(definition, tier);
+            let Some(available) =
+                global_resource_mut(&mut self.snapshot.resources, definition.resource)
+            else {
+                return;
+            };
+            if *available < cost {
+                return;
+            }
+            *available -= cost;
+        }
+
+        let loadout = self
+            .snapshot
+            .drone_loadouts
+            .entry(drone_id.to_string())
+            .or_default();
+        if let Some(slot_tier) = crate::loadouts::slot_tier_mut(loadout, slot) {
+            *slot_tier = tier;
+        }
+        if *loadout == DroneLoadout::default() {
+            self.snapshot.drone_loadouts.remove(drone_id);
+        }
+        self.seed_drone_stats();
+    }
+
+    /// Records a manual order and breaks off the trip it overrides.
+    /// Orders naming an unknown drone, asteroid or factory are ignored.
+    fn handle_order_drone(&mut self, drone_id: &str, order: DroneOrder) {
+        let Some(&index) = self.drone_id_to_index.get(drone_id) else {
+            return;
+        };
+        let recall_index = match &order {
+            DroneOrder::GoTo { asteroid_id, .. } | DroneOrder::Pin { asteroid_id } => {
+                if !self.asteroid_id_to_index.contains_key(asteroid_id) {
+                    return;
+                }
+                None
+            }
+            DroneOrder::Recall { factory_id } => match self.factory_id_to_index.get(factory_id) {
+                Some(&idx) => Some(idx as f32),
+                None => return,
+            },
+            DroneOrder::Hold => None,
+        };
+
+        if let Some(previous) = self
+            .snapshot
+            .drone_orders
+            .insert(drone_id.to_string(), order.clone())
+        {
+            orders::push_order_event(
+                &mut self.order_events,
+                drone_id,
+                &previous,
+                "replaced",
+                self.game_time,
+            );
+        }
+
+        let read = |section: &crate::buffers::BufferSection, data: &[u32]| {
+            section
+                .as_f32_slice(data)
+                .and_then(|values| values.get(index).copied())
+                .unwrap_or(-1.0)
+        };
+        let state = read(&self.layout.drones.states, &self.data);
+        match order {
+            // Outbound drones turn around at once; loaded or mining drones finish their delivery first.
+            DroneOrder::GoTo { .. } | DroneOrder::Pin { .. } => {
+                if state == DRONE_STATE_TO_ASTEROID {
+                    self.abort_drone_trip(index, DRONE_STATE_IDLE);
+                }
+            }
+            // A drone already heading to the ordered factory keeps going; the recall completes
+            // once it docks there.
+            DroneOrder::Recall { .. } => {
+                let heading = read(&self.layout.drones.target_factory_index, &self.data);
+                let heading_there = state == DRONE_STATE_RETURNING && Some(heading) == recall_index;
+                if !heading_there && (state == DRONE_STATE_TO_ASTEROID || state == DRONE_STATE_RETURNING) {
+                    self.abort_drone_trip(index, DRONE_STATE_RETURNING);
+                }
+            }
+            DroneOrder::Hold => {}
+        }
+    }
+
+    /// Cancels a drone's flight and docking slot where it stands so the AI re-plans it next tick.
+    fn abort_drone_trip(&mut self, index: usize, next_state: f32) {
+        let Some(drone_id) = self.drone_index_to_id.get(index).cloned() else {
+            return;
+        };
+        self.snapshot.drone_flights.retain(|flight| flight.drone_id != drone_id);
+        for factory in self.snapshot.factories.iter_mut() {
+            factory.queued_drones.retain(|id| *id != drone_id);
+        }
+        let drones = &self.layout.drones;
+        for (section, value) in [
+            (&drones.states, next_state),
+            (&drones.target_asteroid_index, -1.0),
+            (&drones.target_region_index, -1.0),
+            (&drones.target_factory_index, -1.0),
+        ] {
+            if let Some(slot) = section
+                .as_f32_slice_mut(&mut self.data)
+                .and_then(|values| values.get_mut(index))
+            {
+                *slot = value;
+            }
+        }
+        if let Some(velocity) = drones
+            .velocities
+            .as_f32_slice_mut(&mut self.data)
+            .and_then(|values| values.get_mut(index * 3..index * 3 + 3))
+        {
+            velocity.fill(0.0);
+        }
+    }
+
+    /// Hands a drone to another factory and locks it there. A drone flying home or waiting on
+    /// another factory's docks drops that trip and re-docks at the new owner through the normal
+    /// docking queue on the next AI pass.
+    fn reassign_drone(&mut self, drone_index: usize, factory_index: usize) {
+        let Some(drone_id) = self.drone_index_to_id.get(drone_index).cloned() else {
+            return;
+        };
+        let Some(factory) = self.snapshot.factories.get(factory_index) else {
+            return;
+        };
+        let factory_id = factory.id.clone();
+
+        self.snapshot
+            .drone_owners
+            .insert(drone_id.clone(), Some(factory_id.clone()));
+        self.snapshot.assigned_drones.insert(drone_id.clone());
+        let drones = &self.layout.drones;
+        let state = f32::from_bits(self.data[drones.states.offset_bytes / 4 + drone_index]);
+        self.data[drones.owner_factory_index.offset_bytes / 4 + drone_index] =
+            (factory_index as f32).to_bits();
+        let target_offset = drones.target_factory_index.offset_bytes / 4 + drone_index;
+        let heading = f32::from_bits(self.data[target_offset]);
+
+        if let Some(flight) = self
+            .snapshot
+            .drone_flights
+            .iter_mut()
+            .find(|flight| flight.drone_id == drone_id)
+        {
+            if flight.owner_factory_id.is_some() {
+                flight.owner_factory_id = Some(factory_id.clone());
+            }
+        }
+
+        if state != DRONE_STATE_RETURNING || heading == factory_index as f32 {
+            return;
+        }
+        self.snapshot.drone_flights.retain(|flight| flight.drone_id != drone_id);
+        for factory in self.snapshot.factories.iter_mut() {
+            factory.queued_drones.retain(|id| *id != drone_id);
+        }
+        self.data[target_offset] = (-1.0f32).to_bits();
+    }
+
+    fn handle_rebalance_drones(&mut self, from_factory_id: &str, to_factory_id: &str, count: usize) {
+        let (Some(&from), Some(&to)) = (
+            self.factory_id_to_index.get(from_factory_id),
+            self.factory_id_to_index.get(to_factory_id),
+        ) else {
+            return;
+        };
+        if from == to {
+            return;
+        }
+        let (Some(states), Some(owners)) = (
+            self.layout.drones.states.as_f32_slice(&self.data),
+            self.layout.drones.owner_factory_index.as_f32_slice(&self.data),
+        ) else {
+            return;
+        };
+        for drone_index in balance::pick_transferable_drones(states, owners, from, count) {
+            self.reassign_drone(drone_index, to);
+        }
+    }
+
+    fn handle_invest_prestige(&mut self, investment_id: &str) -> Result<(), SimulationError> {
+        let Some(definition) = crate::sinks::prestige_investment_definition(investment_id) else {
+            return Ok(());
+        };
+        let level = crate::sinks::json_level(&self.snapshot.prestige_investments, investment_id) as i64;
+        if level >= definition.max_tier {
+            return Ok(());
+        }
+
+        let cost = crate::sinks::prestige_investment_cost(definition, level);
+        let Some(available) = global_resource_mut(&mut self.snapshot.resources, definition.resource)
+        else {
+            return Ok(());
+        };
+        if *available < cost {
+            return Ok(());
+        }
+        *available -= cost;
+
+        set_json_level(&mut self.snapshot.prestige_investments, investment_id, level + 1);
+        // Investments count toward spec tech unlocks, as in TS.
+        self.add_spec_tech_spent(definition.resource, cost);
+        Ok(())
+    }
+
+    fn handle_build_factory(&mut self) -> Result<(), SimulationError> {
+        let (metals_cost, crystals_cost) =
+            crate::factories::compute_factory_cost(self.snapshot.factories.len());
+        let resources = &self.snapshot.resources;
+        if resources.metals < metals_cost || resources.crystals < crystals_cost {
+            return Ok(());
+        }
+
+        let positions: Vec<crate::schema::Vector3> =
+            self.snapshot.factories.iter().map(|f| f.position).collect();
+        let position = crate::factories::compute_factory_placement(&positions, &mut self.rng);
+        let factory_id = self.next_entity_id("factory");
+        let factory = crate::factories::create_factory(factory_id, position);
+
+        self.snapshot.resources.metals -= metals_cost;
+        self.snapshot.resources.crystals -= crystals_cost;
+        self.add_spec_tech_spent("metals", metals_cost);
+        self.add_spec_tech_spent("crystals", crystals_cost);
+        self.add_factory(factory)?;
+        Ok(())
+    }
+
+    fn handle_decommission_factory(&mut self, factory_id: &str) -> Result<(), SimulationError> {
+        let Some(&index) = self.factory_id_to_index.get(factory_id) else {
+            return Ok(());
+        };
+        // The last factory cannot be removed; drones and logistics need a home.
+        if self.snapshot.factories.len() <= 1 {
+            return Ok(());
+        }
+
+        self.sync_data_to_snapshot();
+        let removed_position = self.snapshot.factories[index].position;
+        let Some(home_index) =
+            crate::factories::nearest_factory(&self.snapshot.factories, &removed_position, Some(index))
+        else {
+            return Ok(());
+        };
+        let home_id = self.snapshot.factories[home_index].id.clone();
+        let home_position = self.snapshot.factories[home_index].position;
+
+        // Stock held at the factory moves to the warehouse (global resources), along with the
+        // inputs its unfinished batches have not consumed yet. The warehouse only fills up to its
+        // capacity, as with logistics arrivals; the new home takes the rest, along with the alloys
+        // and coolant the warehouse has no slots for.
+        let mut stock = self.snapshot.factories[index].resources.clone();
+        for refine in &self.snapshot.factories[index].active_refines {
+            let Some(recipe_idx) = recipes::recipe_index(&refine.recipe) else {
+                continue;
+            };
+            let remaining = refine.amount * (1.0 - refine.progress.clamp(0.0, 1.0));
+            for &(resource, ratio) in recipes::RECIPE_DEFINITIONS[recipe_idx].inputs {
+                if let Some(slot) = factory_resource_mut(&mut stock, resource) {
+                    *slot += remaining * ratio;
+                }
+            }
+        }
+        let modifiers = get_resource_modifiers(
+            &self.snapshot.resources,
+            self.snapshot.prestige.cores,
+            self.snapshot.prestige_investments.as_ref(),
+            self.snapshot.spec_techs.as_ref(),
+            &self.snapshot.research.completed,
+        );
+        let warehouse_capacity = compute_warehouse_capacity(&self.snapshot.modules, &modifiers);
+        self.snapshot.resources.credits += std::mem::take(&mut stock.credits);
+        for &resource in FACTORY_RESOURCES {
+            let (Some(held), Some(amount)) = (
+                market::warehouse_stock_mut(&mut self.snapshot.resources, resource),
+                factory_resource_mut(&mut stock, resource),
+            ) else {
+                continue;
+            };
+            let landed = amount.min((warehouse_capacity - *held).max(0.0));
+            *held += landed;
+            *amount -= landed;
+        }
+        let home = &mut self.snapshot.factories[home_index];
+        for &resource in FACTORY_RESOURCES {
+            if let (Some(slot), Some(&mut amount)) = (
+                factory_resource_mut(&mut home.resources, resource),
+                factory_resource_mut(&mut stock, resource),
+            ) {
+                *slot += amount;
+            }
+        }
+        home.current_storage = home.resources.ore;
+
+        for owner in self.snapshot.drone_owners.values_mut() {
+            if owner.as_deref() == Some(factory_id) {
+                *owner = Some(home_id.clone());
+            }
+        }
+
+        let mut redocked: Vec<String> = Vec::new();
+        for flight in self.snapshot.drone_flights.iter_mut() {
+            if flight.owner_factory_id.as_deref() == Some(factory_id) {
+                flight.owner_factory_id = Some(home_id.clone());
+            }
+            if flight.target_factory_id.as_deref() != Some(factory_id) {
+                continue;
+            }
+            flight.target_factory_id = Some(home_id.clone());
+
+            // Re-plan the remaining leg toward the new home at the flight's average speed.
+            let Some(&drone_idx) = self.drone_id_to_index.get(&flight.drone_id) else {
+                continue;
+            };
+            let pos_offset = self.layout.drones.positions.offset_bytes / 4 + drone_idx * 3;
+            let from = [
+                f32::from_bits(self.data[pos_offset]),
+                f32::from_bits(self.data[pos_offset + 1]),
+                f32::from_bits(self.data[pos_offset + 2]),
+            ];
+            flight.travel = redirect_travel(&flight.travel, from, home_position);
+            redocked.push(flight.drone_id.clone());
+        }
+
+        // Drones unloading at the removed factory fall back to their owner, so point owners home.
+        let owner_base = self.layout.drones.owner_factory_index.offset_bytes / 4;
+        for i in 0..self.layout.drones.count() {
+            if f32::from_bits(self.data[owner_base + i]) == index as f32 {
+                // Pre-removal index; remove_factory shifts it along with every other index.
+                self.data[owner_base + i] = (home_index as f32).to_bits();
+            }
+        }
+
+        let removed_queue = std::mem::take(&mut self.snapshot.factories[index].queued_drones);
+        {
+            let home = &mut self.snapshot.factories[home_index];
+            for drone_id in removed_queue.iter().chain(redocked.iter()) {
+                if !home.queued_drones.contains(drone_id) {
+                    home.queued_drones.push(drone_id.clone());
+                }
+            }
+        }
+
+        let mut rerouted: Vec<(String, f32, f32)> = Vec::new();
+        let game_time = self.game_time;
+        if let Some(queues) = self.snapshot.logistics_queues.as_mut() {
+            // Outbound legs now ship from the warehouse, which already holds the credited stock.
+            // Inbound legs from the warehouse carry on to the new home; factory-sourced ones
+            // divert to the warehouse.
+            queues.pending_transfers.retain_mut(|transfer| {
+                let from_removed = transfer.from_factory_id == factory_id;
+                let to_removed = transfer.to_factory_id == factory_id;
+                if from_removed && to_removed {
+                    return false;
+                }
+                if from_removed {
+                    if transfer.to_factory_id == WAREHOUSE_NODE_ID {
+                        return false;
+                    }
+                    transfer.from_factory_id =
+                        WAREHOUSE_NODE_ID.to_string();
+                    return true;
+                }
+                if to_removed {
+                    // A hauler already unloading there takes off again for the new destination.
+                    if let Some(hauler) = transfer.hauler.as_mut().filter(|hauler| hauler.arrived_at.is_some()) {
+                        hauler.arrived_at = None;
+                        hauler.moved_at = game_time;
+                    }
+                    if transfer.from_factory_id == WAREHOUSE_NODE_ID {
+                        transfer.to_factory_id = home_id.clone();
+                        rerouted.push((transfer.resource.clone(), transfer.amount, transfer.eta));
+                        return true;
+                    }
+                    transfer.to_factory_id =
+                        WAREHOUSE_NODE_ID.to_string();
+                }
+                true
+            });
+        }
+        for factory in self.snapshot.factories.iter_mut() {
+            if let Some(state) = factory.logistics_state.as_mut() {
+                for schedule in state.inbound_schedules.iter_mut() {
+                    if schedule.from_factory_id == factory_id {
+                        schedule.from_factory_id =
+                            WAREHOUSE_NODE_ID.to_string();
+                    }
+                }
+            }
+        }
+        if !rerouted.is_empty() {
+            let home = &mut self.snapshot.factories[home_index];
+            let state = home.logistics_state.get_or_insert_with(Default::default);
+            for (resource, amount, eta) in rerouted {
+                state.inbound_schedules.push(crate::schema::InboundSchedule {
+                    from_factory_id: WAREHOUSE_NODE_ID.to_string(),
+                    resource,
+                    amount,
+                    eta,
+                });
+            }
+        }
+
+        self.remove_factory(factory_id);
+        self.sync_globals_to_buffer();
+        if let Some(&home_index) = self.factory_id_to_index.get(&home_id) {
+            self.sync_factory_to_buffer(home_index);
+        }
+        Ok(())
+    }
+
+    /// Resizes the hauler group to one slot per scheduled transfer and rewrites every slot.
+    /// If the buffer cannot grow, the haulers that fit are still written before the error returns.
+    fn sync_haulers(&mut self) -> Result<(), SimulationError> {
+        let count = hauler_count(&self.snapshot);
+        let mut needed = self.layout.counts();
+        needed.haulers = count;
+        let grown = self.ensure_capacity(needed);
+        let count = count.min(self.layout.haulers.cargo.entity_capacity());
+        if count != self.layout.haulers.count() {
+            for section in self.layout.haulers.sections_mut() {
+                section.set_entity_count(count);
+            }
+            self.bump_generation();
+        }
+
+        let Some(queues) = self.snapshot.logistics_queues.as_ref() else {
+            return grown;
+        };
+        // SAFETY: the hauler sections are disjoint ranges of `data`, validated during layout planning.
+        unsafe {
+            let data_ptr = self.data.as_mut_ptr();
+            let get_slice_mut = |section: &crate::buffers::BufferSection| -> &mut [f32] {
+                let ptr = data_ptr.add(section.offset_bytes / 4) as *mut f32;
+                std::slice::from_raw_parts_mut(ptr, section.length)
+            };
+            let h = &self.layout.haulers;
+            haulers::sys_haulers(
+                get_slice_mut(&h.positions),
+                get_slice_mut(&h.velocities),
+                get_slice_mut(&h.cargo),
+                get_slice_mut(&h.route),
+                &queues.pending_transfers,
+                &self.snapshot.factories,
+            );
+        }
+        grown
+    }
+
+    /// Transfer id behind each hauler slot, in buffer order.
+    pub fn hauler_transfer_ids(&self) -> Vec<String> {
+        self.snapshot
+            .logistics_queues
+            .as_ref()
+            .map(|queues| {
+                haulers::scheduled_transfers(&queues.pending_transfers)
+                    .map(|transfer| transfer.id.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn sync_globals_to_buffer(&mut self) {
+        let offset = self.layout.globals.resources.offset_bytes / 4;
+        let r = &self.snapshot.resources;
+        self.data[offset] = r.ore.to_bits();
+        self.data[offset + 1] = r.ice.to_bits();
+        self.data[offset + 2] = r.metals.to_bits();
+        self.data[offset + 3] = r.crystals.to_bits();
+        self.data[offset + 4] = r.organics.to_bits();
+        self.data[offset + 5] = r.bars.to_bits();
+        self.data[offset + 6] = r.energy.to_bits();
+        self.data[offset + 7] = r.credits.to_bits();
+    }
+
+    fn sync_factory_to_buffer(&mut self, factory_idx: usize) {
+        if factory_idx >= self.snapshot.factories.len() {
+            return;
+        }
+
+        let factory = &self.snapshot.factories[factory_idx];
+
+        // Sync resources
+        let offset = self.layout.factories.resources.offset_bytes / 4 + factory_idx * FACTORY_RESOURCE_STRIDE;
+        self.data[offset] = factory.resources.ore.to_bits();
+        self.data[offset + 1] = factory.resources.ice.to_bits();
+        self.data[offset + 2] = factory.resources.metals.to_bits();
+        self.data[offset + 3] = factory.resources.crystals.to_bits();
+        self.data[offset + 4] = factory.resources.organics.to_bits();
+        self.data[offset + 5] = factory.resources.bars.to_bits();
+        self.data[offset + 6] = factory.resources.credits.to_bits();
+        self.data[offset + 7] = factory.resources.alloys.to_bits();
+        self.data[offset + 8] = factory.resources.coolant.to_bits();
+
+        // Sync energy
+        let offset = self.layout.factories.energy.offset_bytes / 4 + factory_idx;
+        self.data[offset] = factory.energy.to_bits();
 
         let offset = self.layout.factories.max_energy.offset_bytes / 4 + factory_idx;
         self.data[offset] = factory.energy_capacity.to_bits();
 
         // Sync upgrades
-        let offset = self.layout.factories.upgrades.offset_bytes / 4 + factory_idx * 5;
+        let offset =
+            self.layout.factories.upgrades.offset_bytes / 4 + factory_idx * FACTORY_UPGRADE_STRIDE;
         self.data[offset] = (factory.upgrades.docking as f32).to_bits();
         self.data[offset + 1] = (factory.upgrades.refine as f32).to_bits();
         self.data[offset + 2] = (factory.upgrades.storage as f32).to_bits();
         self.data[offset + 3] = (factory.upgrades.energy as f32).to_bits();
         self.data[offset + 4] = (factory.upgrades.solar as f32).to_bits();
+        self.data[offset + 5] = (factory.upgrades.unload as f32).to_bits();
+        self.data[offset + 6] = (factory.upgrades.cooler as f32).to_bits();
 
         // Sync haulers
         let offset = self.layout.factories.haulers_assigned.offset_bytes / 4 + factory_idx;
@@ -1709,6 +3637,67 @@ impl GameState {
     }
 }
 
+/// Re-plans the rest of a flight from `from` to `to` at the flight's average speed so far.
+fn redirect_travel(
+    travel: &crate::schema::TravelSnapshot,
+    from: [f32; 3],
+    to: [f32; 3],
+) -> crate::schema::TravelSnapshot {
+    let leg = distance(&travel.from, &travel.to);
+    let speed = if travel.duration > 0.0 && leg > 0.0 {
+        leg / travel.duration
+    } else {
+        crate::constants::DRONE_SPEED
+    };
+    let remaining = distance(&from, &to);
+    crate::schema::TravelSnapshot {
+        from,
+        to,
+        elapsed: 0.0,
+        duration: (remaining / speed.max(1.0)).max(0.1),
+        control: None,
+    }
+}
+
+fn factory_resource_mut<'a>(
+    resources: &'a mut crate::schema::FactoryResourceSnapshot,
+    key: &str,
+) -> Option<&'a mut f32> {
+    match key {
+        "ore" => Some(&mut resources.ore),
+        "ice" => Some(&mut resources.ice),
+        "metals" => Some(&mut resources.metals),
+        "crystals" => Some(&mut resources.crystals),
+        "organics" => Some(&mut resources.organics),
+        "bars" => Some(&mut resources.bars),
+        "credits" => Some(&mut resources.credits),
+        "alloys" => Some(&mut resources.alloys),
+        "coolant" => Some(&mut resources.coolant),
+        _ => None,
+    }
+}
+
+fn global_resource_mut<'a>(resources: &'a mut Resources, key: &str) -> Option<&'a mut f32> {
+    match key {
+        "ore" => Some(&mut resources.ore),
+        "ice" => Some(&mut resources.ice),
+        "metals" => Some(&mut resources.metals),
+        "crystals" => Some(&mut resources.crystals),
+        "organics" => Some(&mut resources.organics),
+        "bars" => Some(&mut resources.bars),
+        "credits" => Some(&mut resources.credits),
+        _ => None,
+    }
+}
+
+/// Writes `key: level` into a JSON level map, creating the map if needed.
+fn set_json_level(json: &mut Option<Value>, key: &str, level: i64) {
+    let map = json.get_or_insert_with(|| Value::Object(serde_json::Map::new()));
+    if let Some(map) = map.as_object_mut() {
+        map.insert(key.to_string(), Value::from(level));
+    }
+}
+
 fn asteroid_array(extra: &BTreeMap<String, Value>) -> Option<&Vec<Value>> {
     extra
         .get("asteroids")
@@ -1739,6 +3728,140 @@ fn asteroid_array_mut(extra: &mut BTreeMap<String, Value>) -> Option<&mut Vec<Va
     None
 }
 
+/// Writes a factory's snapshot fields into buffer slot `index`.
+fn write_factory_slot(
+    layout: &EntityBufferLayout,
+    data: &mut [u32],
+    index: usize,
+    factory: &FactorySnapshot,
+) {
+    let offset = layout.factories.positions.offset_bytes / 4 + index * 3;
+    data[offset] = factory.position[0].to_bits();
+    data[offset + 1] = factory.position[1].to_bits();
+    data[offset + 2] = factory.position[2].to_bits();
+
+    let offset = layout.factories.orientations.offset_bytes / 4 + index * 4;
+    let half_yaw = FACTORY_DEFAULT_YAW * 0.5;
+    data[offset] = 0.0f32.to_bits();
+    data[offset + 1] = half_yaw.sin().to_bits();
+    data[offset + 2] = 0.0f32.to_bits();
+    data[offset + 3] = half_yaw.cos().to_bits();
+
+    let offset = layout.factories.resources.offset_bytes / 4 + index * FACTORY_RESOURCE_STRIDE;
+    data[offset] = factory.resources.ore.to_bits();
+    data[offset + 1] = factory.resources.ice.to_bits();
+    data[offset + 2] = factory.resources.metals.to_bits();
+    data[offset + 3] = factory.resources.crystals.to_bits();
+    data[offset + 4] = factory.resources.organics.to_bits();
+    data[offset + 5] = factory.resources.bars.to_bits();
+    data[offset + 6] = factory.resources.credits.to_bits();
+    data[offset + 7] = factory.resources.alloys.to_bits();
+    data[offset + 8] = factory.resources.coolant.to_bits();
+
+    let offset = layout.factories.energy.offset_bytes / 4 + index;
+    data[offset] = factory.energy.to_bits();
+
+    let offset = layout.factories.max_energy.offset_bytes / 4 + index;
+    data[offset] = factory.energy_capacity.to_bits();
+
+    let offset = layout.factories.upgrades.offset_bytes / 4 + index * FACTORY_UPGRADE_STRIDE;
+    data[offset] = (factory.upgrades.docking as f32).to_bits();
+    data[offset + 1] = (factory.upgrades.refine as f32).to_bits();
+    data[offset + 2] = (factory.upgrades.storage as f32).to_bits();
+    data[offset + 3] = (factory.upgrades.energy as f32).to_bits();
+    data[offset + 4] = (factory.upgrades.solar as f32).to_bits();
+    data[offset + 5] = (factory.upgrades.unload as f32).to_bits();
+    data[offset + 6] = (factory.upgrades.cooler as f32).to_bits();
+
+    let offset = layout.factories.heat.offset_bytes / 4 + index;
+    data[offset] = factory.heat.to_bits();
+    let offset = layout.factories.cooling.offset_bytes / 4 + index;
+    data[offset] = (if factory.cooling { 1.0f32 } else { 0.0 }).to_bits();
+
+    let haulers_offset = layout.factories.haulers_assigned.offset_bytes / 4 + index;
+    data[haulers_offset] = (factory.haulers_assigned.unwrap_or(0) as f32).to_bits();
+
+    let recipe_base = layout.factories.refine_recipes.offset_bytes / 4 + index * MAX_REFINE_SLOTS;
+    for s in 0..MAX_REFINE_SLOTS {
+        let recipe = factory
+            .refine_recipes
+            .get(s)
+            .and_then(|id| recipes::recipe_index(id))
+            .unwrap_or(0);
+        data[recipe_base + s] = (recipe as f32).to_bits();
+    }
+
+    // Initialize refinery state slots from factory.active_refines if provided
+    let ref_idx_base = layout.factories.refinery_state.offset_bytes / 4 + index * (MAX_REFINE_SLOTS * 4);
+    let slots_limit = factory.refine_slots as usize;
+    for (s, proc) in factory.active_refines.iter().enumerate().take(slots_limit.min(MAX_REFINE_SLOTS)) {
+        let slot_offset = ref_idx_base + s * 4;
+        data[slot_offset] = (1.0f32).to_bits(); // active
+        data[slot_offset + 1] = proc.amount.to_bits();
+        data[slot_offset + 2] = proc.progress.to_bits();
+        data[slot_offset + 3] = proc.speed_multiplier.to_bits();
+    }
+}
+
+/// Writes a serialized asteroid's position, ore and resource profile into buffer slot `index`.
+fn write_asteroid_slot(
+    layout: &EntityBufferLayout,
+    data: &mut [u32],
+    index: usize,
+    asteroid: &Value,
+) {
+    if let Some(pos) = asteroid.get("position").and_then(|v| v.as_array()) {
+        if pos.len() >= 3 {
+            let offset = layout.asteroids.positions.offset_bytes / 4 + index * 3;
+            data[offset] = (pos[0].as_f64().unwrap_or(0.0) as f32).to_bits();
+            data[offset + 1] = (pos[1].as_f64().unwrap_or(0.0) as f32).to_bits();
+            data[offset + 2] = (pos[2].as_f64().unwrap_or(0.0) as f32).to_bits();
+        }
+    }
+
+    if let Some(ore) = asteroid.get("oreRemaining").and_then(|v| v.as_f64()) {
+        let offset = layout.asteroids.ore_remaining.offset_bytes / 4 + index;
+        data[offset] = (ore as f32).to_bits();
+    }
+
+    if let Some(max_ore) = asteroid.get("maxOre").and_then(|v| v.as_f64()) {
+        let offset = layout.asteroids.max_ore.offset_bytes / 4 + index;
+        data[offset] = (max_ore as f32).to_bits();
+    }
+
+    // Initialize resource profile
+    if let Some(profile) = asteroid.get("resourceProfile").and_then(|v| v.as_object()) {
+        let base_offset = layout.asteroids.resource_profile.offset_bytes / 4 + index * 5;
+        let ore = profile.get("ore").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+        let ice = profile.get("ice").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+        let metals = profile.get("metals").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+        let crystals = profile.get("crystals").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+        let organics = profile.get("organics").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+
+        data[base_offset] = ore.to_bits();
+        data[base_offset + 1] = ice.to_bits();
+        data[base_offset + 2] = metals.to_bits();
+        data[base_offset + 3] = crystals.to_bits();
+        data[base_offset + 4] = organics.to_bits();
+    } else {
+        // Default to 100% ore if missing
+        let base_offset = layout.asteroids.resource_profile.offset_bytes / 4 + index * 5;
+        data[base_offset] = (1.0f32).to_bits();
+        data[base_offset + 1] = (0.0f32).to_bits();
+        data[base_offset + 2] = (0.0f32).to_bits();
+        data[base_offset + 3] = (0.0f32).to_bits();
+        data[base_offset + 4] = (0.0f32).to_bits();
+    }
+}
+
+fn hauler_count(snapshot: &SimulationSnapshot) -> usize {
+    snapshot
+        .logistics_queues
+        .as_ref()
+        .map(|queues| haulers::scheduled_transfers(&queues.pending_transfers).count())
+        .unwrap_or(0)
+}
+
 fn asteroid_count(snapshot: &SimulationSnapshot) -> usize {
     asteroid_array(&snapshot.extra)
         .map(|arr| arr.len())
@@ -1754,6 +3877,14 @@ fn burn_rng_for_asteroids(rng: &mut Mulberry32, asteroid_count: usize) {
     }
 }
 
+fn grow_capacity(current: usize, needed: usize) -> usize {
+    if needed <= current {
+        current
+    } else {
+        capacity_with_spare(needed).max(current * 2)
+    }
+}
+
 fn build_drone_index_to_id(
     drone_id_to_index: &BTreeMap<String, usize>,
     total_drone_count: usize,
@@ -1798,6 +3929,8 @@ mod tests {
                 hauler_depot: 0,
                 logistics_hub: 0,
                 routing_protocol: 0,
+                lab: 0,
+                data_core: 0,
             },
             prestige: Prestige { cores: 0 },
             save: SaveMeta {
@@ -1822,6 +3955,7 @@ mod tests {
                 },
                 use_rust_sim: false,
                 shadow_mode: false,
+                auto_balance_drones: false,
             },
             rng_seed: Some(7),
             drone_flights: vec![],
@@ -1832,6 +3966,16 @@ mod tests {
             spec_techs: None,
             spec_tech_spent: None,
             prestige_investments: None,
+            asteroid_hazards: Vec::new(),
+            lifetime_stats: None,
+            drone_loadouts: BTreeMap::new(),
+            drone_integrity: BTreeMap::new(),
+            disabled_drones: Default::default(),
+            drone_orders: BTreeMap::new(),
+            assigned_drones: Default::default(),
+            market: Default::default(),
+            contracts: Default::default(),
+            research: Default::default(),
             game_time: 0.0,
             extra: BTreeMap::new(),
         }
@@ -1907,6 +4051,7 @@ mod tests {
         factory.active_refines = vec![RefineProcessSnapshot {
             id: "r1".to_string(),
             ore_type: "ore".to_string(),
+            recipe: "bars".to_string(),
             amount: 20.0,
             progress: 0.0,
             time_total: crate::constants::FACTORY_REFINE_TIME,
@@ -1928,5 +4073,991 @@ mod tests {
         let exported: SimulationSnapshot = serde_json::from_str(&json).expect("should parse exported snapshot");
         assert_eq!(exported.factories[0].active_refines.len(), 1);
         assert_eq!(exported.factories[0].active_refines[0].amount, 20.0);
+        assert_eq!(exported.factories[0].active_refines[0].ore_type, "ore");
+        assert_eq!(exported.factories[0].active_refines[0].recipe, "bars");
+    }
+
+    #[test]
+    fn drone_bay_purchase_adds_drone_in_place() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.bars = 1000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.get_drone_battery_mut()[0] = 7.0;
+        let ptr_before = state.data.as_ptr();
+        let generation_before = state.generation();
+
+        state
+            .apply_command(SimulationCommand::BuyModule {
+                module_type: "droneBay".to_string(),
+                factory_id: None,
+                cost_variant: None,
+            })
+            .expect("purchase");
+
+        assert_eq!(state.drone_ids().len(), 2);
+        assert_eq!(state.get_drone_battery_mut()[0], 7.0);
+        assert_ne!(state.generation(), generation_before, "the drone sections grew");
+        assert_eq!(state.data.as_ptr(), ptr_before);
+
+        let capacity = state.layout.capacities().drones;
+        while state.drone_ids().len() <= capacity {
+            state.add_drone(None).expect("add drone");
+        }
+        assert!(state.layout.capacities().drones > capacity, "growing past capacity reallocates");
+        assert!(state.generation() > generation_before);
+        assert_eq!(state.get_drone_battery_mut()[0], 7.0);
+    }
+
+    #[test]
+    fn drone_added_for_an_unknown_factory_has_no_owner() {
+        let mut state = GameState::from_snapshot(sample_snapshot()).expect("should build state");
+        let drone_id = state.add_drone(Some("factory-missing")).expect("add drone");
+        let index = state.drone_id_to_index[&drone_id];
+
+        assert!(!state.snapshot().drone_owners.contains_key(&drone_id));
+        let owner_offset = state.layout.drones.owner_factory_index.offset_bytes / 4;
+        assert_eq!(f32::from_bits(state.data[owner_offset + index]), -1.0);
+    }
+
+    #[test]
+    fn removing_asteroid_remaps_drone_targets() {
+        let mut state = GameState::from_snapshot(sample_snapshot()).expect("should build state");
+        for id in ["asteroid-a", "asteroid-b"] {
+            state
+                .add_asteroid(serde_json::json!({
+                    "id": id,
+                    "position": [1.0, 0.0, 0.0],
+                    "oreRemaining": 50.0,
+                    "maxOre": 50.0
+                }))
+                .expect("add asteroid");
+        }
+        let target_offset = state.layout.drones.target_asteroid_index.offset_bytes / 4;
+        state.data[target_offset] = 1.0f32.to_bits();
+
+        assert!(state.remove_asteroid("asteroid-a"));
+        assert_eq!(state.asteroid_ids(), &["asteroid-b".to_string()]);
+        assert_eq!(f32::from_bits(state.data[target_offset]), 0.0);
+        assert_eq!(state.get_asteroid_ore_remaining_mut(), &[50.0]);
+    }
+
+    #[test]
+    fn build_and_decommission_factory_rehomes_references() {
+        use crate::schema::{FactorySnapshot, LogisticsQueues, PendingTransfer};
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.metals = 500.0;
+        snapshot.resources.crystals = 500.0;
+        let mut origin = FactorySnapshot {
+            id: "factory-0".to_string(),
+            queued_drones: vec!["drone-a".to_string()],
+            ..Default::default()
+        };
+        origin.resources.bars = 12.0;
+        snapshot.factories = vec![origin];
+        snapshot
+            .drone_owners
+            .insert("drone-a".to_string(), Some("factory-0".to_string()));
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+
+        state.apply_command(SimulationCommand::BuildFactory).expect("build");
+        assert_eq!(state.snapshot().factories.len(), 2);
+        assert_eq!(state.snapshot().resources.metals, 400.0);
+        assert_eq!(state.snapshot().resources.crystals, 450.0);
+        let new_id = state.snapshot().factories[1].id.clone();
+        assert_eq!(state.snapshot().factories[1].docking_capacity, 3);
+
+        state.snapshot.logistics_queues = Some(LogisticsQueues {
+            pending_transfers: vec![PendingTransfer {
+                id: "t1".to_string(),
+                from_factory_id: "factory-0".to_string(),
+                to_factory_id: new_id.clone(),
+                resource: "bars".to_string(),
+                amount: 5.0,
+                status: "scheduled".to_string(),
+                eta: 100.0,
+                departed_at: 0.0,
+                pickup_overhead: 0.0,
+                dropoff_overhead: 0.0,
+                hauler: None,
+                carrier_id: None,
+            }],
+            ..Default::default()
+        });
+
+        state
+            .apply_command(SimulationCommand::DecommissionFactory {
+                factory_id: "factory-0".to_string(),
+            })
+            .expect("decommission");
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.factories.len(), 1);
+        assert_eq!(snapshot.factories[0].id, new_id);
+        assert_eq!(snapshot.drone_owners["drone-a"], Some(new_id.clone()));
+        assert!(snapshot.factories[0].queued_drones.contains(&"drone-a".to_string()));
+        assert_eq!(snapshot.resources.bars, 12.0);
+        let transfer = &snapshot.logistics_queues.as_ref().unwrap().pending_transfers[0];
+        assert_eq!(transfer.from_factory_id, WAREHOUSE_NODE_ID);
+    }
+
+    #[test]
+    fn decommission_conserves_stock_refines_and_inbound_transfers() {
+        use crate::schema::{FactorySnapshot, LogisticsQueues, PendingTransfer};
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.ore = 20.0;
+        let home = FactorySnapshot {
+            id: "factory-0".to_string(),
+            ..Default::default()
+        };
+        let mut removed = FactorySnapshot {
+            id: "factory-1".to_string(),
+            position: [10.0, 0.0, 0.0],
+            refine_slots: 1,
+            refine_recipes: vec!["alloys".to_string()],
+            ..Default::default()
+        };
+        removed.resources.metals = 7.0;
+        removed.resources.alloys = 4.0;
+        removed.resources.coolant = 2.5;
+        removed.active_refines = vec![RefineProcessSnapshot {
+            id: "r1".to_string(),
+            ore_type: "ore".to_string(),
+            recipe: "alloys".to_string(),
+            amount: 10.0,
+            progress: 0.4,
+            time_total: 15.0,
+            energy_required: crate::constants::FACTORY_ENERGY_PER_REFINE,
+            speed_multiplier: 1.0,
+        }];
+        snapshot.factories = vec![home, removed];
+        snapshot.logistics_queues = Some(LogisticsQueues {
+            pending_transfers: vec![PendingTransfer {
+                id: "t1".to_string(),
+                from_factory_id: WAREHOUSE_NODE_ID.to_string(),
+                to_factory_id: "factory-1".to_string(),
+                resource: "ore".to_string(),
+                amount: 5.0,
+                status: "scheduled".to_string(),
+                eta: 100.0,
+                departed_at: 0.0,
+                pickup_overhead: 0.0,
+                dropoff_overhead: 0.0,
+                hauler: None,
+                carrier_id: None,
+            }],
+            ..Default::default()
+        });
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+
+        // Warehouse, factory stock and the inputs still held by unfinished batches.
+        let totals = |state: &GameState| -> Vec<f32> {
+            let snapshot = state.snapshot();
+            let r = &snapshot.resources;
+            let mut totals = vec![r.ore, r.ice, r.metals, r.crystals, r.organics, r.bars, r.credits, 0.0, 0.0];
+            for factory in &snapshot.factories {
+                let mut stock = factory.resources.clone();
+                for refine in &factory.active_refines {
+                    let recipe = &recipes::RECIPE_DEFINITIONS[recipes::recipe_index(&refine.recipe).unwrap()];
+                    for &(resource, ratio) in recipe.inputs {
+                        *factory_resource_mut(&mut stock, resource).unwrap() +=
+                            refine.amount * (1.0 - refine.progress) * ratio;
+                    }
+                }
+                let s = &stock;
+                for (total, amount) in totals.iter_mut().zip([
+                    s.ore, s.ice, s.metals, s.crystals, s.organics, s.bars, s.credits, s.alloys, s.coolant,
+                ]) {
+                    *total += amount;
+                }
+            }
+            totals
+        };
+        let before = totals(&state);
+
+        state
+            .apply_command(SimulationCommand::DecommissionFactory {
+                factory_id: "factory-1".to_string(),
+            })
+            .expect("decommission");
+
+        let after = totals(&state);
+        for (before, after) in before.iter().zip(after.iter()) {
+            assert!((before - after).abs() < 1e-4, "{before:?} -> {after:?}");
+        }
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.resources.metals, 13.0);
+        assert_eq!(snapshot.resources.crystals, 3.0);
+        assert_eq!(snapshot.factories[0].resources.alloys, 4.0);
+        assert_eq!(snapshot.factories[0].resources.coolant, 2.5);
+        // The warehouse still holds the in-flight ore; it now lands at the new home.
+        assert_eq!(snapshot.resources.ore, 20.0);
+        let transfer = &snapshot.logistics_queues.as_ref().unwrap().pending_transfers[0];
+        assert_eq!(transfer.to_factory_id, "factory-0");
+        let schedules = &snapshot.factories[0].logistics_state.as_ref().unwrap().inbound_schedules;
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].amount, 5.0);
+    }
+
+    #[test]
+    fn decommission_sends_stock_past_warehouse_capacity_to_the_new_home() {
+        use crate::schema::FactorySnapshot;
+        let mut snapshot = sample_snapshot();
+        let capacity = {
+            let modifiers = get_resource_modifiers(
+                &snapshot.resources,
+                snapshot.prestige.cores,
+                snapshot.prestige_investments.as_ref(),
+                snapshot.spec_techs.as_ref(),
+                &snapshot.research.completed,
+            );
+            compute_warehouse_capacity(&snapshot.modules, &modifiers)
+        };
+        snapshot.resources.ore = capacity - 10.0;
+        let home = FactorySnapshot {
+            id: "factory-0".to_string(),
+            ..Default::default()
+        };
+        let mut removed = FactorySnapshot {
+            id: "factory-1".to_string(),
+            position: [10.0, 0.0, 0.0],
+            ..Default::default()
+        };
+        removed.resources.ore = 30.0;
+        removed.resources.credits = 50.0;
+        snapshot.factories = vec![home, removed];
+        let credits = snapshot.resources.credits;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+
+        state
+            .apply_command(SimulationCommand::DecommissionFactory {
+                factory_id: "factory-1".to_string(),
+            })
+            .expect("decommission");
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.resources.ore, capacity);
+        assert_eq!(snapshot.factories[0].resources.ore, 20.0);
+        assert_eq!(snapshot.factories[0].current_storage, 20.0);
+        // Credits are not warehouse stock and are never capped.
+        assert_eq!(snapshot.resources.credits, credits + 50.0);
+    }
+
+    #[test]
+    fn disabled_drones_stay_parked_across_reload_until_repaired() {
+        let mut snapshot = sample_snapshot();
+        snapshot.modules.drone_bay = 2;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        let integrity_base = state.layout.drones.integrity.offset_bytes / 4;
+        let state_base = state.layout.drones.states.offset_bytes / 4;
+        // One drone is disabled and part-way through repairs; the other is only scratched.
+        let damaged = DRONE_MAX_INTEGRITY * 0.3;
+        state.data[integrity_base] = damaged.to_bits();
+        state.data[state_base] = DRONE_STATE_DISABLED.to_bits();
+        state.data[integrity_base + 1] = damaged.to_bits();
+        let disabled_id = state.drone_ids()[0].clone();
+        let working_id = state.drone_ids()[1].clone();
+
+        let exported = state.export_snapshot_str().expect("export");
+        let reloaded = GameState::from_snapshot(serde_json::from_str(&exported).unwrap())
+            .expect("reload");
+        assert!(reloaded.snapshot().disabled_drones.contains(&disabled_id));
+        let drone_state = |id: &String| {
+            let idx = reloaded.drone_id_to_index[id];
+            f32::from_bits(reloaded.data[reloaded.layout.drones.states.offset_bytes / 4 + idx])
+        };
+        assert_eq!(drone_state(&disabled_id), DRONE_STATE_DISABLED);
+        assert_ne!(drone_state(&working_id), DRONE_STATE_DISABLED);
+    }
+
+    #[test]
+    fn equipping_a_drone_changes_only_its_stats() {
+        let mut snapshot = sample_snapshot();
+        snapshot.modules.drone_bay = 2;
+        snapshot.resources.metals = 400.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        let drone_id = state.drone_ids()[1].clone();
+        let mining_base = state.layout.drones.mining_rate.offset_bytes / 4;
+        let speed_base = state.layout.drones.speed.offset_bytes / 4;
+        let read = |state: &GameState, offset: usize| f32::from_bits(state.data[offset]);
+        let base_rate = read(&state, mining_base);
+
+        let equip = |tier| SimulationCommand::EquipDrone {
+            drone_id: drone_id.clone(),
+            slot: "tool".to_string(),
+            tier,
+        };
+        state.apply_command(equip(2)).expect("equip");
+        assert_eq!(state.snapshot().resources.metals, 400.0 - 240.0);
+        assert_eq!(state.snapshot().drone_loadouts[&drone_id].tool, 2);
+        assert!((read(&state, mining_base + 1) - base_rate * 1.24).abs() < 1e-4);
+        assert_eq!(read(&state, mining_base), base_rate);
+        assert!(read(&state, speed_base) > 0.0);
+
+        // Cannot afford tier 3 (384 metals) with what is left.
+        state.apply_command(equip(3)).expect("equip");
+        assert_eq!(state.snapshot().drone_loadouts[&drone_id].tool, 2);
+
+        state
+            .apply_command(SimulationCommand::UnequipDrone {
+                drone_id: drone_id.clone(),
+                slot: "tool".to_string(),
+            })
+            .expect("unequip");
+        assert!(state.snapshot().drone_loadouts.is_empty());
+        assert_eq!(read(&state, mining_base + 1), base_rate);
+    }
+
+    #[test]
+    fn generated_field_matches_burned_rng_on_reload() {
+        let mut state = GameState::from_snapshot(sample_snapshot()).expect("should build state");
+        let count = state.generate_asteroid_field(42).expect("generate field");
+        assert_eq!(count, ASTEROID_FIELD_TARGET);
+        assert_eq!(state.asteroid_ids().len(), count);
+        assert!(state.asteroid_ids()[0].starts_with("asteroid-"));
+
+        let exported = state.export_snapshot_str().expect("export");
+        let mut reloaded = GameState::from_snapshot(serde_json::from_str(&exported).unwrap())
+            .expect("reload");
+        assert_eq!(reloaded.asteroid_ids(), state.asteroid_ids());
+        assert_eq!(reloaded.rng.next_u32(), state.rng.next_u32());
+        assert!(!state.snapshot().asteroid_hazards.is_empty());
+        assert_eq!(reloaded.snapshot().asteroid_hazards, state.snapshot().asteroid_hazards);
+
+        let mut again = GameState::from_snapshot(sample_snapshot()).expect("should build state");
+        again.generate_asteroid_field(42).expect("generate field");
+        assert_eq!(
+            again.export_snapshot_str().expect("export"),
+            state.export_snapshot_str().expect("export")
+        );
+    }
+
+    #[test]
+    fn loaded_asteroids_without_hazards_are_seeded_from_biome_and_regions() {
+        let mut snapshot = sample_snapshot();
+        snapshot.extra.insert(
+            "asteroids".to_string(),
+            serde_json::json!([
+                {
+                    "id": "asteroid-a",
+                    "position": [10.0, 0.0, 0.0],
+                    "oreRemaining": 50.0,
+                    "maxOre": 50.0,
+                    "biome": { "biomeId": "ice", "fractureSeed": 250, "hazard": { "id": "ionStorm", "severity": "high" } },
+                    "regions": null
+                },
+                {
+                    "id": "asteroid-b",
+                    "position": [-10.0, 0.0, 0.0],
+                    "oreRemaining": 50.0,
+                    "maxOre": 50.0,
+                    "biome": { "biomeId": "ice", "fractureSeed": 7, "hazard": { "id": "ionStorm", "severity": "low" } },
+                    "regions": [
+                        { "id": "b-r0", "weight": 0.5, "hazard": { "id": "sporeBurst", "severity": "medium" } },
+                        { "id": "b-r1", "weight": 0.5, "hazard": null }
+                    ]
+                }
+            ]),
+        );
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+
+        let hazards = state.snapshot().asteroid_hazards.clone();
+        assert_eq!(hazards.len(), 2);
+        assert_eq!(hazards[0].asteroid_id, "asteroid-a");
+        assert_eq!(hazards[0].kind, "ionStorm");
+        assert_eq!(hazards[0].severity, "high");
+        assert_eq!(hazards[0].region_id, None);
+        assert!(!hazards[0].active && hazards[0].remaining > 0.0);
+        // Once fractured, the regions' hazards replace the asteroid-wide one.
+        assert_eq!(hazards[1].asteroid_id, "asteroid-b");
+        assert_eq!(hazards[1].kind, "sporeBurst");
+        assert_eq!(hazards[1].region_id.as_deref(), Some("b-r0"));
+
+        let exported = state.export_snapshot_str().expect("export");
+        let reloaded = GameState::from_snapshot(serde_json::from_str(&exported).unwrap())
+            .expect("reload");
+        assert_eq!(reloaded.snapshot().asteroid_hazards, hazards);
+    }
+
+    #[test]
+    fn drone_orders_override_targeting_and_report_when_they_end() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.metals = 1000.0;
+        snapshot.resources.crystals = 1000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        let factory_id = state.snapshot().factories[0].id.clone();
+        state.generate_asteroid_field(11).expect("generate field");
+        let drone_id = state.drone_ids()[0].clone();
+        let order = |order| SimulationCommand::OrderDrone {
+            drone_id: drone_id.clone(),
+            order,
+        };
+
+        state
+            .apply_command(order(DroneOrder::GoTo {
+                asteroid_id: "asteroid-missing".to_string(),
+                region_id: None,
+            }))
+            .expect("order");
+        assert!(state.snapshot().drone_orders.is_empty());
+
+        state.apply_command(order(DroneOrder::Hold)).expect("order");
+        state.step(0.1);
+        assert!(state.snapshot().drone_flights.is_empty());
+
+        let target = state.asteroid_ids().last().cloned().unwrap();
+        state
+            .apply_command(order(DroneOrder::GoTo {
+                asteroid_id: target.clone(),
+                region_id: None,
+            }))
+            .expect("order");
+        state.step(0.1);
+        let flight = &state.snapshot().drone_flights[0];
+        assert_eq!(flight.target_asteroid_id.as_deref(), Some(target.as_str()));
+        assert!(state.snapshot().drone_orders.is_empty());
+        let reasons: Vec<_> = state
+            .drain_order_events()
+            .into_iter()
+            .map(|event| (event.kind, event.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("hold".to_string(), "replaced".to_string()),
+                ("goTo".to_string(), "completed".to_string()),
+            ]
+        );
+
+        // Recalling an outbound drone turns it around toward the ordered factory.
+        for _ in 0..10 {
+            state.step(0.1);
+        }
+        assert_eq!(state.snapshot().drone_flights[0].state, "toAsteroid");
+        state
+            .apply_command(order(DroneOrder::Recall {
+                factory_id: factory_id.clone(),
+            }))
+            .expect("order");
+        state.step(0.1);
+        let flight = &state.snapshot().drone_flights[0];
+        assert_eq!(flight.state, "returning");
+        assert_eq!(flight.target_factory_id, Some(factory_id));
+        // The recall stays on the books until the drone docks.
+        assert!(state.drain_order_events().is_empty());
+        assert_eq!(state.snapshot().drone_orders.len(), 1);
+        let mut completed = Vec::new();
+        for _ in 0..600 {
+            state.step(0.1);
+            completed = state.drain_order_events();
+            if !completed.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(completed.len(), 1);
+        assert_eq!(
+            (completed[0].kind.as_str(), completed[0].reason.as_str()),
+            ("recall", "completed")
+        );
+        assert!(state.snapshot().drone_orders.is_empty());
+        assert!(state.snapshot().drone_flights.is_empty(), "the drone has docked");
+
+        state
+            .apply_command(order(DroneOrder::Pin {
+                asteroid_id: target.clone(),
+            }))
+            .expect("order");
+        state
+            .apply_command(SimulationCommand::RecycleAsteroid {
+                asteroid_id: target,
+            })
+            .expect("recycle");
+        state.step(0.1);
+        assert!(state.snapshot().drone_orders.is_empty());
+        assert_eq!(state.drain_order_events()[0].reason, "targetLost");
+    }
+
+    #[test]
+    fn refine_slots_run_their_selected_recipes() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.metals = 1000.0;
+        snapshot.resources.crystals = 1000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        let factory_id = state.snapshot().factories[0].id.clone();
+        {
+            let resources = state.get_factory_resources_mut();
+            resources[..FACTORY_RESOURCE_STRIDE].fill(0.0);
+            resources[1] = 100.0; // ice
+            resources[2] = 100.0; // metals
+            resources[3] = 100.0; // crystals
+        }
+        for (slot, recipe) in [(0, "alloys"), (1, "coolant"), (1, "unobtainium"), (7, "bars")] {
+            state
+                .apply_command(SimulationCommand::SetRefineRecipe {
+                    factory_id: factory_id.clone(),
+                    slot,
+                    recipe: recipe.to_string(),
+                })
+                .expect("set recipe");
+        }
+
+        state.step(1.0);
+        let resources = state.get_factory_resources_mut().to_vec();
+        assert!(resources[2] < 100.0 && resources[3] < 100.0, "alloys consume metals and crystals");
+        assert!(resources[7] > 0.0, "alloys produced");
+        assert!(resources[8] > 0.0, "coolant produced");
+        assert!(resources[4] > 0.0, "organics by-product");
+        assert_eq!(resources[5], 0.0, "no ore, no bars");
+
+        let exported: SimulationSnapshot =
+            serde_json::from_str(&state.export_snapshot_str().expect("export")).expect("parse");
+        let factory = &exported.factories[0];
+        assert_eq!(factory.refine_recipes, vec!["alloys".to_string(), "coolant".to_string()]);
+        assert_eq!(factory.active_refines[0].ore_type, "ore");
+        assert_eq!(factory.active_refines[0].recipe, "alloys");
+
+        // Switching a busy slot scraps the batch and refunds what was still unrefined.
+        let ice_before = resources[1];
+        state
+            .apply_command(SimulationCommand::SetRefineRecipe {
+                factory_id,
+                slot: 1,
+                recipe: "bars".to_string(),
+            })
+            .expect("set recipe");
+        let resources = state.get_factory_resources_mut().to_vec();
+        assert!(resources[1] > ice_before);
+        let exported: SimulationSnapshot =
+            serde_json::from_str(&state.export_snapshot_str().expect("export")).expect("parse");
+        assert_eq!(exported.factories[0].active_refines.len(), 1);
+        assert_eq!(exported.factories[0].refine_recipes[1], "bars");
+    }
+
+    #[test]
+    fn research_gates_modules_and_upgrade_variants() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.bars = 1000.0;
+        snapshot.resources.crystals = 500.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        let buy = |state: &mut GameState, module_type: &str| {
+            state
+                .apply_command(SimulationCommand::BuyModule {
+                    module_type: module_type.to_string(),
+                    factory_id: None,
+                    cost_variant: None,
+                })
+                .expect("buy module");
+        };
+
+        buy(&mut state, "dataCore");
+        assert_eq!(state.snapshot().modules.data_core, 0, "needs research first");
+        buy(&mut state, "lab");
+        assert_eq!(state.snapshot().modules.lab, 1);
+
+        state
+            .apply_command(SimulationCommand::FundResearch { crystals: 500.0 })
+            .expect("fund");
+        assert_eq!(state.snapshot().resources.crystals, 0.0);
+        assert_eq!(state.snapshot().research.points, 50.0);
+
+        for node_id in ["refiningTheory", "lightweightFrames", "researchNetwork"] {
+            state
+                .apply_command(SimulationCommand::StartResearch { node_id: node_id.to_string() })
+                .expect("start research");
+            assert!(state.snapshot().research.active.is_some(), "{}", node_id);
+            // Labs keep producing offline, which also pays for the next node.
+            state.simulate_offline(400.0, 10.0).expect("offline");
+        }
+        assert_eq!(state.snapshot().research.completed.len(), 3);
+
+        buy(&mut state, "dataCore");
+        assert_eq!(state.snapshot().modules.data_core, 1);
+    }
+
+    #[test]
+    fn contracts_are_accepted_abandoned_and_expire_offline() {
+        let mut state = GameState::from_snapshot(sample_snapshot()).expect("should build state");
+        state.step(0.1);
+        let offers = state.snapshot().contracts.offers.clone();
+        assert_eq!(offers.len(), 1, "an offer posts on the first tick");
+
+        let id = offers[0].id.clone();
+        state
+            .apply_command(SimulationCommand::AcceptContract { contract_id: id.clone() })
+            .expect("accept");
+        assert!(state.snapshot().contracts.offers.is_empty());
+        assert_eq!(state.snapshot().contracts.active[0].id, id);
+
+        state
+            .apply_command(SimulationCommand::AbandonContract { contract_id: id.clone() })
+            .expect("abandon");
+        let events = state.drain_contract_events();
+        assert_eq!((events[0].contract_id.as_str(), events[0].outcome.as_str()), (id.as_str(), "abandoned"));
+
+        state.simulate_offline(60.0, 1.0).expect("offline");
+        let next = state.snapshot().contracts.offers[0].clone();
+        state
+            .apply_command(SimulationCommand::AcceptContract { contract_id: next.id.clone() })
+            .expect("accept");
+        state.simulate_offline(next.duration + 10.0, 5.0).expect("offline");
+        let events = state.drain_contract_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].contract_id.as_str(), events[0].outcome.as_str()), (next.id.as_str(), "expired"));
+        assert!(state.snapshot().contracts.active.is_empty());
+    }
+
+    #[test]
+    fn market_trades_pay_for_credit_purchases() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.bars = 200.0;
+        snapshot.resources.metals = 1000.0;
+        snapshot.resources.crystals = 1000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        let factory_id = state.snapshot().factories[0].id.clone();
+
+        state
+            .apply_command(SimulationCommand::Sell {
+                resource: "bars".to_string(),
+                amount: 100.0,
+                factory_id: None,
+            })
+            .expect("sell");
+        let earned = state.snapshot().resources.credits;
+        assert_eq!(state.snapshot().resources.bars, 100.0);
+        assert!(earned > 0.0 && earned < 100.0 * 12.0, "price slides as volume lands");
+
+        state
+            .apply_command(SimulationCommand::UpdateResources(Resources {
+                credits: 10_000.0,
+                ..state.snapshot().resources.clone()
+            }))
+            .expect("update");
+        let scanner = state.snapshot().modules.scanner;
+        state
+            .apply_command(SimulationCommand::BuyModule {
+                module_type: "scanner".to_string(),
+                factory_id: None,
+                cost_variant: Some("credits".to_string()),
+            })
+            .expect("buy module");
+        assert_eq!(state.snapshot().modules.scanner, scanner + 1);
+        assert_eq!(state.snapshot().resources.bars, 100.0, "bars untouched");
+        let after_module = state.snapshot().resources.credits;
+        assert!(after_module < 10_000.0);
+
+        let docking = state.snapshot().factories[0].upgrades.docking;
+        state
+            .apply_command(SimulationCommand::PurchaseFactoryUpgrade {
+                factory_id,
+                upgrade_type: "docking".to_string(),
+                cost_variant: Some("credits".to_string()),
+            })
+            .expect("upgrade");
+        assert_eq!(state.snapshot().factories[0].upgrades.docking, docking + 1);
+        assert!(state.snapshot().resources.credits < after_module);
+
+        let credits = state.snapshot().resources.credits;
+        state
+            .apply_command(SimulationCommand::Buy {
+                resource: "metals".to_string(),
+                amount: 10.0,
+            })
+            .expect("buy");
+        assert_eq!(state.snapshot().resources.metals, 1010.0 - 100.0);
+        assert!(state.snapshot().resources.credits < credits);
+        let metals_price = state.market_prices()["metals"];
+        assert!(metals_price > 3.0, "buying pushed metals above base");
+        assert_eq!(state.market_prices()["ice"], 1.5);
+
+        // Purchases stop at the warehouse's free space and only what fits is charged.
+        let capacity = {
+            let snapshot = state.snapshot();
+            let modifiers = get_resource_modifiers(
+                &snapshot.resources,
+                snapshot.prestige.cores,
+                snapshot.prestige_investments.as_ref(),
+                snapshot.spec_techs.as_ref(),
+                &snapshot.research.completed,
+            );
+            compute_warehouse_capacity(&snapshot.modules, &modifiers)
+        };
+        state
+            .apply_command(SimulationCommand::UpdateResources(Resources {
+                credits: 1_000_000.0,
+                ..state.snapshot().resources.clone()
+            }))
+            .expect("update");
+        let credits = state.snapshot().resources.credits;
+        let expected_cost = market::buy(
+            &mut state.snapshot().market.clone(),
+            market::market_good("ice").unwrap(),
+            capacity,
+            credits,
+        )
+        .expect("affordable");
+        state
+            .apply_command(SimulationCommand::Buy {
+                resource: "ice".to_string(),
+                amount: capacity * 10.0,
+            })
+            .expect("buy");
+        assert_eq!(state.snapshot().resources.ice, capacity);
+        assert_eq!(state.snapshot().resources.credits, credits - expected_cost);
+
+        for (resource, reserve) in [("ore", Some(25.0)), ("alloys", Some(5.0))] {
+            state
+                .apply_command(SimulationCommand::SetAutoSell {
+                    resource: resource.to_string(),
+                    reserve,
+                })
+                .expect("auto sell");
+        }
+        let orders: Vec<&String> = state.snapshot().market.auto_sell.keys().collect();
+        assert_eq!(orders, vec!["ore"], "the warehouse can't hold alloys");
+    }
+
+    #[test]
+    fn reassigned_drones_stay_with_their_new_factory() {
+        let mut snapshot = sample_snapshot();
+        snapshot.modules.drone_bay = 3;
+        snapshot.resources.metals = 5000.0;
+        snapshot.resources.crystals = 5000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        let factory_ids: Vec<String> =
+            state.snapshot().factories.iter().map(|f| f.id.clone()).collect();
+        let owners = |state: &GameState| {
+            state
+                .layout
+                .drones
+                .owner_factory_index
+                .as_f32_slice(&state.data)
+                .unwrap()
+                .to_vec()
+        };
+
+        for drone_id in state.drone_ids().to_vec() {
+            state
+                .apply_command(SimulationCommand::ReassignDrone {
+                    drone_id,
+                    factory_id: factory_ids[0].clone(),
+                })
+                .expect("reassign");
+        }
+        assert_eq!(owners(&state), vec![0.0, 0.0, 0.0]);
+        assert_eq!(state.snapshot().assigned_drones.len(), 3);
+
+        let mut settings = state.snapshot().settings.clone();
+        settings.auto_balance_drones = true;
+        state.apply_command(SimulationCommand::SetSettings(settings)).expect("settings");
+        state.step(balance::DRONE_BALANCE_INTERVAL);
+        assert_eq!(owners(&state).iter().filter(|&&o| o == 1.0).count(), 1);
+
+        state
+            .apply_command(SimulationCommand::RebalanceDrones {
+                from_factory_id: factory_ids[0].clone(),
+                to_factory_id: factory_ids[1].clone(),
+                count: 5,
+            })
+            .expect("rebalance");
+        assert_eq!(owners(&state), vec![1.0, 1.0, 1.0]);
+        assert!(state
+            .snapshot()
+            .drone_owners
+            .values()
+            .all(|owner| owner.as_deref() == Some(factory_ids[1].as_str())));
+        assert!(state.snapshot().factories[0].queued_drones.is_empty());
+
+        // Loaded drones head back to their assigned owner instead of the least busy dock.
+        let mut settings = state.snapshot().settings.clone();
+        settings.auto_balance_drones = false;
+        state.apply_command(SimulationCommand::SetSettings(settings)).expect("settings");
+        for _ in 0..600 {
+            state.step(0.1);
+        }
+        assert_eq!(owners(&state), vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn reassigned_drones_queue_for_a_dock_and_can_be_released() {
+        let mut snapshot = sample_snapshot();
+        snapshot.modules.drone_bay = 2;
+        snapshot.resources.metals = 5000.0;
+        snapshot.resources.crystals = 5000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        state.apply_command(SimulationCommand::BuildFactory).expect("build factory");
+        let target_id = state.snapshot().factories[1].id.clone();
+        state.snapshot.factories[1].docking_capacity = 1;
+        state.snapshot.factories[1].queued_drones = vec!["parked".to_string()];
+        state.generate_asteroid_field(11).expect("generate field");
+
+        let mut returning = None;
+        for _ in 0..2000 {
+            state.step(0.1);
+            returning = state
+                .snapshot()
+                .drone_flights
+                .iter()
+                .find(|flight| {
+                    flight.state == "returning"
+                        && flight.target_factory_id.as_deref() != Some(target_id.as_str())
+                })
+                .map(|flight| flight.drone_id.clone());
+            if returning.is_some() {
+                break;
+            }
+        }
+        let drone_id = returning.expect("a drone heads home");
+        state
+            .apply_command(SimulationCommand::ReassignDrone {
+                drone_id: drone_id.clone(),
+                factory_id: target_id.clone(),
+            })
+            .expect("reassign");
+        state.step(0.1);
+
+        // The only dock is taken, so the drone waits its turn instead of docking anyway.
+        let queue = &state.snapshot().factories[1].queued_drones;
+        assert_eq!(queue, &vec!["parked".to_string(), drone_id.clone()]);
+        let flight = state
+            .snapshot()
+            .drone_flights
+            .iter()
+            .find(|flight| flight.drone_id == drone_id);
+        assert!(flight.is_none_or(|flight| flight.state == "queued"));
+
+        state
+            .apply_command(SimulationCommand::UnassignDrone {
+                drone_id: drone_id.clone(),
+            })
+            .expect("unassign");
+        assert!(state.snapshot().assigned_drones.is_empty());
+
+        let other = state.drone_ids().iter().find(|id| **id != drone_id).cloned().unwrap();
+        state
+            .apply_command(SimulationCommand::ReassignDrone {
+                drone_id: other.clone(),
+                factory_id: target_id,
+            })
+            .expect("reassign");
+        assert!(state.remove_drone(&other));
+        assert!(state.snapshot().assigned_drones.is_empty());
+    }
+
+    #[test]
+    fn prestige_resets_run_and_keeps_investments() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.bars = 8000.0;
+        snapshot.resources.metals = 200.0;
+        snapshot.resources.crystals = 100.0;
+        snapshot.prestige_investments = Some(serde_json::json!({ "droneVelocity": 2 }));
+        snapshot.game_time = 120.0;
+        snapshot.factories = vec![crate::factories::create_starter_factory("hq".to_string())];
+        snapshot.market.prices.insert("ore".to_string(), 9.0);
+        snapshot.market.auto_sell.insert("bars".to_string(), 100.0);
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        state.game_time = 120.0;
+        state
+            .apply_command(SimulationCommand::BuildFactory)
+            .expect("build factory");
+
+        let below = GameState::from_snapshot(sample_snapshot())
+            .expect("should build state")
+            .prestige()
+            .expect("prestige");
+        assert!(below.is_none());
+
+        let summary = state.prestige().expect("prestige").expect("eligible");
+        assert_eq!(summary.bars_converted, 8000.0);
+        assert_eq!(summary.cores_gained, GameState::compute_prestige_gain(8000.0));
+        assert_eq!(summary.run_time, 120.0);
+        assert_eq!(summary.factories_reset, 2);
+        assert_eq!(summary.asteroids_generated, ASTEROID_FIELD_TARGET);
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.resources.bars, 0.0);
+        assert_eq!(snapshot.game_time, 0.0);
+        assert_eq!(snapshot.factories.len(), 1);
+        assert_eq!(snapshot.factories[0].id, "hq");
+        assert_eq!(snapshot.selected_factory_id.as_deref(), Some("hq"));
+        assert_eq!(snapshot.factories[0].resources.ore, 50.0);
+        assert_eq!(snapshot.market, Default::default());
+        assert_eq!(state.drone_ids().len(), 1);
+        let starter_drone = &state.drone_ids()[0];
+        assert_eq!(
+            snapshot.drone_owners.get(starter_drone),
+            Some(&Some("hq".to_string()))
+        );
+        let owner_base = state.layout.drones.owner_factory_index.offset_bytes / 4;
+        assert_eq!(f32::from_bits(state.data[owner_base]), 0.0);
+        assert_eq!(state.asteroid_ids().len(), ASTEROID_FIELD_TARGET);
+        assert_eq!(state.layout.asteroids.count(), ASTEROID_FIELD_TARGET);
+        assert!(!snapshot.asteroid_hazards.is_empty());
+        assert!(snapshot
+            .asteroid_hazards
+            .iter()
+            .all(|h| state.asteroid_ids().contains(&h.asteroid_id)));
+        assert_eq!(snapshot.prestige_investments.as_ref().unwrap()["droneVelocity"], 2);
+        assert_eq!(snapshot.spec_tech_spent.as_ref().unwrap()["metals"], 0);
+        assert_eq!(snapshot.lifetime_stats.as_ref().unwrap().prestiges, 1);
+        assert!(snapshot
+            .logistics_queues
+            .as_ref()
+            .unwrap()
+            .pending_transfers
+            .is_empty());
+    }
+
+    #[test]
+    fn spec_tech_requires_unlock_and_investments_count_toward_it() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.metals = 100_000.0;
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+        let purchase = SimulationCommand::PurchaseSpecTech {
+            tech_id: "oreMagnet".to_string(),
+        };
+
+        state.apply_command(purchase.clone()).expect("purchase");
+        assert_eq!(crate::sinks::json_level(&state.snapshot().spec_techs, "oreMagnet"), 0.0);
+
+        // Tiers cost 1000, 1500, 2250, ... metals; spend past the 50k unlock.
+        for _ in 0..9 {
+            state
+                .apply_command(SimulationCommand::InvestPrestige {
+                    investment_id: "droneVelocity".to_string(),
+                })
+                .expect("invest");
+        }
+        let tiers = crate::sinks::json_level(&state.snapshot().prestige_investments, "droneVelocity");
+        assert_eq!(tiers, 9.0);
+        assert!(crate::sinks::json_level(&state.snapshot().spec_tech_spent, "metals") >= 50_000.0);
+        let metals_before = state.snapshot().resources.metals;
+
+        state.apply_command(purchase).expect("purchase");
+        assert_eq!(crate::sinks::json_level(&state.snapshot().spec_techs, "oreMagnet"), 1.0);
+        assert_eq!(state.snapshot().resources.metals, metals_before - 8_000.0);
+        assert!(crate::sinks::get_sink_bonuses(state.snapshot()).ore_yield_multiplier > 1.0);
+    }
+
+    #[test]
+    fn prestige_investment_is_refused_at_its_max_tier() {
+        let mut snapshot = sample_snapshot();
+        snapshot.resources.metals = 1e9;
+        snapshot.prestige_investments = Some(serde_json::json!({ "droneVelocity": 20 }));
+        let mut state = GameState::from_snapshot(snapshot).expect("should build state");
+
+        state
+            .apply_command(SimulationCommand::InvestPrestige {
+                investment_id: "droneVelocity".to_string(),
+            })
+            .expect("invest");
+
+        let tiers = crate::sinks::json_level(&state.snapshot().prestige_investments, "droneVelocity");
+        assert_eq!(tiers, 20.0);
+        assert_eq!(state.snapshot().resources.metals, 1e9);
     }
 }
@@ -5,12 +5,20 @@ use crate::error::SimulationError;
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BufferSection {
     pub offset_bytes: usize,
+    /// Live element count (entities * stride); systems only see this many floats.
     pub length: usize,
+    /// Reserved element count; spare slots beyond `length` let entities spawn in place.
+    #[serde(default)]
+    pub capacity: usize,
+    /// Floats per entity.
+    #[serde(default)]
+    pub stride: usize,
 }
 
 impl BufferSection {
     fn advance(&self, stride_bytes: usize) -> Result<usize, SimulationError> {
-        self.length
+        self.capacity
+            .max(self.length)
             .checked_mul(stride_bytes)
             .and_then(|delta| self.offset_bytes.checked_add(delta))
             .ok_or_else(|| {
@@ -49,6 +57,35 @@ impl BufferSection {
     }
 }
 
+impl BufferSection {
+    pub fn entity_capacity(&self) -> usize {
+        self.capacity.max(self.length) / self.stride.max(1)
+    }
+
+    pub fn set_entity_count(&mut self, count: usize) {
+        self.length = count * self.stride;
+    }
+
+    /// Zeroes the floats belonging to entity `index`.
+    pub fn clear_entity(&self, data: &mut [u32], index: usize) {
+        let start = self.offset_bytes / 4 + index * self.stride;
+        data[start..start + self.stride].fill(0);
+    }
+
+    /// Removes entity `index`, shifting later entities down one slot and zeroing the freed tail.
+    /// Order is preserved so index-ordered iteration (and RNG consumption) stays stable.
+    pub fn remove_entity(&self, data: &mut [u32], index: usize) {
+        let base = self.offset_bytes / 4;
+        let end = base + self.length;
+        let start = base + index * self.stride;
+        if start + self.stride > end {
+            return;
+        }
+        data.copy_within(start + self.stride..end, start);
+        data[end - self.stride..end].fill(0);
+    }
+}
+
 /// Cast a &mut [u32] to &mut [f32] safely.
 /// f32 and u32 have the same size (4 bytes) and alignment requirements.
 #[inline]
@@ -81,6 +118,10 @@ pub struct DroneBuffers {
     pub target_asteroid_index: BufferSection,
     pub target_region_index: BufferSection,
     pub charging: BufferSection,
+    /// Cruise speed after modules, modifiers and the drone's loadout.
+    pub speed: BufferSection,
+    /// Hull integrity, 0 (disabled) to `DRONE_MAX_INTEGRITY`.
+    pub integrity: BufferSection,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -91,8 +132,101 @@ pub struct AsteroidBuffers {
     pub resource_profile: BufferSection, // 5 floats per asteroid
 }
 
+impl DroneBuffers {
+    pub fn sections(&self) -> [&BufferSection; 16] {
+        [
+            &self.positions,
+            &self.velocities,
+            &self.states,
+            &self.cargo,
+            &self.battery,
+            &self.max_battery,
+            &self.capacity,
+            &self.mining_rate,
+            &self.cargo_profile,
+            &self.target_factory_index,
+            &self.owner_factory_index,
+            &self.target_asteroid_index,
+            &self.target_region_index,
+            &self.charging,
+            &self.speed,
+            &self.integrity,
+        ]
+    }
+
+    pub fn sections_mut(&mut self) -> [&mut BufferSection; 16] {
+        [
+            &mut self.positions,
+            &mut self.velocities,
+            &mut self.states,
+            &mut self.cargo,
+            &mut self.battery,
+            &mut self.max_battery,
+            &mut self.capacity,
+            &mut self.mining_rate,
+            &mut self.cargo_profile,
+            &mut self.target_factory_index,
+            &mut self.owner_factory_index,
+            &mut self.target_asteroid_index,
+            &mut self.target_region_index,
+            &mut self.charging,
+            &mut self.speed,
+            &mut self.integrity,
+        ]
+    }
+
+    pub fn count(&self) -> usize {
+        self.states.length
+    }
+}
+
+impl AsteroidBuffers {
+    pub fn sections(&self) -> [&BufferSection; 4] {
+        [&self.positions, &self.ore_remaining, &self.max_ore, &self.resource_profile]
+    }
+
+    pub fn sections_mut(&mut self) -> [&mut BufferSection; 4] {
+        [
+            &mut self.positions,
+            &mut self.ore_remaining,
+            &mut self.max_ore,
+            &mut self.resource_profile,
+        ]
+    }
+
+    pub fn count(&self) -> usize {
+        self.ore_remaining.length
+    }
+}
+
 pub const MAX_REFINE_SLOTS: usize = 16;
 
+/// Bumped whenever section order, strides or component meaning change.
+pub const LAYOUT_VERSION: u32 = 8;
+
+pub const POSITION_STRIDE: usize = 3;
+pub const VELOCITY_STRIDE: usize = 3;
+pub const ORIENTATION_STRIDE: usize = 4;
+pub const CARGO_PROFILE_STRIDE: usize = 5;
+pub const RESOURCE_PROFILE_STRIDE: usize = 5;
+pub const FACTORY_RESOURCE_STRIDE: usize = 9;
+pub const FACTORY_UPGRADE_STRIDE: usize = 7;
+pub const REFINE_SLOT_STRIDE: usize = 4;
+pub const REFINERY_STATE_STRIDE: usize = MAX_REFINE_SLOTS * REFINE_SLOT_STRIDE;
+pub const GLOBAL_RESOURCE_STRIDE: usize = 8;
+pub const HAULER_ROUTE_STRIDE: usize = 2;
+
+const XYZ: &[&str] = &["x", "y", "z"];
+const ORE_PROFILE: &[&str] = &["ore", "ice", "metals", "crystals", "organics"];
+pub const FACTORY_RESOURCES: &[&str] = &[
+    "ore", "ice", "metals", "crystals", "organics", "bars", "credits", "alloys", "coolant",
+];
+const FACTORY_UPGRADES: &[&str] = &["docking", "refine", "storage", "energy", "solar", "unload", "cooler"];
+const REFINE_SLOT_FIELDS: &[&str] = &["active", "amount", "progress", "speed"];
+const GLOBAL_RESOURCES: &[&str] = &[
+    "ore", "ice", "metals", "crystals", "organics", "bars", "energy", "credits",
+];
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FactoryBuffers {
     pub positions: BufferSection,
@@ -103,7 +237,79 @@ pub struct FactoryBuffers {
     pub max_energy: BufferSection,
     pub upgrades: BufferSection,
     pub refinery_state: BufferSection,
+    pub refine_recipes: BufferSection,
     pub haulers_assigned: BufferSection,
+    pub heat: BufferSection,
+    pub cooling: BufferSection,
+}
+
+impl FactoryBuffers {
+    pub fn sections(&self) -> [&BufferSection; 12] {
+        [
+            &self.positions,
+            &self.orientations,
+            &self.activity,
+            &self.resources,
+            &self.energy,
+            &self.max_energy,
+            &self.upgrades,
+            &self.refinery_state,
+            &self.refine_recipes,
+            &self.haulers_assigned,
+            &self.heat,
+            &self.cooling,
+        ]
+    }
+
+    pub fn sections_mut(&mut self) -> [&mut BufferSection; 12] {
+        [
+            &mut self.positions,
+            &mut self.orientations,
+            &mut self.activity,
+            &mut self.resources,
+            &mut self.energy,
+            &mut self.max_energy,
+            &mut self.upgrades,
+            &mut self.refinery_state,
+            &mut self.refine_recipes,
+            &mut self.haulers_assigned,
+            &mut self.heat,
+            &mut self.cooling,
+        ]
+    }
+
+    pub fn count(&self) -> usize {
+        self.activity.length
+    }
+}
+
+/// One slot per scheduled logistics transfer, in `pending_transfers` order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HaulerBuffers {
+    pub positions: BufferSection,
+    pub velocities: BufferSection,
+    pub cargo: BufferSection,
+    /// Source and destination factory indices; -1 is the warehouse.
+    pub route: BufferSection,
+}
+
+impl HaulerBuffers {
+    pub fn sections(&self) -> [&BufferSection; 4] {
+        [&self.positions, &self.velocities, &self.cargo, &self.route]
+    }
+
+    pub fn sections_mut(&mut self) -> [&mut BufferSection; 4] {
+        [
+            &mut self.positions,
+            &mut self.velocities,
+            &mut self.cargo,
+            &mut self.route,
+        ]
+    }
+
+    pub fn count(&self) -> usize {
+        self.cargo.length
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -116,202 +322,310 @@ pub struct EntityBufferLayout {
     pub drones: DroneBuffers,
     pub asteroids: AsteroidBuffers,
     pub factories: FactoryBuffers,
+    pub haulers: HaulerBuffers,
     pub globals: GlobalBuffers,
     pub total_size_bytes: usize,
 }
 
+/// Per-section metadata so JS can build typed views without hardcoded strides.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SectionDescriptor {
+    pub name: String,
+    pub offset_bytes: usize,
+    pub length: usize,
+    pub capacity: usize,
+    pub dtype: String,
+    pub stride: usize,
+    pub components: Vec<String>,
+    pub units: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LayoutDescriptor {
+    pub version: u32,
+    pub total_size_bytes: usize,
+    pub sections: Vec<SectionDescriptor>,
+}
+
+fn describe_section(
+    name: &str,
+    section: &BufferSection,
+    components: &[&str],
+    units: &str,
+) -> SectionDescriptor {
+    SectionDescriptor {
+        name: name.to_string(),
+        offset_bytes: section.offset_bytes,
+        length: section.length,
+        capacity: section.capacity.max(section.length),
+        dtype: "f32".to_string(),
+        stride: components.len(),
+        components: components.iter().map(|c| c.to_string()).collect(),
+        units: units.to_string(),
+    }
+}
+
+fn refinery_slot_components() -> Vec<String> {
+    (0..MAX_REFINE_SLOTS)
+        .flat_map(|slot| {
+            REFINE_SLOT_FIELDS
+                .iter()
+                .map(move |field| format!("slot{}.{}", slot, field))
+        })
+        .collect()
+}
+
+impl EntityBufferLayout {
+    /// All sections in buffer order.
+    pub fn sections(&self) -> Vec<&BufferSection> {
+        let mut sections: Vec<&BufferSection> = Vec::new();
+        sections.extend(self.drones.sections());
+        sections.extend(self.asteroids.sections());
+        sections.extend(self.factories.sections());
+        sections.extend(self.haulers.sections());
+        sections.push(&self.globals.resources);
+        sections
+    }
+
+    pub fn counts(&self) -> EntityCounts {
+        EntityCounts::new(
+            self.drones.count(),
+            self.asteroids.count(),
+            self.factories.count(),
+            self.haulers.count(),
+        )
+    }
+
+    pub fn capacities(&self) -> EntityCounts {
+        EntityCounts::new(
+            self.drones.states.entity_capacity(),
+            self.asteroids.ore_remaining.entity_capacity(),
+            self.factories.activity.entity_capacity(),
+            self.haulers.cargo.entity_capacity(),
+        )
+    }
+
+    /// Describes every section in buffer order (dtype, stride, component names, units).
+    pub fn describe(&self) -> LayoutDescriptor {
+        let d = &self.drones;
+        let a = &self.asteroids;
+        let f = &self.factories;
+        let h = &self.haulers;
+
+        let mut refinery_state = describe_section("factories.refinery_state", &f.refinery_state, &[], "mixed");
+        refinery_state.components = refinery_slot_components();
+        refinery_state.stride = REFINERY_STATE_STRIDE;
+
+        let mut refine_recipes =
+            describe_section("factories.refine_recipes", &f.refine_recipes, &[], "index:recipes");
+        refine_recipes.components = (0..MAX_REFINE_SLOTS).map(|slot| format!("slot{}", slot)).collect();
+        refine_recipes.stride = MAX_REFINE_SLOTS;
+
+        let sections = vec![
+            describe_section("drones.positions", &d.positions, XYZ, "world_units"),
+            describe_section("drones.velocities", &d.velocities, XYZ, "world_units_per_second"),
+            describe_section("drones.states", &d.states, &["state"], "enum:drone_state"),
+            describe_section("drones.cargo", &d.cargo, &["amount"], "ore_units"),
+            describe_section("drones.battery", &d.battery, &["energy"], "energy_units"),
+            describe_section("drones.max_battery", &d.max_battery, &["energy"], "energy_units"),
+            describe_section("drones.capacity", &d.capacity, &["amount"], "ore_units"),
+            describe_section("drones.mining_rate", &d.mining_rate, &["rate"], "ore_units_per_second"),
+            describe_section("drones.cargo_profile", &d.cargo_profile, ORE_PROFILE, "ore_units"),
+            describe_section("drones.target_factory_index", &d.target_factory_index, &["index"], "index:factories"),
+            describe_section("drones.owner_factory_index", &d.owner_factory_index, &["index"], "index:factories"),
+            describe_section("drones.target_asteroid_index", &d.target_asteroid_index, &["index"], "index:asteroids"),
+            describe_section("drones.target_region_index", &d.target_region_index, &["index"], "index:regions"),
+            describe_section("drones.charging", &d.charging, &["flag"], "bool"),
+            describe_section("drones.speed", &d.speed, &["speed"], "world_units_per_second"),
+            describe_section("drones.integrity", &d.integrity, &["integrity"], "integrity_points"),
+            describe_section("asteroids.positions", &a.positions, XYZ, "world_units"),
+            describe_section("asteroids.ore_remaining", &a.ore_remaining, &["amount"], "ore_units"),
+            describe_section("asteroids.max_ore", &a.max_ore, &["amount"], "ore_units"),
+            describe_section("asteroids.resource_profile", &a.resource_profile, ORE_PROFILE, "fraction"),
+            describe_section("factories.positions", &f.positions, XYZ, "world_units"),
+            describe_section("factories.orientations", &f.orientations, &["x", "y", "z", "w"], "quaternion"),
+            describe_section("factories.activity", &f.activity, &["level"], "fraction"),
+            describe_section("factories.resources", &f.resources, FACTORY_RESOURCES, "resource_units"),
+            describe_section("factories.energy", &f.energy, &["energy"], "energy_units"),
+            describe_section("factories.max_energy", &f.max_energy, &["energy"], "energy_units"),
+            describe_section("factories.upgrades", &f.upgrades, FACTORY_UPGRADES, "level"),
+            refinery_state,
+            refine_recipes,
+            describe_section("factories.haulers_assigned", &f.haulers_assigned, &["count"], "count"),
+            describe_section("factories.heat", &f.heat, &["heat"], "heat_units"),
+            describe_section("factories.cooling", &f.cooling, &["flag"], "bool"),
+            describe_section("haulers.positions", &h.positions, XYZ, "world_units"),
+            describe_section("haulers.velocities", &h.velocities, XYZ, "world_units_per_second"),
+            describe_section("haulers.cargo", &h.cargo, &["amount"], "resource_units"),
+            describe_section("haulers.route", &h.route, &["from", "to"], "index:factories"),
+            describe_section("globals.resources", &self.globals.resources, GLOBAL_RESOURCES, "resource_units"),
+        ];
+
+        LayoutDescriptor {
+            version: LAYOUT_VERSION,
+            total_size_bytes: self.total_size_bytes,
+            sections,
+        }
+    }
+}
+
+/// Live entity counts (or reserved capacities) per entity group.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EntityCounts {
+    pub drones: usize,
+    pub asteroids: usize,
+    pub factories: usize,
+    #[serde(default)]
+    pub haulers: usize,
+}
+
+impl EntityCounts {
+    pub fn new(drones: usize, asteroids: usize, factories: usize, haulers: usize) -> Self {
+        Self {
+            drones,
+            asteroids,
+            factories,
+            haulers,
+        }
+    }
+
+    /// Adds spare slots so a handful of spawns fit without reallocating `data`.
+    pub fn with_spare(&self) -> Self {
+        Self {
+            drones: capacity_with_spare(self.drones),
+            asteroids: capacity_with_spare(self.asteroids),
+            factories: capacity_with_spare(self.factories),
+            haulers: capacity_with_spare(self.haulers),
+        }
+    }
+}
+
+const MIN_SPARE_SLOTS: usize = 4;
+
+pub fn capacity_with_spare(count: usize) -> usize {
+    count + (count / 4).max(MIN_SPARE_SLOTS)
+}
+
+struct SectionPlanner {
+    offset: usize,
+}
+
+impl SectionPlanner {
+    fn next(
+        &mut self,
+        count: usize,
+        capacity: usize,
+        stride: usize,
+    ) -> Result<BufferSection, SimulationError> {
+        let capacity = capacity.max(count);
+        let section = BufferSection {
+            offset_bytes: self.offset,
+            length: count * stride,
+            capacity: capacity * stride,
+            stride,
+        };
+        self.offset = section.advance(4)?;
+        Ok(section)
+    }
+}
+
 pub fn plan_layout(
     drone_count: usize,
     asteroid_count: usize,
     factory_count: usize,
 ) -> Result<EntityBufferLayout, SimulationError> {
-    let mut offset = 0usize;
+    let counts = EntityCounts::new(drone_count, asteroid_count, factory_count, 0);
+    plan_layout_with_capacity(counts, counts)
+}
 
-    let drone_positions = BufferSection {
-        offset_bytes: offset,
-        length: drone_count * 3,
-    };
-    offset = drone_positions.advance(4)?;
-    let drone_velocities = BufferSection {
-        offset_bytes: offset,
-        length: drone_count * 3,
-    };
-    offset = drone_velocities.advance(4)?;
-    let drone_states = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_states.advance(4)?;
-    let drone_cargo = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_cargo.advance(4)?;
-    let drone_battery = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_battery.advance(4)?;
-    let drone_max_battery = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_max_battery.advance(4)?;
-    let drone_capacity = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_capacity.advance(4)?;
-    let drone_mining_rate = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_mining_rate.advance(4)?;
-    let drone_cargo_profile = BufferSection {
-        offset_bytes: offset,
-        length: drone_count * 5,
-    };
-    offset = drone_cargo_profile.advance(4)?;
-    let drone_target_factory_index = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_target_factory_index.advance(4)?;
-    let drone_owner_factory_index = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_owner_factory_index.advance(4)?;
-    let drone_target_asteroid_index = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_target_asteroid_index.advance(4)?;
-    let drone_target_region_index = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_target_region_index.advance(4)?;
-    let drone_charging = BufferSection {
-        offset_bytes: offset,
-        length: drone_count,
-    };
-    offset = drone_charging.advance(4)?;
+/// Plans a layout whose sections reserve `capacity` slots but expose `counts` live entities.
+pub fn plan_layout_with_capacity(
+    counts: EntityCounts,
+    capacity: EntityCounts,
+) -> Result<EntityBufferLayout, SimulationError> {
+    let mut planner = SectionPlanner { offset: 0 };
+    let (d, dc) = (counts.drones, capacity.drones);
+    let (a, ac) = (counts.asteroids, capacity.asteroids);
+    let (f, fc) = (counts.factories, capacity.factories);
+    let (h, hc) = (counts.haulers, capacity.haulers);
 
-    let asteroid_positions = BufferSection {
-        offset_bytes: offset,
-        length: asteroid_count * 3,
+    let drones = DroneBuffers {
+        positions: planner.next(d, dc, POSITION_STRIDE)?,
+        velocities: planner.next(d, dc, VELOCITY_STRIDE)?,
+        states: planner.next(d, dc, 1)?,
+        cargo: planner.next(d, dc, 1)?,
+        battery: planner.next(d, dc, 1)?,
+        max_battery: planner.next(d, dc, 1)?,
+        capacity: planner.next(d, dc, 1)?,
+        mining_rate: planner.next(d, dc, 1)?,
+        cargo_profile: planner.next(d, dc, CARGO_PROFILE_STRIDE)?,
+        target_factory_index: planner.next(d, dc, 1)?,
+        owner_factory_index: planner.next(d, dc, 1)?,
+        target_asteroid_index: planner.next(d, dc, 1)?,
+        target_region_index: planner.next(d, dc, 1)?,
+        charging: planner.next(d, dc, 1)?,
+        speed: planner.next(d, dc, 1)?,
+        integrity: planner.next(d, dc, 1)?,
     };
-    offset = asteroid_positions.advance(4)?;
-    let asteroid_ore_remaining = BufferSection {
-        offset_bytes: offset,
-        length: asteroid_count,
-    };
-    offset = asteroid_ore_remaining.advance(4)?;
-    let asteroid_max_ore = BufferSection {
-        offset_bytes: offset,
-        length: asteroid_count,
-    };
-    offset = asteroid_max_ore.advance(4)?;
-    let asteroid_resource_profile = BufferSection {
-        offset_bytes: offset,
-        length: asteroid_count * 5,
-    };
-    offset = asteroid_resource_profile.advance(4)?;
 
-    let factory_positions = BufferSection {
-        offset_bytes: offset,
-        length: factory_count * 3,
-    };
-    offset = factory_positions.advance(4)?;
-    let factory_orientations = BufferSection {
-        offset_bytes: offset,
-        length: factory_count * 4,
+    let asteroids = AsteroidBuffers {
+        positions: planner.next(a, ac, POSITION_STRIDE)?,
+        ore_remaining: planner.next(a, ac, 1)?,
+        max_ore: planner.next(a, ac, 1)?,
+        resource_profile: planner.next(a, ac, RESOURCE_PROFILE_STRIDE)?,
     };
-    offset = factory_orientations.advance(4)?;
-    let factory_activity = BufferSection {
-        offset_bytes: offset,
-        length: factory_count,
-    };
-    offset = factory_activity.advance(4)?;
-    let factory_resources = BufferSection {
-        offset_bytes: offset,
-        length: factory_count * 7,
-    };
-    offset = factory_resources.advance(4)?;
-    let factory_energy = BufferSection {
-        offset_bytes: offset,
-        length: factory_count,
-    };
-    offset = factory_energy.advance(4)?;
-    let factory_max_energy = BufferSection {
-        offset_bytes: offset,
-        length: factory_count,
-    };
-    offset = factory_max_energy.advance(4)?;
-    let factory_upgrades = BufferSection {
-        offset_bytes: offset,
-        length: factory_count * 5,
-    };
-    offset = factory_upgrades.advance(4)?;
-    let factory_refinery_state = BufferSection {
-        offset_bytes: offset,
-        length: factory_count * MAX_REFINE_SLOTS * 4,
+
+    let factories = FactoryBuffers {
+        positions: planner.next(f, fc, POSITION_STRIDE)?,
+        orientations: planner.next(f, fc, ORIENTATION_STRIDE)?,
+        activity: planner.next(f, fc, 1)?,
+        resources: planner.next(f, fc, FACTORY_RESOURCE_STRIDE)?,
+        energy: planner.next(f, fc, 1)?,
+        max_energy: planner.next(f, fc, 1)?,
+        upgrades: planner.next(f, fc, FACTORY_UPGRADE_STRIDE)?,
+        refinery_state: planner.next(f, fc, REFINERY_STATE_STRIDE)?,
+        refine_recipes: planner.next(f, fc, MAX_REFINE_SLOTS)?,
+        haulers_assigned: planner.next(f, fc, 1)?,
+        heat: planner.next(f, fc, 1)?,
+        cooling: planner.next(f, fc, 1)?,
     };
-    offset = factory_refinery_state.advance(4)?;
-    let factory_haulers_assigned = BufferSection {
-        offset_bytes: offset,
-        length: factory_count,
+
+    let haulers = HaulerBuffers {
+        positions: planner.next(h, hc, POSITION_STRIDE)?,
+        velocities: planner.next(h, hc, VELOCITY_STRIDE)?,
+        cargo: planner.next(h, hc, 1)?,
+        route: planner.next(h, hc, HAULER_ROUTE_STRIDE)?,
     };
-    offset = factory_haulers_assigned.advance(4)?;
 
-    let global_resources = BufferSection {
-        offset_bytes: offset,
-        length: 8,
+    let globals = GlobalBuffers {
+        resources: planner.next(1, 1, GLOBAL_RESOURCE_STRIDE)?,
     };
-    offset = global_resources.advance(4)?;
 
     Ok(EntityBufferLayout {
-        drones: DroneBuffers {
-            positions: drone_positions,
-            velocities: drone_velocities,
-            states: drone_states,
-            cargo: drone_cargo,
-            battery: drone_battery,
-            max_battery: drone_max_battery,
-            capacity: drone_capacity,
-            mining_rate: drone_mining_rate,
-            cargo_profile: drone_cargo_profile,
-            target_factory_index: drone_target_factory_index,
-            owner_factory_index: drone_owner_factory_index,
-            target_asteroid_index: drone_target_asteroid_index,
-            target_region_index: drone_target_region_index,
-            charging: drone_charging,
-        },
-        asteroids: AsteroidBuffers {
-            positions: asteroid_positions,
-            ore_remaining: asteroid_ore_remaining,
-            max_ore: asteroid_max_ore,
-            resource_profile: asteroid_resource_profile,
-        },
-        factories: FactoryBuffers {
-            positions: factory_positions,
-            orientations: factory_orientations,
-            activity: factory_activity,
-            resources: factory_resources,
-            energy: factory_energy,
-            max_energy: factory_max_energy,
-            upgrades: factory_upgrades,
-            refinery_state: factory_refinery_state,
-            haulers_assigned: factory_haulers_assigned,
-        },
-        globals: GlobalBuffers {
-            resources: global_resources,
-        },
-        total_size_bytes: offset,
+        drones,
+        asteroids,
+        factories,
+        haulers,
+        globals,
+        total_size_bytes: planner.offset,
     })
 }
 
+/// Copies every live section of `old_data` into the matching section of a freshly planned buffer.
+pub fn relocate_data(
+    old_layout: &EntityBufferLayout,
+    old_data: &[u32],
+    new_layout: &EntityBufferLayout,
+    new_data: &mut [u32],
+) {
+    for (old, new) in old_layout.sections().into_iter().zip(new_layout.sections()) {
+        let len = old.length.min(new.capacity.max(new.length));
+        let src = old.offset_bytes / 4;
+        let dst = new.offset_bytes / 4;
+        new_data[dst..dst + len].copy_from_slice(&old_data[src..src + len]);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +662,8 @@ mod tests {
         assert!(
             layout.drones.target_region_index.offset_bytes < layout.drones.charging.offset_bytes
         );
+        assert!(layout.drones.charging.offset_bytes < layout.drones.speed.offset_bytes);
+        assert!(layout.drones.speed.offset_bytes < layout.drones.integrity.offset_bytes);
 
         assert!(
             layout.asteroids.positions.offset_bytes < layout.asteroids.ore_remaining.offset_bytes
@@ -399,6 +715,8 @@ mod tests {
         assert_eq!(layout.drones.target_asteroid_index.length, 3);
         assert_eq!(layout.drones.target_region_index.length, 3);
         assert_eq!(layout.drones.charging.length, 3);
+        assert_eq!(layout.drones.speed.length, 3);
+        assert_eq!(layout.drones.integrity.length, 3);
 
         assert_eq!(layout.asteroids.positions.length, 12);
         assert_eq!(layout.asteroids.ore_remaining.length, 4);
@@ -407,10 +725,67 @@ mod tests {
 
         assert_eq!(layout.factories.orientations.length, 20);
         assert_eq!(layout.factories.activity.length, 5);
-        assert_eq!(layout.factories.resources.length, 35);
+        assert_eq!(layout.factories.resources.length, 45);
         assert_eq!(layout.factories.energy.length, 5);
         assert_eq!(layout.factories.max_energy.length, 5);
-        assert_eq!(layout.factories.upgrades.length, 25);
+        assert_eq!(layout.factories.upgrades.length, 35);
         assert_eq!(layout.factories.refinery_state.length, 5 * MAX_REFINE_SLOTS * 4);
+        assert_eq!(layout.factories.refine_recipes.length, 5 * MAX_REFINE_SLOTS);
+        assert_eq!(layout.factories.heat.length, 5);
+        assert_eq!(layout.factories.cooling.length, 5);
+    }
+
+    #[test]
+    fn descriptor_covers_every_section_in_order() {
+        let layout = plan_layout(3, 4, 5).expect("layout should be valid");
+        let descriptor = layout.describe();
+        assert_eq!(descriptor.version, LAYOUT_VERSION);
+        assert_eq!(descriptor.total_size_bytes, layout.total_size_bytes);
+
+        let mut expected_offset = 0;
+        for section in &descriptor.sections {
+            assert_eq!(section.offset_bytes, expected_offset, "{}", section.name);
+            assert_eq!(section.components.len(), section.stride, "{}", section.name);
+            assert_eq!(section.length % section.stride, 0, "{}", section.name);
+            expected_offset += section.capacity * 4;
+        }
+        assert_eq!(expected_offset, layout.total_size_bytes);
+
+        let refinery = descriptor
+            .sections
+            .iter()
+            .find(|s| s.name == "factories.refinery_state")
+            .expect("refinery section");
+        assert_eq!(refinery.stride, MAX_REFINE_SLOTS * 4);
+        assert_eq!(refinery.components[5], "slot1.amount");
+    }
+
+    #[test]
+    fn spare_capacity_keeps_live_lengths() {
+        let counts = EntityCounts::new(2, 3, 1, 2);
+        let layout = plan_layout_with_capacity(counts, counts.with_spare()).expect("layout");
+        assert_eq!(layout.counts(), counts);
+        assert_eq!(layout.capacities(), counts.with_spare());
+        assert_eq!(layout.drones.positions.length, 6);
+        assert_eq!(layout.drones.positions.capacity, 6 * POSITION_STRIDE);
+        assert_eq!(layout.haulers.route.length, 2 * HAULER_ROUTE_STRIDE);
+        assert_eq!(layout.haulers.cargo.entity_capacity(), capacity_with_spare(2));
+        assert_eq!(
+            layout.drones.velocities.offset_bytes,
+            layout.drones.positions.offset_bytes + layout.drones.positions.capacity * 4
+        );
+    }
+
+    #[test]
+    fn remove_entity_shifts_later_slots() {
+        let layout = plan_layout(3, 0, 0).expect("layout");
+        let mut data = vec![0u32; layout.total_size_bytes / 4];
+        let section = &layout.drones.positions;
+        let base = section.offset_bytes / 4;
+        for (i, value) in data[base..base + 9].iter_mut().enumerate() {
+            *value = i as u32 + 1;
+        }
+        section.remove_entity(&mut data, 0);
+        assert_eq!(&data[base..base + 9], &[4, 5, 6, 7, 8, 9, 0, 0, 0]);
     }
 }
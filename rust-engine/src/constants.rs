@@ -7,12 +7,27 @@ pub const FACTORY_STORAGE_CAPACITY: f32 = 300.0;
 pub const FACTORY_ENERGY_CAPACITY: f32 = 80.0;
 pub const FACTORY_INITIAL_ENERGY: f32 = 40.0;
 pub const FACTORY_INITIAL_DOCKING_CAPACITY: usize = 3;
+pub const FACTORY_BASE_COST_METALS: f32 = 100.0;
+pub const FACTORY_BASE_COST_CRYSTALS: f32 = 50.0;
+pub const FACTORY_PRICE_SCALE_INCREMENT: f32 = 50.0;
 
 // Factory Upgrades
 pub const FACTORY_SOLAR_MAX_ENERGY_PER_LEVEL: f32 = 10.0;
 pub const FACTORY_SOLAR_BASE_REGEN: f32 = 1.25;
 pub const FACTORY_SOLAR_REGEN_PER_LEVEL: f32 = 0.5;
 pub const FACTORY_UPGRADE_GROWTH: f32 = 1.35;
+/// Extra heat shed per second for each cooler level.
+pub const FACTORY_COOLER_DISSIPATION_PER_LEVEL: f32 = 2.0;
+
+// Refinery Heat
+pub const FACTORY_MAX_HEAT: f32 = 100.0;
+/// Heat added per second by each refining slot running at full speed.
+pub const FACTORY_HEAT_PER_ACTIVE_SLOT: f32 = 1.5;
+pub const FACTORY_HEAT_DISSIPATION: f32 = 4.0;
+/// An overheated factory stays in cooldown until heat falls to this fraction of the maximum.
+pub const FACTORY_HEAT_RESUME_FRACTION: f32 = 0.5;
+pub const REFINERY_OVERDRIVE_SPEED_MULTIPLIER: f32 = 1.5;
+pub const REFINERY_OVERDRIVE_HEAT_MULTIPLIER: f32 = 3.0;
 
 // Global Modules
 pub const SOLAR_ARRAY_LOCAL_MAX_ENERGY_PER_LEVEL: f32 = 3.0;
@@ -34,6 +49,12 @@ pub const DRONE_ENERGY_COST: f32 = 0.9;
 pub const DRONE_MAX_CARGO: f32 = 40.0;
 pub const DRONE_SPEED: f32 = 14.0;
 pub const DRONE_MINING_RATE: f32 = 6.0;
+pub const DRONE_MAX_INTEGRITY: f32 = 100.0;
+/// Cargo handed over per second by a docked drone before factory unload upgrades.
+pub const DRONE_UNLOAD_RATE: f32 = 20.0;
+pub const FACTORY_UNLOAD_RATE_PER_LEVEL: f32 = 0.25;
+/// Seconds over which a factory's reported unload throughput is smoothed.
+pub const DOCKING_METRICS_WINDOW: f32 = 10.0;
 
 // Drone States (f32 for buffer compatibility)
 pub const DRONE_STATE_IDLE: f32 = 0.0;
@@ -41,6 +62,8 @@ pub const DRONE_STATE_TO_ASTEROID: f32 = 1.0;
 pub const DRONE_STATE_MINING: f32 = 2.0;
 pub const DRONE_STATE_RETURNING: f32 = 3.0;
 pub const DRONE_STATE_UNLOADING: f32 = 4.0;
+/// Hull integrity hit zero; the drone waits at its factory until repaired.
+pub const DRONE_STATE_DISABLED: f32 = 5.0;
 
 // Factory Placement
 pub const FACTORY_MIN_DISTANCE: f32 = 10.0;
@@ -70,3 +93,10 @@ pub const LOGISTICS_HAULER_SPEED: f32 = 1.0;
 pub const LOGISTICS_PICKUP_OVERHEAD: f32 = 1.0;
 pub const LOGISTICS_DROPOFF_OVERHEAD: f32 = 1.0;
 pub const WAREHOUSE_STORAGE_MULTIPLIER: f32 = 8.0;
+pub const STARTER_FACTORY_HAULERS: i32 = 1;
+pub const STARTER_FACTORY_ORE: f32 = 50.0;
+pub const STARTER_FACTORY_BARS: f32 = 10.0;
+
+// Market
+/// Credits charged per bar of the regular price when a purchase is paid in credits.
+pub const CREDITS_PER_BAR_COST: f32 = 15.0;
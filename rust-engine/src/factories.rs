@@ -0,0 +1,167 @@
+//! Factory construction helpers mirroring `src/ecs/factories` in TypeScript.
+
+use std::f32::consts::{PI, TAU};
+
+use crate::constants::{
+    FACTORY_BASE_COST_CRYSTALS, FACTORY_BASE_COST_METALS, FACTORY_ENERGY_CAPACITY,
+    FACTORY_ENERGY_PER_REFINE, FACTORY_IDLE_ENERGY_PER_SEC, FACTORY_INITIAL_DOCKING_CAPACITY,
+    FACTORY_INITIAL_ENERGY, FACTORY_MAX_DISTANCE, FACTORY_MIN_DISTANCE,
+    FACTORY_PLACEMENT_ATTEMPTS, FACTORY_PRICE_SCALE_INCREMENT, FACTORY_REFINE_SLOTS,
+    FACTORY_STORAGE_CAPACITY, LOGISTICS_DROPOFF_OVERHEAD, LOGISTICS_HAULER_CAPACITY,
+    LOGISTICS_HAULER_SPEED, LOGISTICS_PICKUP_OVERHEAD, STARTER_FACTORY_BARS,
+    STARTER_FACTORY_HAULERS, STARTER_FACTORY_ORE,
+};
+use crate::rng::Mulberry32;
+use crate::schema::{FactoryLogisticsState, FactorySnapshot, HaulerConfig, Vector3};
+use crate::spatial::distance;
+
+/// Metals/crystals cost of the next factory (`computeFactoryCost` in TS).
+pub fn compute_factory_cost(factory_count: usize) -> (f32, f32) {
+    let purchase_index = factory_count.saturating_sub(1) as f32;
+    let increase = FACTORY_PRICE_SCALE_INCREMENT * purchase_index;
+    (
+        FACTORY_BASE_COST_METALS + increase,
+        FACTORY_BASE_COST_CRYSTALS + increase,
+    )
+}
+
+/// Picks a ring position around the factory centroid (`computeFactoryPlacement` in TS),
+/// drawing from the simulation RNG instead of `Math.random` so placement is replayable.
+pub fn compute_factory_placement(existing: &[Vector3], rng: &mut Mulberry32) -> Vector3 {
+    if existing.is_empty() {
+        return [0.0, 0.0, 0.0];
+    }
+
+    let count = existing.len() as f32;
+    let mut centroid = [0.0f32; 3];
+    for position in existing {
+        centroid[0] += position[0];
+        centroid[1] += position[1];
+        centroid[2] += position[2];
+    }
+    centroid = [centroid[0] / count, centroid[1] / count, centroid[2] / count];
+
+    for _ in 0..FACTORY_PLACEMENT_ATTEMPTS {
+        let angle = rng.next_f32() * TAU;
+        let radius =
+            FACTORY_MIN_DISTANCE + rng.next_f32() * (FACTORY_MAX_DISTANCE - FACTORY_MIN_DISTANCE);
+        let candidate = [
+            centroid[0] + angle.cos() * radius,
+            centroid[1],
+            centroid[2] + angle.sin() * radius,
+        ];
+
+        let mut distances: Vec<f32> = existing.iter().map(|p| distance(&candidate, p)).collect();
+        distances.sort_by(|a, b| a.total_cmp(b));
+        let nearest = distances[0];
+        let second_nearest = distances.get(1).copied().unwrap_or(nearest);
+
+        if !(FACTORY_MIN_DISTANCE..=FACTORY_MAX_DISTANCE).contains(&nearest) {
+            continue;
+        }
+        if existing.len() > 1 && second_nearest > FACTORY_MAX_DISTANCE {
+            continue;
+        }
+        return candidate;
+    }
+
+    // Deterministic hex-ring fallback when every random candidate was rejected.
+    let index = existing.len();
+    let ring = (index / 6) as f32;
+    let angle = (index % 6) as f32 * (PI / 3.0);
+    let radius = FACTORY_MAX_DISTANCE
+        .min(FACTORY_MIN_DISTANCE + ring * ((FACTORY_MAX_DISTANCE - FACTORY_MIN_DISTANCE) * 0.5));
+    [
+        centroid[0] + angle.cos() * radius,
+        centroid[1],
+        centroid[2] + angle.sin() * radius,
+    ]
+}
+
+/// Builds a factory with the same defaults as TS `createFactory`.
+pub fn create_factory(id: String, position: Vector3) -> FactorySnapshot {
+    FactorySnapshot {
+        id,
+        position,
+        docking_capacity: FACTORY_INITIAL_DOCKING_CAPACITY as i32,
+        refine_slots: FACTORY_REFINE_SLOTS as i32,
+        idle_energy_per_sec: FACTORY_IDLE_ENERGY_PER_SEC,
+        energy_per_refine: FACTORY_ENERGY_PER_REFINE,
+        storage_capacity: FACTORY_STORAGE_CAPACITY,
+        energy: FACTORY_INITIAL_ENERGY,
+        energy_capacity: FACTORY_ENERGY_CAPACITY,
+        haulers_assigned: Some(0),
+        hauler_config: Some(HaulerConfig {
+            capacity: LOGISTICS_HAULER_CAPACITY,
+            speed: LOGISTICS_HAULER_SPEED,
+            pickup_overhead: LOGISTICS_PICKUP_OVERHEAD,
+            dropoff_overhead: LOGISTICS_DROPOFF_OVERHEAD,
+            resource_filters: Vec::new(),
+            mode: "auto".to_string(),
+            priority: 5,
+        }),
+        logistics_state: Some(FactoryLogisticsState::default()),
+        ..Default::default()
+    }
+}
+
+/// The factory a new run starts with: at the origin, with a hauler and seed stock
+/// (`WAREHOUSE_CONFIG.starterFactory*` in TS).
+pub fn create_starter_factory(id: String) -> FactorySnapshot {
+    let mut factory = create_factory(id, [0.0, 0.0, 0.0]);
+    factory.haulers_assigned = Some(STARTER_FACTORY_HAULERS);
+    factory.resources.ore = STARTER_FACTORY_ORE;
+    factory.resources.bars = STARTER_FACTORY_BARS;
+    factory.current_storage = STARTER_FACTORY_ORE;
+    factory
+}
+
+/// Index of the factory closest to `position`, skipping `exclude`. Ties keep the lowest index.
+pub fn nearest_factory(
+    factories: &[FactorySnapshot],
+    position: &Vector3,
+    exclude: Option<usize>,
+) -> Option<usize> {
+    let mut best: Option<(usize, f32)> = None;
+    for (i, factory) in factories.iter().enumerate() {
+        if Some(i) == exclude {
+            continue;
+        }
+        let d = distance(&factory.position, position);
+        if best.map(|(_, best_d)| d < best_d).unwrap_or(true) {
+            best = Some((i, d));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_respects_distance_band() {
+        let mut rng = Mulberry32::new(7);
+        let mut positions: Vec<Vector3> = vec![[0.0, 0.0, 0.0]];
+        for _ in 0..5 {
+            let next = compute_factory_placement(&positions, &mut rng);
+            let nearest = positions
+                .iter()
+                .map(|p| distance(p, &next))
+                .fold(f32::INFINITY, f32::min);
+            assert!(nearest >= FACTORY_MIN_DISTANCE - 1e-3);
+            assert!(nearest <= FACTORY_MAX_DISTANCE + 1e-3);
+            positions.push(next);
+        }
+
+        let mut replay = Mulberry32::new(7);
+        let first = compute_factory_placement(&positions[..1], &mut replay);
+        assert_eq!(first, positions[1]);
+    }
+
+    #[test]
+    fn cost_scales_linearly_after_first_factory() {
+        assert_eq!(compute_factory_cost(1), (100.0, 50.0));
+        assert_eq!(compute_factory_cost(3), (200.0, 150.0));
+    }
+}
@@ -6,11 +6,15 @@ pub mod api;
 pub mod buffers;
 pub mod constants;
 pub mod error;
+pub mod factories;
+pub mod loadouts;
 pub mod modifiers;
 pub mod parity_debug;
+pub mod recipes;
 pub mod rng;
 pub mod schema;
 pub mod sinks;
+pub mod spatial;
 pub mod systems;
 
 #[cfg(feature = "wasm")]
@@ -18,7 +22,9 @@ pub mod wasm;
 
 pub use api::{GameState, OfflineResult, SimulationCommand, TickResult};
 pub use buffers::{
-    AsteroidBuffers, BufferSection, DroneBuffers, EntityBufferLayout, FactoryBuffers, plan_layout,
+    AsteroidBuffers, BufferSection, DroneBuffers, EntityBufferLayout, EntityCounts,
+    FactoryBuffers, HaulerBuffers, LayoutDescriptor, SectionDescriptor, LAYOUT_VERSION, plan_layout,
+    plan_layout_with_capacity,
 };
 pub use error::SimulationError;
 pub use rng::Mulberry32;
@@ -0,0 +1,153 @@
+use crate::constants::{DRONE_MAX_BATTERY, DRONE_MAX_CARGO, DRONE_MINING_RATE, DRONE_SPEED};
+use crate::modifiers::ResourceModifierSnapshot;
+use crate::schema::{DroneLoadout, Modules};
+
+/// One equipment slot and what each tier fitted there adds to the drone.
+pub struct EquipmentDefinition {
+    pub slot: &'static str,
+    pub resource: &'static str,
+    pub base_cost: f32,
+    pub cost_growth: f32,
+    pub max_tier: u32,
+    pub capacity_per_tier: f32,
+    pub mining_rate_per_tier: f32,
+    pub battery_per_tier: f32,
+    pub speed_per_tier: f32,
+}
+
+pub const EQUIPMENT_DEFINITIONS: [EquipmentDefinition; 4] = [
+    EquipmentDefinition {
+        slot: "tool",
+        resource: "metals",
+        base_cost: 150.0,
+        cost_growth: 1.6,
+        max_tier: 5,
+        capacity_per_tier: 0.0,
+        mining_rate_per_tier: 0.12,
+        battery_per_tier: 0.0,
+        speed_per_tier: 0.0,
+    },
+    EquipmentDefinition {
+        slot: "engine",
+        resource: "metals",
+        base_cost: 180.0,
+        cost_growth: 1.6,
+        max_tier: 5,
+        capacity_per_tier: 0.0,
+        mining_rate_per_tier: 0.0,
+        battery_per_tier: 0.0,
+        speed_per_tier: 0.08,
+    },
+    EquipmentDefinition {
+        slot: "hull",
+        resource: "metals",
+        base_cost: 120.0,
+        cost_growth: 1.55,
+        max_tier: 5,
+        capacity_per_tier: 0.1,
+        mining_rate_per_tier: 0.0,
+        battery_per_tier: 0.05,
+        speed_per_tier: 0.0,
+    },
+    EquipmentDefinition {
+        slot: "aiModule",
+        resource: "crystals",
+        base_cost: 200.0,
+        cost_growth: 1.7,
+        max_tier: 5,
+        capacity_per_tier: 0.0,
+        mining_rate_per_tier: 0.04,
+        battery_per_tier: 0.1,
+        speed_per_tier: 0.0,
+    },
+];
+
+/// Per-drone stat multipliers from its fitted equipment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoadoutMultipliers {
+    pub capacity: f32,
+    pub mining_rate: f32,
+    pub max_battery: f32,
+    pub speed: f32,
+}
+
+impl Default for LoadoutMultipliers {
+    fn default() -> Self {
+        Self {
+            capacity: 1.0,
+            mining_rate: 1.0,
+            max_battery: 1.0,
+            speed: 1.0,
+        }
+    }
+}
+
+/// A drone's effective stats after modules, global modifiers and its own equipment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DroneStats {
+    pub speed: f32,
+    pub capacity: f32,
+    pub mining_rate: f32,
+    pub max_battery: f32,
+}
+
+/// The drone stat formula shared by `sys_fleet` and the drone AI.
+pub fn drone_stats(
+    modules: &Modules,
+    modifiers: &ResourceModifierSnapshot,
+    loadout: &LoadoutMultipliers,
+) -> DroneStats {
+    let speed_bonus = 1.0 + (modules.drone_bay as f32 - 1.0).max(0.0) * 0.05;
+    let base_speed = DRONE_SPEED * speed_bonus;
+    let capacity_base = DRONE_MAX_CARGO + modules.storage as f32 * 5.0;
+    let mining_base = DRONE_MINING_RATE + modules.refinery as f32 * 0.5;
+    DroneStats {
+        speed: base_speed * modifiers.drone_production_speed_multiplier * loadout.speed,
+        capacity: capacity_base * modifiers.drone_capacity_multiplier * loadout.capacity,
+        mining_rate: mining_base * modifiers.drone_production_speed_multiplier * loadout.mining_rate,
+        max_battery: DRONE_MAX_BATTERY * modifiers.drone_battery_multiplier * loadout.max_battery,
+    }
+}
+
+pub fn equipment_definition(slot: &str) -> Option<&'static EquipmentDefinition> {
+    EQUIPMENT_DEFINITIONS.iter().find(|def| def.slot == slot)
+}
+
+/// Price of fitting `tier` (1-based) in the definition's slot.
+pub fn equipment_cost(definition: &EquipmentDefinition, tier: u32) -> f32 {
+    (definition.base_cost * definition.cost_growth.powi(tier.saturating_sub(1) as i32)).ceil()
+}
+
+pub fn slot_tier(loadout: &DroneLoadout, slot: &str) -> Option<u32> {
+    match slot {
+        "tool" => Some(loadout.tool),
+        "engine" => Some(loadout.engine),
+        "hull" => Some(loadout.hull),
+        "aiModule" => Some(loadout.ai_module),
+        _ => None,
+    }
+}
+
+pub fn slot_tier_mut<'a>(loadout: &'a mut DroneLoadout, slot: &str) -> Option<&'a mut u32> {
+    match slot {
+        "tool" => Some(&mut loadout.tool),
+        "engine" => Some(&mut loadout.engine),
+        "hull" => Some(&mut loadout.hull),
+        "aiModule" => Some(&mut loadout.ai_module),
+        _ => None,
+    }
+}
+
+pub fn loadout_multipliers(loadout: &DroneLoadout) -> LoadoutMultipliers {
+    let mut multipliers = LoadoutMultipliers::default();
+    for definition in EQUIPMENT_DEFINITIONS.iter() {
+        let tier = slot_tier(loadout, definition.slot)
+            .unwrap_or(0)
+            .min(definition.max_tier) as f32;
+        multipliers.capacity += definition.capacity_per_tier * tier;
+        multipliers.mining_rate += definition.mining_rate_per_tier * tier;
+        multipliers.max_battery += definition.battery_per_tier * tier;
+        multipliers.speed += definition.speed_per_tier * tier;
+    }
+    multipliers
+}
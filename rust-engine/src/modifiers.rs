@@ -1,4 +1,5 @@
 use crate::schema::Resources;
+use crate::systems::research::{research_bonus, ResearchBonus};
 use serde_json::Value;
 
 pub struct ResourceModifierSnapshot {
@@ -66,6 +67,7 @@ pub fn get_resource_modifiers(
     prestige_cores: i32,
     prestige_investments: Option<&Value>,
     spec_techs: Option<&Value>,
+    completed_research: &[String],
 ) -> ResourceModifierSnapshot {
     let metals_balance = get_balance_with_prestige(&RESOURCE_BALANCE_METALS, prestige_cores);
     let crystals_balance = get_balance_with_prestige(&RESOURCE_BALANCE_CRYSTALS, prestige_cores);
@@ -77,13 +79,18 @@ pub fn get_resource_modifiers(
     let organics_bonus = compute_bonus(resources.organics, &organics_balance);
     let ice_bonus = compute_bonus(resources.ice, &ice_balance);
 
-    let drone_battery_multiplier = (1.0 + metals_bonus).max(1.0);
-    let drone_capacity_multiplier = (1.0 + metals_bonus).max(1.0);
-    let storage_capacity_multiplier = (1.0 + metals_bonus).max(1.0);
-    let refinery_yield_multiplier = (1.0 + crystals_bonus).max(1.0);
+    // Completed research scales on top of the resource bonuses.
+    let research = |bonus| 1.0 + research_bonus(completed_research, bonus);
+
+    let drone_battery_multiplier = (1.0 + metals_bonus).max(1.0) * research(ResearchBonus::DroneBattery);
+    let drone_capacity_multiplier = (1.0 + metals_bonus).max(1.0) * research(ResearchBonus::DroneCapacity);
+    let storage_capacity_multiplier =
+        (1.0 + metals_bonus).max(1.0) * research(ResearchBonus::StorageCapacity);
+    let refinery_yield_multiplier = (1.0 + crystals_bonus).max(1.0) * research(ResearchBonus::RefineryYield);
     let drone_production_speed_multiplier = (1.0 + ORGANICS_DRONE_OUTPUT_FACTOR * organics_bonus).max(1.0);
-    let energy_generation_multiplier = (1.0 + ORGANICS_ENERGY_REGEN_FACTOR * organics_bonus).max(1.0);
-    let energy_storage_multiplier = (1.0 + ice_bonus).max(1.0);
+    let energy_generation_multiplier = (1.0 + ORGANICS_ENERGY_REGEN_FACTOR * organics_bonus).max(1.0)
+        * research(ResearchBonus::EnergyGeneration);
+    let energy_storage_multiplier = (1.0 + ice_bonus).max(1.0) * research(ResearchBonus::EnergyStorage);
     let energy_drain_multiplier = (1.0 - ICE_DRAIN_REDUCTION_FACTOR * ice_bonus).max(1.0).clamp(0.5, 1.0);
 
     let drone_velocity_tier = prestige_investments
@@ -32,6 +32,11 @@ mod console {
     }
 }
 
+/// Reports an engine problem that cannot surface as an error, regardless of the debug toggle.
+pub fn warn(message: &str) {
+    console::log(message);
+}
+
 pub fn log_json(label: &str, payload: &serde_json::Value) {
     if !enabled() {
         return;
@@ -0,0 +1,73 @@
+//! Refining recipes a factory's refine slots can run.
+
+use crate::buffers::FACTORY_RESOURCES;
+use crate::constants::FACTORY_REFINE_TIME;
+
+/// Inputs consumed and outputs yielded per unit of batch, plus how long and how hard it runs.
+/// A batch is as large as the scarcest input allows; outputs are paid out as the batch progresses.
+pub struct RecipeDefinition {
+    pub id: &'static str,
+    pub inputs: &'static [(&'static str, f32)],
+    pub outputs: &'static [(&'static str, f32)],
+    pub refine_time: f32,
+    /// Scales the factory's energy drain per refine while the recipe runs.
+    pub energy_multiplier: f32,
+}
+
+/// Index 0 is the default every slot starts on.
+pub const RECIPE_DEFINITIONS: [RecipeDefinition; 3] = [
+    RecipeDefinition {
+        id: "bars",
+        inputs: &[("ore", 1.0)],
+        outputs: &[("bars", 1.0)],
+        refine_time: FACTORY_REFINE_TIME,
+        energy_multiplier: 1.0,
+    },
+    RecipeDefinition {
+        id: "alloys",
+        inputs: &[("metals", 1.0), ("crystals", 0.5)],
+        outputs: &[("alloys", 0.5)],
+        refine_time: 15.0,
+        energy_multiplier: 1.5,
+    },
+    RecipeDefinition {
+        id: "coolant",
+        inputs: &[("ice", 1.0)],
+        outputs: &[("coolant", 0.8), ("organics", 0.1)],
+        refine_time: 8.0,
+        energy_multiplier: 0.75,
+    },
+];
+
+pub fn recipe_index(id: &str) -> Option<usize> {
+    RECIPE_DEFINITIONS.iter().position(|def| def.id == id)
+}
+
+/// Recipe stored in a buffer slot; anything out of range falls back to the default.
+pub fn recipe_at(index: f32) -> &'static RecipeDefinition {
+    let idx = if index >= 0.0 { index as usize } else { 0 };
+    RECIPE_DEFINITIONS.get(idx).unwrap_or(&RECIPE_DEFINITIONS[0])
+}
+
+/// Position of a resource within a factory's resource stride.
+pub fn factory_resource_index(resource: &str) -> Option<usize> {
+    FACTORY_RESOURCES.iter().position(|&name| name == resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_recipe_resource_has_a_factory_slot() {
+        for recipe in RECIPE_DEFINITIONS.iter() {
+            for (resource, ratio) in recipe.inputs.iter().chain(recipe.outputs) {
+                assert!(factory_resource_index(resource).is_some(), "{}", resource);
+                assert!(*ratio > 0.0);
+            }
+        }
+        assert_eq!(recipe_index("alloys"), Some(1));
+        assert_eq!(recipe_at(-1.0).id, "bars");
+        assert_eq!(recipe_at(99.0).id, "bars");
+    }
+}
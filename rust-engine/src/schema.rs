@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 pub struct RefineProcessSnapshot {
     pub id: String,
     pub ore_type: String,
+    /// Recipe id the batch runs, e.g. "bars", "alloys" or "coolant".
+    #[serde(default = "default_refine_recipe")]
+    pub recipe: String,
     #[serde(default)]
     pub amount: f32,
     #[serde(default)]
@@ -21,6 +24,10 @@ pub struct RefineProcessSnapshot {
     #[serde(default)]
     pub speed_multiplier: f32,
 }
+
+fn default_refine_recipe() -> String {
+    "bars".to_string()
+}
 use crate::error::SimulationError;
 
 pub const SCHEMA_VERSION: &str = "1.0.0";
@@ -89,6 +96,10 @@ pub struct FactoryResourceSnapshot {
     pub ice: f32,
     #[serde(default)]
     pub credits: f32,
+    #[serde(default)]
+    pub alloys: f32,
+    #[serde(default)]
+    pub coolant: f32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
@@ -103,6 +114,12 @@ pub struct FactoryUpgradeSnapshot {
     pub energy: i32,
     #[serde(default)]
     pub solar: i32,
+    /// Raises how fast docked drones hand over their cargo.
+    #[serde(default)]
+    pub unload: i32,
+    /// Sheds refinery heat faster.
+    #[serde(default)]
+    pub cooler: i32,
 }
 
 fn default_upgrade_request_status() -> String {
@@ -141,6 +158,23 @@ pub struct FactoryLogisticsState {
     pub outbound_reservations: BTreeMap<String, f32>,
     #[serde(default)]
     pub inbound_schedules: Vec<InboundSchedule>,
+    /// Game time each empty hauler flying home gets back.
+    #[serde(default)]
+    pub returning_haulers: Vec<f32>,
+    /// Haulers carrying a transfer or flying home.
+    #[serde(default)]
+    pub haulers_busy: i32,
+    /// `haulers_busy` over `haulers_assigned`, 0 with no haulers.
+    #[serde(default)]
+    pub hauler_utilization: f32,
+}
+
+/// One factory's hauler usage, as last counted by the logistics system.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HaulerUsage {
+    pub busy: i32,
+    pub utilization: f32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -210,6 +244,37 @@ pub struct FactorySnapshot {
     pub logistics_state: Option<FactoryLogisticsState>,
     #[serde(default, rename = "activeRefines")]
     pub active_refines: Vec<RefineProcessSnapshot>,
+    /// Recipe id each refine slot runs; missing slots run the default recipe.
+    #[serde(default)]
+    pub refine_recipes: Vec<String>,
+    #[serde(default)]
+    pub heat: f32,
+    /// Set while an overheated refinery is paused to cool down.
+    #[serde(default)]
+    pub cooling: bool,
+    /// Refines faster at the cost of much more heat.
+    #[serde(default)]
+    pub overdrive: bool,
+    #[serde(default)]
+    pub docking: DockingMetrics,
+}
+
+/// Dock throughput at one factory, updated by the unload system.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DockingMetrics {
+    /// Cargo unloaded per second, smoothed over `DOCKING_METRICS_WINDOW`.
+    #[serde(default)]
+    pub unload_rate: f32,
+    /// Cargo unloaded here since the factory was built.
+    #[serde(default)]
+    pub units_unloaded: f32,
+    /// Drones queued beyond the docking capacity.
+    #[serde(default)]
+    pub waiting: u32,
+    /// Longest the wait has been.
+    #[serde(default)]
+    pub peak_waiting: u32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -224,6 +289,34 @@ pub struct PendingTransfer {
     pub eta: f32,
     #[serde(default)]
     pub departed_at: f32,
+    /// Seconds the hauler spends loading at the source before it sets off.
+    #[serde(default)]
+    pub pickup_overhead: f32,
+    /// Seconds the hauler spends unloading at the destination before `eta`.
+    #[serde(default)]
+    pub dropoff_overhead: f32,
+    /// The hauler's flight, present once it has loaded and left the source.
+    #[serde(default)]
+    pub hauler: Option<HaulerFlight>,
+    /// Factory that lent the hauler, when neither end of the transfer flies it.
+    #[serde(default)]
+    pub carrier_id: Option<String>,
+}
+
+/// Where a transfer's hauler is, advanced by the logistics system.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HaulerFlight {
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub velocity: [f32; 3],
+    /// Cruise speed, fixed at launch so the flight lands at `eta` on the planned route.
+    pub speed: f32,
+    /// Game time `position` was last advanced to.
+    pub moved_at: f32,
+    /// When the hauler reached the destination and began unloading.
+    #[serde(default)]
+    pub arrived_at: Option<f32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
@@ -231,6 +324,99 @@ pub struct PendingTransfer {
 pub struct LogisticsQueues {
     #[serde(default)]
     pub pending_transfers: Vec<PendingTransfer>,
+    #[serde(default)]
+    pub analytics: LogisticsAnalytics,
+}
+
+/// Running totals over every completed transfer.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LogisticsAnalytics {
+    #[serde(default)]
+    pub transfers_completed: u32,
+    #[serde(default)]
+    pub units_delivered: f32,
+    /// Seconds haulers spent flying loaded between route ends.
+    #[serde(default)]
+    pub travel_seconds: f32,
+    /// Seconds haulers spent loading and unloading.
+    #[serde(default)]
+    pub overhead_seconds: f32,
+}
+
+/// What a contract pays out when filled.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractReward {
+    #[serde(default)]
+    pub bars: f32,
+    #[serde(default)]
+    pub credits: f32,
+    /// Bonus prestige cores, granted straight away rather than at the next prestige.
+    #[serde(default)]
+    pub cores: i32,
+}
+
+/// A delivery contract: `amount` of `resource` into the warehouse before `deadline`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractSnapshot {
+    pub id: String,
+    pub resource: String,
+    pub amount: f32,
+    #[serde(default)]
+    pub delivered: f32,
+    /// Seconds allowed to fill the contract once accepted.
+    pub duration: f32,
+    /// Game time an accepted contract must be filled by, or an open offer lapses at.
+    pub deadline: f32,
+    #[serde(default)]
+    pub reward: ContractReward,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractsSnapshot {
+    #[serde(default)]
+    pub offers: Vec<ContractSnapshot>,
+    #[serde(default)]
+    pub active: Vec<ContractSnapshot>,
+    /// Game time the next offer is posted.
+    #[serde(default)]
+    pub next_offer_at: f32,
+    #[serde(default)]
+    pub next_id: u32,
+}
+
+/// The node being researched and how long it has run.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ActiveResearchSnapshot {
+    pub id: String,
+    #[serde(default)]
+    pub progress: f32,
+}
+
+/// Research points on hand, finished nodes and the node in progress.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct ResearchSnapshot {
+    #[serde(default)]
+    pub points: f32,
+    #[serde(default)]
+    pub completed: Vec<String>,
+    #[serde(default)]
+    pub active: Option<ActiveResearchSnapshot>,
+}
+
+/// Market prices and standing sell orders.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketSnapshot {
+    /// Credits per unit; goods missing here trade at their base price.
+    #[serde(default)]
+    pub prices: BTreeMap<String, f32>,
+    /// Standing auto-sell orders: resource to the warehouse reserve kept back from sale.
+    #[serde(default)]
+    pub auto_sell: BTreeMap<String, f32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
@@ -275,6 +461,11 @@ pub struct Modules {
     #[serde(rename = "routingProtocol")]
     #[serde(default)]
     pub routing_protocol: i32,
+    #[serde(default)]
+    pub lab: i32,
+    #[serde(rename = "dataCore")]
+    #[serde(default)]
+    pub data_core: i32,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -282,6 +473,85 @@ pub struct Prestige {
     pub cores: i32,
 }
 
+/// A hazard attached to an asteroid, cycling between active and dormant phases.
+/// Fractured asteroids carry one per hazardous region instead of a single asteroid-wide one.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AsteroidHazard {
+    pub asteroid_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<String>,
+    /// One of ionStorm, solarFlare, sporeBurst, microQuakes.
+    pub kind: String,
+    /// low, medium or high.
+    pub severity: String,
+    #[serde(default)]
+    pub active: bool,
+    /// Seconds left in the current phase.
+    #[serde(default)]
+    pub remaining: f32,
+}
+
+/// Totals that survive prestige resets.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LifetimeStats {
+    #[serde(default)]
+    pub prestiges: u32,
+    #[serde(default)]
+    pub cores_earned: i32,
+    #[serde(default)]
+    pub bars_converted: f32,
+    #[serde(default)]
+    pub game_time: f32,
+}
+
+/// Equipment tiers fitted to one drone; 0 leaves a slot empty.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneLoadout {
+    #[serde(default)]
+    pub tool: u32,
+    #[serde(default)]
+    pub engine: u32,
+    #[serde(default)]
+    pub hull: u32,
+    #[serde(default)]
+    pub ai_module: u32,
+}
+
+/// A player override on one drone's automatic targeting.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DroneOrder {
+    /// Mine this asteroid (and region, when given) on the next trip, then resume automatic targeting.
+    #[serde(rename_all = "camelCase")]
+    GoTo {
+        asteroid_id: String,
+        #[serde(default)]
+        region_id: Option<String>,
+    },
+    /// Return to this factory now, then resume automatic targeting.
+    #[serde(rename_all = "camelCase")]
+    Recall { factory_id: String },
+    /// Stay idle once docked until the order is cleared.
+    Hold,
+    /// Keep mining this asteroid until it is depleted.
+    #[serde(rename_all = "camelCase")]
+    Pin { asteroid_id: String },
+}
+
+impl DroneOrder {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DroneOrder::GoTo { .. } => "goTo",
+            DroneOrder::Recall { .. } => "recall",
+            DroneOrder::Hold => "hold",
+            DroneOrder::Pin { .. } => "pin",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SaveMeta {
     #[serde(rename = "lastSave")]
@@ -317,6 +587,10 @@ pub struct StoreSettings {
     #[serde(rename = "shadowMode")]
     #[serde(default)]
     pub shadow_mode: bool,
+    /// Periodically moves drone ownership toward factories with fewer drones than their share.
+    #[serde(rename = "autoBalanceDrones")]
+    #[serde(default)]
+    pub auto_balance_drones: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
@@ -358,6 +632,29 @@ pub struct SimulationSnapshot {
     pub spec_tech_spent: Option<serde_json::Value>,
     #[serde(default, rename = "prestigeInvestments")]
     pub prestige_investments: Option<serde_json::Value>,
+    #[serde(default, rename = "asteroidHazards")]
+    pub asteroid_hazards: Vec<AsteroidHazard>,
+    #[serde(default, rename = "lifetimeStats")]
+    pub lifetime_stats: Option<LifetimeStats>,
+    #[serde(default, rename = "droneLoadouts")]
+    pub drone_loadouts: BTreeMap<String, DroneLoadout>,
+    /// Hull integrity of damaged drones; drones missing here are at full integrity.
+    #[serde(default, rename = "droneIntegrity")]
+    pub drone_integrity: BTreeMap<String, f32>,
+    /// Drones parked by hull damage; they stay parked across reloads until repaired.
+    #[serde(default, rename = "disabledDrones")]
+    pub disabled_drones: BTreeSet<String>,
+    #[serde(default, rename = "droneOrders")]
+    pub drone_orders: BTreeMap<String, DroneOrder>,
+    /// Drones whose owner was set by command; they always return to their owner factory.
+    #[serde(default, rename = "assignedDrones")]
+    pub assigned_drones: BTreeSet<String>,
+    #[serde(default)]
+    pub market: MarketSnapshot,
+    #[serde(default)]
+    pub contracts: ContractsSnapshot,
+    #[serde(default)]
+    pub research: ResearchSnapshot,
     #[serde(default, rename = "gameTime")]
     pub game_time: f32,
     #[serde(flatten, default)]
@@ -1,6 +1,131 @@
 use crate::schema::SimulationSnapshot;
 use serde_json::Value;
 
+/// A specialization tech, bought with one secondary resource once enough of it has been spent.
+pub struct SpecTechDefinition {
+    pub id: &'static str,
+    pub resource: &'static str,
+    pub unlock_at: f32,
+    pub base_cost: f32,
+    pub cost_growth: f32,
+    pub bonus_per_level: f32,
+    pub max_level: i64,
+}
+
+/// A permanent investment that survives prestige, capped at `max_tier`.
+pub struct PrestigeInvestmentDefinition {
+    pub id: &'static str,
+    pub resource: &'static str,
+    pub base_cost: f32,
+    pub growth_factor: f32,
+    pub bonus_per_tier: f32,
+    pub max_tier: i64,
+}
+
+// Mirrors specTechDefinitions in src/state/constants.ts
+pub const SPEC_TECH_DEFINITIONS: [SpecTechDefinition; 4] = [
+    SpecTechDefinition {
+        id: "oreMagnet",
+        resource: "metals",
+        unlock_at: 50_000.0,
+        base_cost: 8_000.0,
+        cost_growth: 1.28,
+        bonus_per_level: 0.03,
+        max_level: 20,
+    },
+    SpecTechDefinition {
+        id: "crystalResonance",
+        resource: "crystals",
+        unlock_at: 50_000.0,
+        base_cost: 7_500.0,
+        cost_growth: 1.3,
+        bonus_per_level: 0.02,
+        max_level: 20,
+    },
+    SpecTechDefinition {
+        id: "biotechFarming",
+        resource: "organics",
+        unlock_at: 50_000.0,
+        base_cost: 6_500.0,
+        cost_growth: 1.26,
+        bonus_per_level: 0.03,
+        max_level: 20,
+    },
+    SpecTechDefinition {
+        id: "cryoPreservation",
+        resource: "ice",
+        unlock_at: 50_000.0,
+        base_cost: 5_000.0,
+        cost_growth: 1.24,
+        bonus_per_level: 0.05,
+        max_level: 15,
+    },
+];
+
+// Mirrors prestigeInvestmentDefinitions in src/state/constants.ts
+pub const PRESTIGE_INVESTMENT_DEFINITIONS: [PrestigeInvestmentDefinition; 4] = [
+    PrestigeInvestmentDefinition {
+        id: "droneVelocity",
+        resource: "metals",
+        base_cost: 1_000.0,
+        growth_factor: 1.5,
+        bonus_per_tier: 0.02,
+        max_tier: 20,
+    },
+    PrestigeInvestmentDefinition {
+        id: "asteroidAbundance",
+        resource: "crystals",
+        base_cost: 1_000.0,
+        growth_factor: 1.5,
+        bonus_per_tier: 0.02,
+        max_tier: 20,
+    },
+    PrestigeInvestmentDefinition {
+        id: "refineryMastery",
+        resource: "organics",
+        base_cost: 1_000.0,
+        growth_factor: 1.5,
+        bonus_per_tier: 0.01,
+        max_tier: 20,
+    },
+    PrestigeInvestmentDefinition {
+        id: "offlineEfficiency",
+        resource: "ice",
+        base_cost: 1_000.0,
+        growth_factor: 1.5,
+        bonus_per_tier: 0.03,
+        max_tier: 20,
+    },
+];
+
+pub fn spec_tech_definition(id: &str) -> Option<&'static SpecTechDefinition> {
+    SPEC_TECH_DEFINITIONS.iter().find(|def| def.id == id)
+}
+
+pub fn prestige_investment_definition(id: &str) -> Option<&'static PrestigeInvestmentDefinition> {
+    PRESTIGE_INVESTMENT_DEFINITIONS.iter().find(|def| def.id == id)
+}
+
+pub fn spec_tech_cost(definition: &SpecTechDefinition, level: i64) -> f32 {
+    (definition.base_cost * definition.cost_growth.powi(level.max(0) as i32)).ceil()
+}
+
+pub fn prestige_investment_cost(definition: &PrestigeInvestmentDefinition, level: i64) -> f32 {
+    (definition.base_cost * definition.growth_factor.powi(level.max(0) as i32)).ceil()
+}
+
+/// Reads an integer level (or spent amount) from one of the snapshot's JSON maps.
+pub fn json_level(json: &Option<Value>, key: &str) -> f64 {
+    json.as_ref()
+        .and_then(|v| v.get(key))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0)
+}
+
+pub fn spec_tech_unlocked(snapshot: &SimulationSnapshot, definition: &SpecTechDefinition) -> bool {
+    json_level(&snapshot.spec_tech_spent, definition.resource) >= definition.unlock_at as f64
+}
+
 pub struct SinkBonuses {
     pub ore_yield_multiplier: f32,
     pub drone_speed_multiplier: f32,
@@ -14,33 +139,27 @@ pub fn get_sink_bonuses(snapshot: &SimulationSnapshot) -> SinkBonuses {
     let spec_techs = &snapshot.spec_techs;
     let prestige_investments = &snapshot.prestige_investments;
 
-    let get_level = |json: &Option<Value>, key: &str| -> f32 {
-        json.as_ref()
-            .and_then(|v| v.get(key))
-            .and_then(|v| v.as_f64())
-            .unwrap_or(0.0) as f32
+    let get_level = |json: &Option<Value>, key: &str| -> f32 { json_level(json, key) as f32 };
+    let spec_bonus = |id: &str| -> f32 {
+        spec_tech_definition(id)
+            .map(|def| get_level(spec_techs, id) * def.bonus_per_level)
+            .unwrap_or(0.0)
     };
+    let investment_bonus = |id: &str| -> f32 {
+        prestige_investment_definition(id)
+            .map(|def| get_level(prestige_investments, id) * def.bonus_per_tier)
+            .unwrap_or(0.0)
+    };
+
+    let ore_bonus = spec_bonus("oreMagnet");
+    let crystal_bonus = spec_bonus("crystalResonance");
+    let biotech_bonus = spec_bonus("biotechFarming");
+    let cryo_bonus = spec_bonus("cryoPreservation");
 
-    let ore_magnet = get_level(spec_techs, "oreMagnet");
-    let crystal_resonance = get_level(spec_techs, "crystalResonance");
-    let biotech_farming = get_level(spec_techs, "biotechFarming");
-    let cryo_preservation = get_level(spec_techs, "cryoPreservation");
-
-    let drone_velocity = get_level(prestige_investments, "droneVelocity");
-    let asteroid_abundance = get_level(prestige_investments, "asteroidAbundance");
-    let refinery_mastery = get_level(prestige_investments, "refineryMastery");
-    let offline_efficiency = get_level(prestige_investments, "offlineEfficiency");
-
-    // Constants from TS
-    let ore_bonus = ore_magnet * 0.03;
-    let crystal_bonus = crystal_resonance * 0.02;
-    let biotech_bonus = biotech_farming * 0.03;
-    let cryo_bonus = cryo_preservation * 0.05;
-
-    let velocity_bonus = drone_velocity * 0.02;
-    let spawn_bonus = asteroid_abundance * 0.02;
-    let refinery_bonus = refinery_mastery * 0.01;
-    let offline_bonus = offline_efficiency * 0.03;
+    let velocity_bonus = investment_bonus("droneVelocity");
+    let spawn_bonus = investment_bonus("asteroidAbundance");
+    let refinery_bonus = investment_bonus("refineryMastery");
+    let offline_bonus = investment_bonus("offlineEfficiency");
 
     let clamp = |v: f32| if v.is_finite() { v.max(0.0) } else { 1.0 };
 
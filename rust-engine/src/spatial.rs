@@ -0,0 +1,318 @@
+//! Uniform grid over entity positions for nearest-neighbour queries.
+//!
+//! The grid only buckets indices; distances are always measured against the live
+//! position buffer so results match a full linear scan exactly, including ties.
+
+use std::collections::HashMap;
+
+type CellKey = (i32, i32, i32);
+
+/// Asteroids spawn on a 12-48 unit ring, 6 units tall; 8-unit cells keep buckets small.
+pub const ASTEROID_GRID_CELL_SIZE: f32 = 8.0;
+
+/// Added to the stop bound so f32 rounding in `sqrt` cannot drop a boundary candidate.
+const DISTANCE_MARGIN: f32 = 1e-3;
+
+/// Straight-line distance between two points.
+pub fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = a[2] - b[2];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+/// Candidate returned by [`SpatialGrid::nearest`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Neighbor {
+    pub index: usize,
+    pub distance: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<CellKey, Vec<usize>>,
+    entries: Vec<CellKey>,
+    /// Smallest and largest occupied cell per axis.
+    bounds: Option<(CellKey, CellKey)>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: HashMap::new(),
+            entries: Vec::new(),
+            bounds: None,
+        }
+    }
+
+    /// Builds a grid from a `[x, y, z] * N` position buffer.
+    pub fn from_positions(cell_size: f32, positions: &[f32]) -> Self {
+        let mut grid = Self::new(cell_size);
+        grid.rebuild(positions);
+        grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Re-buckets every entity; used after indices shift (removals, loads).
+    pub fn rebuild(&mut self, positions: &[f32]) {
+        self.cells.clear();
+        self.entries.clear();
+        for (index, pos) in positions.chunks_exact(3).enumerate() {
+            let key = self.cell_of([pos[0], pos[1], pos[2]]);
+            self.cells.entry(key).or_default().push(index);
+            self.entries.push(key);
+        }
+        self.recompute_bounds();
+    }
+
+    /// Moves `index` to the cell containing `position`, appending it when `index == len()`.
+    pub fn update(&mut self, index: usize, position: [f32; 3]) {
+        let key = self.cell_of(position);
+        if index >= self.entries.len() {
+            debug_assert_eq!(index, self.entries.len());
+            self.entries.push(key);
+            self.cells.entry(key).or_default().push(index);
+            self.extend_bounds(key);
+            return;
+        }
+        let old = self.entries[index];
+        if old == key {
+            return;
+        }
+        if let Some(bucket) = self.cells.get_mut(&old) {
+            bucket.retain(|&i| i != index);
+            if bucket.is_empty() {
+                self.cells.remove(&old);
+            }
+        }
+        self.cells.entry(key).or_default().push(index);
+        self.entries[index] = key;
+        self.extend_bounds(key);
+    }
+
+    /// Drops `index` and shifts every later index down by one, mirroring a removal from the
+    /// position buffer.
+    pub fn remove(&mut self, index: usize) {
+        if index >= self.entries.len() {
+            return;
+        }
+        let key = self.entries.remove(index);
+        if let Some(bucket) = self.cells.get_mut(&key) {
+            bucket.retain(|&i| i != index);
+            if bucket.is_empty() {
+                self.cells.remove(&key);
+            }
+        }
+        for bucket in self.cells.values_mut() {
+            for i in bucket.iter_mut().filter(|i| **i > index) {
+                *i -= 1;
+            }
+        }
+    }
+
+    /// Returns up to `limit` entities accepted by `filter`, ordered by distance then index,
+    /// which is exactly what sorting a full scan and truncating it would give.
+    pub fn nearest(
+        &self,
+        origin: [f32; 3],
+        positions: &[f32],
+        limit: usize,
+        filter: impl Fn(usize) -> bool,
+    ) -> Vec<Neighbor> {
+        let mut found: Vec<Neighbor> = Vec::new();
+        let Some((min_cell, max_cell)) = self.bounds else {
+            return found;
+        };
+        if limit == 0 {
+            return found;
+        }
+
+        let center = self.cell_of(origin);
+        let max_ring = [
+            (center.0 - min_cell.0).abs(),
+            (max_cell.0 - center.0).abs(),
+            (center.1 - min_cell.1).abs(),
+            (max_cell.1 - center.1).abs(),
+            (center.2 - min_cell.2).abs(),
+            (max_cell.2 - center.2).abs(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+
+        for ring in 0..=max_ring {
+            self.visit_ring(center, ring, (min_cell, max_cell), |index| {
+                if !filter(index) {
+                    return;
+                }
+                let Some(pos) = positions.get(index * 3..index * 3 + 3) else {
+                    return;
+                };
+                let dx = pos[0] - origin[0];
+                let dy = pos[1] - origin[1];
+                let dz = pos[2] - origin[2];
+                found.push(Neighbor {
+                    index,
+                    distance: (dx * dx + dy * dy + dz * dz).sqrt(),
+                });
+            });
+
+            if found.len() >= limit {
+                sort_neighbors(&mut found);
+                // Anything outside the searched cube is at least this far away.
+                if self.ring_clearance(origin, center, ring) > found[limit - 1].distance + DISTANCE_MARGIN {
+                    break;
+                }
+            }
+        }
+
+        sort_neighbors(&mut found);
+        found.truncate(limit);
+        found
+    }
+
+    fn cell_of(&self, position: [f32; 3]) -> CellKey {
+        (
+            (position[0] / self.cell_size).floor() as i32,
+            (position[1] / self.cell_size).floor() as i32,
+            (position[2] / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Visits every entity in cells at Chebyshev distance exactly `ring` from `center`,
+    /// skipping the part of the shell outside the occupied `bounds`.
+    fn visit_ring(
+        &self,
+        center: CellKey,
+        ring: i32,
+        (min, max): (CellKey, CellKey),
+        mut visit: impl FnMut(usize),
+    ) {
+        for x in (center.0 - ring).max(min.0)..=(center.0 + ring).min(max.0) {
+            for y in (center.1 - ring).max(min.1)..=(center.1 + ring).min(max.1) {
+                for z in (center.2 - ring).max(min.2)..=(center.2 + ring).min(max.2) {
+                    let on_shell = (x - center.0).abs() == ring
+                        || (y - center.1).abs() == ring
+                        || (z - center.2).abs() == ring;
+                    if !on_shell {
+                        continue;
+                    }
+                    if let Some(bucket) = self.cells.get(&(x, y, z)) {
+                        bucket.iter().copied().for_each(&mut visit);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Distance from `origin` to the nearest face of the cube of cells searched so far.
+    fn ring_clearance(&self, origin: [f32; 3], center: CellKey, ring: i32) -> f32 {
+        let axes = [(origin[0], center.0), (origin[1], center.1), (origin[2], center.2)];
+        axes.iter()
+            .map(|&(value, cell)| {
+                let low = (cell - ring) as f32 * self.cell_size;
+                let high = (cell + ring + 1) as f32 * self.cell_size;
+                (value - low).min(high - value)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = None;
+        let keys: Vec<CellKey> = self.cells.keys().copied().collect();
+        for key in keys {
+            self.extend_bounds(key);
+        }
+    }
+
+    /// Bounds only ever grow between rebuilds; stale bounds just widen the search.
+    fn extend_bounds(&mut self, key: CellKey) {
+        self.bounds = Some(match self.bounds {
+            None => (key, key),
+            Some((min, max)) => (
+                (min.0.min(key.0), min.1.min(key.1), min.2.min(key.2)),
+                (max.0.max(key.0), max.1.max(key.1), max.2.max(key.2)),
+            ),
+        });
+    }
+}
+
+fn sort_neighbors(neighbors: &mut [Neighbor]) {
+    neighbors.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then_with(|| a.index.cmp(&b.index))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Mulberry32;
+
+    fn linear_nearest(origin: [f32; 3], positions: &[f32], limit: usize) -> Vec<Neighbor> {
+        let mut all: Vec<Neighbor> = positions
+            .chunks_exact(3)
+            .enumerate()
+            .filter(|(index, _)| index % 5 != 0)
+            .map(|(index, p)| {
+                let dx = p[0] - origin[0];
+                let dy = p[1] - origin[1];
+                let dz = p[2] - origin[2];
+                Neighbor {
+                    index,
+                    distance: (dx * dx + dy * dy + dz * dz).sqrt(),
+                }
+            })
+            .collect();
+        sort_neighbors(&mut all);
+        all.truncate(limit);
+        all
+    }
+
+    #[test]
+    fn grid_matches_linear_scan() {
+        let mut rng = Mulberry32::new(99);
+        let mut positions: Vec<f32> = (0..600).map(|_| rng.next_f32() * 96.0 - 48.0).collect();
+        // Duplicate points to exercise index tie-breaking.
+        positions.extend_from_slice(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        let mut grid = SpatialGrid::from_positions(8.0, &positions);
+
+        for _ in 0..50 {
+            let origin = [
+                rng.next_f32() * 120.0 - 60.0,
+                rng.next_f32() * 12.0 - 6.0,
+                rng.next_f32() * 120.0 - 60.0,
+            ];
+            let got = grid.nearest(origin, &positions, 4, |index| index % 5 != 0);
+            assert_eq!(got, linear_nearest(origin, &positions, 4));
+        }
+
+        positions[3..6].copy_from_slice(&[40.0, -3.0, 40.0]);
+        grid.update(1, [40.0, -3.0, 40.0]);
+        let origin = [40.0, -3.0, 40.5];
+        assert_eq!(
+            grid.nearest(origin, &positions, 4, |index| index % 5 != 0),
+            linear_nearest(origin, &positions, 4)
+        );
+
+        positions.drain(3..6);
+        grid.remove(1);
+        assert_eq!(grid.len(), positions.len() / 3);
+        for origin in [[40.0, -3.0, 40.5], [0.0, 0.0, 0.0], [-30.0, 2.0, 10.0]] {
+            assert_eq!(
+                grid.nearest(origin, &positions, 4, |index| index % 5 != 0),
+                linear_nearest(origin, &positions, 4)
+            );
+        }
+    }
+}
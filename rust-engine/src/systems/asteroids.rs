@@ -1,9 +1,13 @@
 use std::f32::consts::TAU;
 
 use crate::rng::Mulberry32;
+use crate::schema::Vector3;
 use crate::sinks::SinkBonuses;
-use crate::systems::drone_ai::AsteroidMetadata;
+use crate::spatial::SpatialGrid;
+use crate::systems::drone_ai::{AsteroidMetadata, AsteroidRegionMeta};
 
+/// Asteroid count of a freshly created field (`ASTEROID_TARGET` in TS).
+pub const ASTEROID_FIELD_TARGET: usize = 200;
 const BASE_ASTEROID_RICHNESS: f32 = 80.0;
 const POSITION_RING_MIN: f32 = 12.0;
 const POSITION_RING_MAX: f32 = 48.0;
@@ -11,6 +15,11 @@ const POSITION_RING_HEIGHT: f32 = 6.0;
 const SCANNER_RICHNESS_PER_LEVEL: f32 = 0.05;
 const FRACTURE_TIMER_MIN: f32 = 24.0;
 const FRACTURE_TIMER_MAX: f32 = 64.0;
+const FRACTURE_MIN_REGIONS: i32 = 2;
+const FRACTURE_MAX_REGIONS: i32 = 4;
+const FRACTURE_PRIMARY_BIAS: f32 = 1.6;
+const FRACTURE_SEED_MIX: u32 = 0x9e37_79b9;
+const REGION_OFFSET_RADIUS: f32 = 0.7;
 const MIN_SEED: u32 = 1;
 
 #[derive(Clone, Copy)]
@@ -24,11 +33,22 @@ struct ResourceWeights {
 
 #[derive(Clone, Copy)]
 struct HazardDefinition {
+    kind: &'static str,
+    severity: &'static str,
     weight: f32,
 }
 
+/// The hazard an asteroid rolled at spawn, plus the biome seed used to phase its cycle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HazardRoll {
+    pub kind: &'static str,
+    pub severity: &'static str,
+    pub seed: u32,
+}
+
 #[derive(Clone, Copy)]
 struct BiomeDefinition {
+    id: &'static str,
     gravity_multiplier: f32,
     resource_weights: ResourceWeights,
     hazard_profile: &'static [HazardDefinition],
@@ -37,6 +57,7 @@ struct BiomeDefinition {
 const fn biome_definitions() -> [BiomeDefinition; 4] {
     [
         BiomeDefinition {
+            id: "ice",
             gravity_multiplier: 0.9,
             resource_weights: ResourceWeights {
                 ore: 0.6,
@@ -46,11 +67,20 @@ const fn biome_definitions() -> [BiomeDefinition; 4] {
                 ice: 1.0,
             },
             hazard_profile: &[
-                HazardDefinition { weight: 2.0 }, // ionStorm (medium)
-                HazardDefinition { weight: 1.0 }, // microQuakes (low)
+                HazardDefinition {
+                    kind: "ionStorm",
+                    severity: "medium",
+                    weight: 2.0,
+                },
+                HazardDefinition {
+                    kind: "microQuakes",
+                    severity: "low",
+                    weight: 1.0,
+                },
             ],
         },
         BiomeDefinition {
+            id: "metalRich",
             gravity_multiplier: 1.25,
             resource_weights: ResourceWeights {
                 ore: 1.0,
@@ -60,11 +90,20 @@ const fn biome_definitions() -> [BiomeDefinition; 4] {
                 ice: 0.1,
             },
             hazard_profile: &[
-                HazardDefinition { weight: 3.0 }, // solarFlare (medium)
-                HazardDefinition { weight: 2.0 }, // microQuakes (high)
+                HazardDefinition {
+                    kind: "solarFlare",
+                    severity: "medium",
+                    weight: 3.0,
+                },
+                HazardDefinition {
+                    kind: "microQuakes",
+                    severity: "high",
+                    weight: 2.0,
+                },
             ],
         },
         BiomeDefinition {
+            id: "crystal",
             gravity_multiplier: 1.05,
             resource_weights: ResourceWeights {
                 ore: 0.7,
@@ -74,11 +113,20 @@ const fn biome_definitions() -> [BiomeDefinition; 4] {
                 ice: 0.15,
             },
             hazard_profile: &[
-                HazardDefinition { weight: 1.0 }, // solarFlare (low)
-                HazardDefinition { weight: 2.0 }, // ionStorm (high)
+                HazardDefinition {
+                    kind: "solarFlare",
+                    severity: "low",
+                    weight: 1.0,
+                },
+                HazardDefinition {
+                    kind: "ionStorm",
+                    severity: "high",
+                    weight: 2.0,
+                },
             ],
         },
         BiomeDefinition {
+            id: "organic",
             gravity_multiplier: 0.82,
             resource_weights: ResourceWeights {
                 ore: 0.5,
@@ -88,8 +136,16 @@ const fn biome_definitions() -> [BiomeDefinition; 4] {
                 ice: 0.35,
             },
             hazard_profile: &[
-                HazardDefinition { weight: 3.0 }, // sporeBurst (medium)
-                HazardDefinition { weight: 1.0 }, // ionStorm (low)
+                HazardDefinition {
+                    kind: "sporeBurst",
+                    severity: "medium",
+                    weight: 3.0,
+                },
+                HazardDefinition {
+                    kind: "ionStorm",
+                    severity: "low",
+                    weight: 1.0,
+                },
             ],
         },
     ]
@@ -132,12 +188,21 @@ fn normalize_resource_weights(weights: ResourceWeights) -> ResourceWeights {
 }
 
 fn choose_biome(rng: &mut Mulberry32) -> BiomeDefinition {
+    choose_biome_biased(rng, None)
+}
+
+/// `chooseBiome` with an optional `(biome id, bias)` weight override.
+fn choose_biome_biased(rng: &mut Mulberry32, bias: Option<(&str, f32)>) -> BiomeDefinition {
     let definitions = biome_definitions();
     let mut total: f32 = 0.0;
     let mut weighted = Vec::with_capacity(definitions.len());
     for def in definitions.iter() {
-        total += 1.0;
-        weighted.push((def, 1.0));
+        let weight = match bias {
+            Some((id, value)) if id == def.id => value.max(0.0),
+            _ => 1.0,
+        };
+        total += weight;
+        weighted.push((def, weight));
     }
     let mut roll = rng.next_f32() * total.max(0.0001);
     let mut chosen = *definitions.last().unwrap_or(&definitions[0]);
@@ -151,7 +216,7 @@ fn choose_biome(rng: &mut Mulberry32) -> BiomeDefinition {
     chosen
 }
 
-fn roll_hazard(rng: &mut Mulberry32, biome: &BiomeDefinition) {
+fn roll_hazard(rng: &mut Mulberry32, biome: &BiomeDefinition) -> Option<HazardDefinition> {
     let mut total = 0.0;
     let mut weighted = Vec::with_capacity(biome.hazard_profile.len());
     for hazard in biome.hazard_profile {
@@ -160,27 +225,191 @@ fn roll_hazard(rng: &mut Mulberry32, biome: &BiomeDefinition) {
         weighted.push(weight);
     }
     if weighted.is_empty() || total <= 0.0 {
-        return;
+        return None;
     }
-    // Consume one RNG sample to mirror TS hazard roll.
+    // One RNG sample, as in the TS hazard roll.
     let mut roll = rng.next_f32() * total;
-    for weight in weighted {
+    let mut chosen = biome.hazard_profile.last().copied();
+    for (hazard, weight) in biome.hazard_profile.iter().zip(weighted) {
         roll -= weight;
         if roll <= 0.0 {
+            chosen = Some(*hazard);
             break;
         }
     }
+    chosen
+}
+
+/// Biome and fracture countdown of an asteroid (`AsteroidBiomeState` in TS).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FractureState {
+    pub biome_id: String,
+    pub timer: f32,
+    pub seed: u32,
+    pub count: u32,
+    pub radius: f32,
+}
+
+struct BiomeRoll {
+    weights: ResourceWeights,
+    gravity_multiplier: f32,
+    hazard: Option<HazardRoll>,
+    biome_id: &'static str,
+    fracture_timer: f32,
+    fracture_seed: u32,
 }
 
-fn pick_biome_profile(rng: &mut Mulberry32) -> (ResourceWeights, f32) {
+fn pick_biome_profile(rng: &mut Mulberry32) -> BiomeRoll {
     let biome = choose_biome(rng);
     let normalized = normalize_resource_weights(biome.resource_weights);
-    roll_hazard(rng, &biome);
+    let hazard = roll_hazard(rng, &biome);
     // Fracture timer + seed draw to match TS createAsteroidBiomeState
-    let _fracture_timer = random_range(rng, FRACTURE_TIMER_MIN, FRACTURE_TIMER_MAX);
+    let fracture_timer = random_range(rng, FRACTURE_TIMER_MIN, FRACTURE_TIMER_MAX);
     let seed_scaled = (rng.next_f32() * 0xffff_ffffu32 as f32).floor() as u32;
-    let _fracture_seed = seed_scaled.max(MIN_SEED);
-    (normalized, clamp_gravity(biome.gravity_multiplier))
+    let fracture_seed = seed_scaled.max(MIN_SEED);
+    let hazard = hazard.map(|h| HazardRoll {
+        kind: h.kind,
+        severity: h.severity,
+        seed: fracture_seed,
+    });
+    BiomeRoll {
+        weights: normalized,
+        gravity_multiplier: clamp_gravity(biome.gravity_multiplier),
+        hazard,
+        biome_id: biome.id,
+        fracture_timer,
+        fracture_seed,
+    }
+}
+
+fn mix_seed(seed: u32, iteration: u32) -> u32 {
+    seed ^ iteration.wrapping_add(1).wrapping_mul(FRACTURE_SEED_MIX)
+}
+
+fn random_region_offset(rng: &mut Mulberry32, radius: f32) -> Vector3 {
+    let u = rng.next_f32();
+    let v = rng.next_f32();
+    let theta = TAU * u;
+    let phi = (2.0 * v - 1.0).clamp(-1.0, 1.0).acos();
+    let scale = radius * REGION_OFFSET_RADIUS;
+    [
+        phi.sin() * theta.cos() * scale,
+        phi.cos() * scale,
+        phi.sin() * theta.sin() * scale,
+    ]
+}
+
+fn profile_array(weights: &ResourceWeights) -> [f32; 5] {
+    [
+        weights.ore,
+        weights.ice,
+        weights.metals,
+        weights.crystals,
+        weights.organics,
+    ]
+}
+
+/// Splits an asteroid into 2-4 biome regions (`generateFractureRegions` in TS).
+/// Uses its own RNG seeded from the fracture seed and count, so the main stream is untouched.
+pub fn generate_fracture_regions(asteroid_id: &str, state: &FractureState) -> Vec<AsteroidRegionMeta> {
+    let mut rng = Mulberry32::new(mix_seed(state.seed, state.count));
+    let span = FRACTURE_MAX_REGIONS - FRACTURE_MIN_REGIONS;
+    let extra = rng.next_int(0, span).unwrap_or(0);
+    let count = (FRACTURE_MIN_REGIONS + extra).min(FRACTURE_MAX_REGIONS) as usize;
+
+    let raw: Vec<f32> = (0..count).map(|_| random_range(&mut rng, 0.3, 1.5)).collect();
+    let total: f32 = raw.iter().map(|w| w.max(0.01)).sum();
+
+    (0..count)
+        .map(|i| {
+            let biome = if i == 0 {
+                choose_biome_biased(&mut rng, Some((state.biome_id.as_str(), FRACTURE_PRIMARY_BIAS)))
+            } else {
+                choose_biome(&mut rng)
+            };
+            let weights = normalize_resource_weights(biome.resource_weights);
+            let hazard = roll_hazard(&mut rng, &biome);
+            let offset = random_region_offset(&mut rng, state.radius);
+            AsteroidRegionMeta {
+                id: format!("{}-r{}-{}", asteroid_id, state.count, i),
+                weight: raw[i].max(0.01) / total,
+                gravity_multiplier: biome.gravity_multiplier,
+                offset,
+                hazard_severity: hazard.map(|h| h.severity.to_string()),
+                hazard_kind: hazard.map(|h| h.kind.to_string()),
+                biome_id: Some(biome.id.to_string()),
+                resource_profile: Some(profile_array(&weights)),
+            }
+        })
+        .collect()
+}
+
+/// Counts down fracture timers; expired asteroids split into new regions, and their blended
+/// gravity and resource profile replace the asteroid's own. Fractured indices are reported.
+pub fn sys_fracture(
+    asteroid_metadata: &mut [AsteroidMetadata],
+    asteroid_index_to_id: &[String],
+    asteroid_resource_profile: &mut [f32], // [ore, ice, metals, crystals, organics] * M
+    fractured_indices: &mut Vec<usize>,
+    rng: &mut Mulberry32,
+    dt: f32,
+) {
+    fractured_indices.clear();
+    if dt <= 0.0 {
+        return;
+    }
+    let count = asteroid_metadata
+        .len()
+        .min(asteroid_index_to_id.len())
+        .min(asteroid_resource_profile.len() / 5);
+    for i in 0..count {
+        let entry = &mut asteroid_metadata[i];
+        let Some(state) = entry.fracture.as_mut() else {
+            continue;
+        };
+        state.timer -= dt;
+        if state.timer > 0.0 {
+            continue;
+        }
+        state.count += 1;
+        state.timer = random_range(rng, FRACTURE_TIMER_MIN, FRACTURE_TIMER_MAX);
+
+        let regions = generate_fracture_regions(&asteroid_index_to_id[i], state);
+        let mut gravity = 0.0;
+        let mut blended = [0.0f32; 5];
+        for region in &regions {
+            gravity += region.gravity_multiplier * region.weight;
+            if let Some(profile) = region.resource_profile {
+                for (acc, value) in blended.iter_mut().zip(profile) {
+                    *acc += value * region.weight;
+                }
+            }
+        }
+        let normalized = normalize_resource_weights(ResourceWeights {
+            ore: blended[0],
+            ice: blended[1],
+            metals: blended[2],
+            crystals: blended[3],
+            organics: blended[4],
+        });
+
+        entry.gravity_multiplier = gravity.max(0.01);
+        entry.regions = regions;
+        asteroid_resource_profile[i * 5..i * 5 + 5].copy_from_slice(&profile_array(&normalized));
+        fractured_indices.push(i);
+    }
+}
+
+/// Name of the largest share in an `[ore, ice, metals, crystals, organics]` profile.
+pub fn dominant_resource_of(profile: &[f32]) -> &'static str {
+    let get = |i: usize| profile.get(i).copied().unwrap_or(0.0);
+    dominant_resource(&ResourceWeights {
+        ore: get(0),
+        ice: get(1),
+        metals: get(2),
+        crystals: get(3),
+        organics: get(4),
+    })
 }
 
 fn dominant_resource(profile: &ResourceWeights) -> &'static str {
@@ -207,6 +436,8 @@ pub fn sys_asteroids(
     asteroid_resource_profile: &mut [f32],
     asteroid_metadata: &mut [AsteroidMetadata],
     respawned_indices: &mut Vec<usize>,
+    respawned_hazards: &mut Vec<(usize, HazardRoll)>,
+    asteroid_grid: &mut SpatialGrid,
     rng: &mut Mulberry32,
     sink_bonuses: &SinkBonuses,
     scanner_level: i32,
@@ -214,9 +445,10 @@ pub fn sys_asteroids(
 ) {
     let count = asteroid_positions.len() / 3;
     respawned_indices.clear();
+    respawned_hazards.clear();
     for i in 0..count {
         if asteroid_ore.get(i).copied().unwrap_or(0.0) <= 0.01 {
-            respawn_asteroid(
+            let hazard = respawn_asteroid(
                 i,
                 asteroid_positions,
                 asteroid_ore,
@@ -227,60 +459,187 @@ pub fn sys_asteroids(
                 sink_bonuses,
                 scanner_level,
             );
+            asteroid_grid.update(
+                i,
+                [
+                    asteroid_positions[i * 3],
+                    asteroid_positions[i * 3 + 1],
+                    asteroid_positions[i * 3 + 2],
+                ],
+            );
             respawned_indices.push(i);
+            if let Some(hazard) = hazard {
+                respawned_hazards.push((i, hazard));
+            }
         }
     }
 }
 
-fn respawn_asteroid(
-    index: usize,
-    positions: &mut [f32],
-    ore: &mut [f32],
-    max_ore: &mut [f32],
-    profile: &mut [f32],
-    metadata: &mut [AsteroidMetadata],
+/// A freshly rolled asteroid, drawn in the same RNG order as TS `createAsteroid`.
+pub struct GeneratedAsteroid {
+    pub position: Vector3,
+    pub ore: f32,
+    /// [ore, ice, metals, crystals, organics]
+    pub profile: [f32; 5],
+    pub gravity_multiplier: f32,
+    pub dominant_resource: &'static str,
+    pub hazard: Option<HazardRoll>,
+    pub fracture: FractureState,
+}
+
+impl GeneratedAsteroid {
+    /// Serializes into the snapshot asteroid shape.
+    pub fn to_json(&self, id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "position": self.position,
+            "oreRemaining": self.ore,
+            "maxOre": self.ore,
+            "resourceProfile": {
+                "ore": self.profile[0],
+                "ice": self.profile[1],
+                "metals": self.profile[2],
+                "crystals": self.profile[3],
+                "organics": self.profile[4],
+            },
+            "gravityMultiplier": self.gravity_multiplier,
+            "dominantResource": self.dominant_resource,
+            "radius": self.fracture.radius,
+            "biome": biome_json(&self.fracture, self.hazard.as_ref()),
+            "regions": null,
+        })
+    }
+}
+
+/// Serializes the biome/fracture state stored under an asteroid's `biome` key.
+pub fn fracture_json(state: &FractureState) -> serde_json::Value {
+    serde_json::json!({
+        "biomeId": state.biome_id,
+        "fractureTimer": state.timer,
+        "fractureSeed": state.seed,
+        "fractureCount": state.count,
+    })
+}
+
+/// `fracture_json` plus the asteroid-wide hazard in the TS `{ id, severity }` shape.
+pub fn biome_json(state: &FractureState, hazard: Option<&HazardRoll>) -> serde_json::Value {
+    let mut biome = fracture_json(state);
+    biome["hazard"] = hazard
+        .map(|h| serde_json::json!({ "id": h.kind, "severity": h.severity }))
+        .unwrap_or(serde_json::Value::Null);
+    biome
+}
+
+pub fn generate_asteroid(
     rng: &mut Mulberry32,
     sink_bonuses: &SinkBonuses,
     scanner_level: i32,
-) {
+) -> GeneratedAsteroid {
     // Position: randomOnRing(12, 48, 6)
     let dist = random_range(rng, POSITION_RING_MIN, POSITION_RING_MAX);
     let angle = random_range(rng, 0.0, TAU);
     let y = random_range(rng, -POSITION_RING_HEIGHT, POSITION_RING_HEIGHT);
 
-    positions[index * 3] = dist * angle.cos();
-    positions[index * 3 + 1] = y;
-    positions[index * 3 + 2] = dist * angle.sin();
-
     // Max Ore
     let richness_bias = (1.0 + (scanner_level as f32) * SCANNER_RICHNESS_PER_LEVEL)
         * sink_bonuses.asteroid_richness_multiplier;
     let richness = random_range(rng, 0.8, 1.2) * richness_bias.max(0.0);
-    let new_ore = BASE_ASTEROID_RICHNESS * richness;
+    let ore = BASE_ASTEROID_RICHNESS * richness;
+
+    let radius = random_range(rng, 0.6, 1.4);
+
+    let biome = pick_biome_profile(rng);
+    let weights = biome.weights;
+
+    // Consume remaining draws for rotation/spin parity
+    let _rotation = random_range(rng, 0.0, TAU);
+    let _spin = random_range(rng, -0.4, 0.4);
 
-    max_ore[index] = new_ore;
-    ore[index] = new_ore;
+    GeneratedAsteroid {
+        position: [dist * angle.cos(), y, dist * angle.sin()],
+        ore,
+        profile: profile_array(&weights),
+        gravity_multiplier: biome.gravity_multiplier,
+        dominant_resource: dominant_resource(&weights),
+        hazard: biome.hazard,
+        fracture: FractureState {
+            biome_id: biome.biome_id.to_string(),
+            timer: biome.fracture_timer,
+            seed: biome.fracture_seed,
+            count: 0,
+            radius,
+        },
+    }
+}
 
-    // Radius draw (not stored) to mirror TS createAsteroid randomness
-    let _radius = random_range(rng, 0.6, 1.4);
+fn respawn_asteroid(
+    index: usize,
+    positions: &mut [f32],
+    ore: &mut [f32],
+    max_ore: &mut [f32],
+    profile: &mut [f32],
+    metadata: &mut [AsteroidMetadata],
+    rng: &mut Mulberry32,
+    sink_bonuses: &SinkBonuses,
+    scanner_level: i32,
+) -> Option<HazardRoll> {
+    let asteroid = generate_asteroid(rng, sink_bonuses, scanner_level);
 
-    let (weights, gravity_multiplier) = pick_biome_profile(rng);
-    let p_idx = index * 5;
-    profile[p_idx] = weights.ore;
-    profile[p_idx + 1] = weights.ice;
-    profile[p_idx + 2] = weights.metals;
-    profile[p_idx + 3] = weights.crystals;
-    profile[p_idx + 4] = weights.organics;
+    positions[index * 3..index * 3 + 3].copy_from_slice(&asteroid.position);
+    max_ore[index] = asteroid.ore;
+    ore[index] = asteroid.ore;
+    profile[index * 5..index * 5 + 5].copy_from_slice(&asteroid.profile);
 
     if let Some(entry) = metadata.get_mut(index) {
-        entry.gravity_multiplier = gravity_multiplier;
+        entry.gravity_multiplier = asteroid.gravity_multiplier;
         entry.regions.clear();
+        entry.fracture = Some(asteroid.fracture.clone());
     }
+    asteroid.hazard
+}
 
-    // Consume remaining draws for rotation/spin parity
-    let _rotation = random_range(rng, 0.0, TAU);
-    let _spin = random_range(rng, -0.4, 0.4);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Keep dominant resource calculated for potential future parity checks
-    let _ = dominant_resource(&weights);
+    #[test]
+    fn fracture_splits_into_weighted_regions_when_timer_expires() {
+        let state = FractureState {
+            biome_id: "ice".to_string(),
+            timer: 1.0,
+            seed: 12345,
+            count: 0,
+            radius: 2.0,
+        };
+        let first = generate_fracture_regions("asteroid-1", &state);
+        let again = generate_fracture_regions("asteroid-1", &state);
+        assert_eq!(
+            first.iter().map(|r| (&r.id, r.weight)).collect::<Vec<_>>(),
+            again.iter().map(|r| (&r.id, r.weight)).collect::<Vec<_>>()
+        );
+        assert!((2..=4).contains(&first.len()));
+        let total: f32 = first.iter().map(|r| r.weight).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+
+        let mut metadata = vec![AsteroidMetadata {
+            gravity_multiplier: 1.0,
+            regions: Vec::new(),
+            fracture: Some(state),
+        }];
+        let ids = vec!["asteroid-1".to_string()];
+        let mut profile = vec![1.0, 0.0, 0.0, 0.0, 0.0];
+        let mut fractured = Vec::new();
+        let mut rng = Mulberry32::new(7);
+
+        sys_fracture(&mut metadata, &ids, &mut profile, &mut fractured, &mut rng, 0.5);
+        assert!(fractured.is_empty());
+
+        sys_fracture(&mut metadata, &ids, &mut profile, &mut fractured, &mut rng, 0.5);
+        assert_eq!(fractured, vec![0]);
+        let fracture = metadata[0].fracture.as_ref().unwrap();
+        assert_eq!(fracture.count, 1);
+        assert!(fracture.timer >= FRACTURE_TIMER_MIN);
+        assert!(!metadata[0].regions.is_empty());
+        assert!((profile.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    }
 }
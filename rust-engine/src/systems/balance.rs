@@ -0,0 +1,90 @@
+use crate::constants::{DRONE_STATE_DISABLED, DRONE_STATE_IDLE, DRONE_STATE_UNLOADING};
+
+/// Seconds between auto-balancer passes; each pass moves at most one drone.
+pub const DRONE_BALANCE_INTERVAL: f32 = 10.0;
+
+/// Picks drones owned by `from_factory` to hand over, idle drones first, then in index order.
+/// Disabled drones and drones mid-unload stay where they are.
+pub fn pick_transferable_drones(
+    drone_states: &[f32],
+    drone_owner_factory_index: &[f32],
+    from_factory: usize,
+    count: usize,
+) -> Vec<usize> {
+    let mut candidates: Vec<usize> = (0..drone_states.len().min(drone_owner_factory_index.len()))
+        .filter(|&i| drone_owner_factory_index[i] == from_factory as f32)
+        .filter(|&i| {
+            drone_states[i] != DRONE_STATE_DISABLED && drone_states[i] != DRONE_STATE_UNLOADING
+        })
+        .collect();
+    candidates.sort_by_key(|&i| (drone_states[i] != DRONE_STATE_IDLE, i));
+    candidates.truncate(count);
+    candidates
+}
+
+/// Finds one ownership move that narrows the gap between each factory's drone count and its
+/// share of the fleet, weighted by docking capacity. Returns `(drone_index, to_factory)`.
+pub fn sys_drone_balance(
+    drone_states: &[f32],
+    drone_owner_factory_index: &[f32],
+    factory_docking_capacity: &[i32],
+) -> Option<(usize, usize)> {
+    let factory_count = factory_docking_capacity.len();
+    if factory_count < 2 {
+        return None;
+    }
+
+    let mut counts = vec![0usize; factory_count];
+    for &owner in drone_owner_factory_index {
+        if owner >= 0.0 && (owner as usize) < factory_count {
+            counts[owner as usize] += 1;
+        }
+    }
+    let total: usize = counts.iter().sum();
+    let weights: Vec<f32> = factory_docking_capacity
+        .iter()
+        .map(|&capacity| capacity.max(1) as f32)
+        .collect();
+    let weight_sum: f32 = weights.iter().sum();
+    let surplus: Vec<f32> = counts
+        .iter()
+        .zip(&weights)
+        .map(|(&count, &weight)| count as f32 - total as f32 * weight / weight_sum)
+        .collect();
+
+    // Ties go to the lowest index so the pass is deterministic.
+    let (donor, _) = surplus
+        .iter()
+        .enumerate()
+        .fold((0, f32::MIN), |best, (i, &s)| if s > best.1 { (i, s) } else { best });
+    let (receiver, _) = surplus
+        .iter()
+        .enumerate()
+        .fold((0, f32::MAX), |best, (i, &s)| if s < best.1 { (i, s) } else { best });
+    // Moving one drone shifts the gap by two; anything smaller would just swap the imbalance.
+    if surplus[donor] - surplus[receiver] <= 1.0 + 1e-4 {
+        return None;
+    }
+
+    pick_transferable_drones(drone_states, drone_owner_factory_index, donor, 1)
+        .first()
+        .map(|&drone| (drone, receiver))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DRONE_STATE_MINING;
+
+    #[test]
+    fn balancer_moves_idle_drones_toward_capacity_share() {
+        let states = [DRONE_STATE_MINING, DRONE_STATE_IDLE, DRONE_STATE_IDLE, DRONE_STATE_MINING];
+        let owners = [0.0, 0.0, 0.0, 1.0];
+
+        assert_eq!(sys_drone_balance(&states, &owners, &[1, 1]), Some((1, 1)));
+        // Factory 0 has three docks to factory 1's one, so 3:1 is already balanced.
+        assert_eq!(sys_drone_balance(&states, &owners, &[3, 1]), None);
+        assert_eq!(sys_drone_balance(&states, &[0.0, 0.0, 1.0, 1.0], &[1, 1]), None);
+        assert_eq!(pick_transferable_drones(&states, &owners, 0, 5), vec![1, 2, 0]);
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rng::Mulberry32;
+use crate::schema::{ContractReward, ContractSnapshot, ContractsSnapshot, Prestige, Resources};
+use crate::systems::events::push_event;
+use crate::systems::market::warehouse_stock_mut;
+
+/// Seconds between new contract offers.
+pub const CONTRACT_OFFER_INTERVAL: f32 = 60.0;
+pub const MAX_CONTRACT_OFFERS: usize = 3;
+pub const MAX_ACTIVE_CONTRACTS: usize = 3;
+/// Seconds an offer stays open before it lapses.
+pub const CONTRACT_OFFER_LIFETIME: f32 = 300.0;
+/// Chance an offer also pays a bonus prestige core.
+const CORE_BONUS_CHANCE: f32 = 0.1;
+
+/// Shape of the offers posted for one resource.
+struct ContractTemplate {
+    resource: &'static str,
+    min_amount: f32,
+    max_amount: f32,
+    duration: f32,
+    bars_per_unit: f32,
+    credits_per_unit: f32,
+}
+
+const CONTRACT_TEMPLATES: [ContractTemplate; 5] = [
+    ContractTemplate {
+        resource: "ore",
+        min_amount: 300.0,
+        max_amount: 1000.0,
+        duration: 600.0,
+        bars_per_unit: 0.05,
+        credits_per_unit: 1.5,
+    },
+    ContractTemplate {
+        resource: "ice",
+        min_amount: 200.0,
+        max_amount: 600.0,
+        duration: 600.0,
+        bars_per_unit: 0.08,
+        credits_per_unit: 2.0,
+    },
+    ContractTemplate {
+        resource: "metals",
+        min_amount: 150.0,
+        max_amount: 500.0,
+        duration: 600.0,
+        bars_per_unit: 0.12,
+        credits_per_unit: 4.0,
+    },
+    ContractTemplate {
+        resource: "crystals",
+        min_amount: 100.0,
+        max_amount: 500.0,
+        duration: 600.0,
+        bars_per_unit: 0.2,
+        credits_per_unit: 7.0,
+    },
+    ContractTemplate {
+        resource: "organics",
+        min_amount: 100.0,
+        max_amount: 400.0,
+        duration: 480.0,
+        bars_per_unit: 0.15,
+        credits_per_unit: 5.5,
+    },
+];
+
+/// A contract leaving the books.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractEvent {
+    pub contract_id: String,
+    pub resource: String,
+    /// "completed", "expired" or "abandoned".
+    pub outcome: String,
+    pub game_time: f32,
+}
+
+pub fn push_contract_event(
+    events: &mut Vec<ContractEvent>,
+    contract: &ContractSnapshot,
+    outcome: &str,
+    game_time: f32,
+) {
+    push_event(
+        events,
+        ContractEvent {
+            contract_id: contract.id.clone(),
+            resource: contract.resource.clone(),
+            outcome: outcome.to_string(),
+            game_time,
+        },
+    );
+}
+
+/// Rolls a new offer from the simulation RNG so the offer stream replays per save.
+fn generate_offer(contracts: &mut ContractsSnapshot, rng: &mut Mulberry32, game_time: f32) -> ContractSnapshot {
+    let pick = rng
+        .next_int(0, CONTRACT_TEMPLATES.len() as i32 - 1)
+        .unwrap_or(0) as usize;
+    let template = &CONTRACT_TEMPLATES[pick.min(CONTRACT_TEMPLATES.len() - 1)];
+    let amount = rng
+        .next_range(template.min_amount, template.max_amount)
+        .unwrap_or(template.min_amount);
+    // Round to tens so offers read like "deliver 430 crystals".
+    let amount = ((amount / 10.0).round() * 10.0).max(10.0);
+    let cores = if rng.next_f32() < CORE_BONUS_CHANCE { 1 } else { 0 };
+
+    contracts.next_id += 1;
+    ContractSnapshot {
+        id: format!("contract-{}", contracts.next_id),
+        resource: template.resource.to_string(),
+        amount,
+        delivered: 0.0,
+        duration: template.duration,
+        deadline: game_time + CONTRACT_OFFER_LIFETIME,
+        reward: ContractReward {
+            bars: amount * template.bars_per_unit,
+            credits: amount * template.credits_per_unit,
+            cores,
+        },
+    }
+}
+
+/// Fills active contracts from this tick's warehouse deliveries, pays out completed ones,
+/// expires overdue ones and posts new offers on schedule. Delivered goods are handed over,
+/// so they leave the warehouse.
+pub fn sys_contracts(
+    contracts: &mut ContractsSnapshot,
+    events: &mut Vec<ContractEvent>,
+    warehouse_deliveries: &BTreeMap<String, f32>,
+    resources: &mut Resources,
+    prestige: &mut Prestige,
+    rng: &mut Mulberry32,
+    game_time: f32,
+) {
+    for (resource, &amount) in warehouse_deliveries {
+        let mut left = amount;
+        for contract in contracts.active.iter_mut().filter(|c| &c.resource == resource) {
+            if left <= 0.0 {
+                break;
+            }
+            let taken = (contract.amount - contract.delivered).max(0.0).min(left);
+            contract.delivered += taken;
+            left -= taken;
+        }
+        if let Some(stock) = warehouse_stock_mut(resources, resource) {
+            *stock = (*stock - (amount - left)).max(0.0);
+        }
+    }
+
+    let mut index = 0;
+    while index < contracts.active.len() {
+        let contract = &contracts.active[index];
+        if contract.delivered >= contract.amount {
+            let contract = contracts.active.remove(index);
+            resources.bars += contract.reward.bars;
+            resources.credits += contract.reward.credits;
+            prestige.cores += contract.reward.cores;
+            push_contract_event(events, &contract, "completed", game_time);
+        } else if game_time > contract.deadline {
+            let contract = contracts.active.remove(index);
+            push_contract_event(events, &contract, "expired", game_time);
+        } else {
+            index += 1;
+        }
+    }
+
+    contracts.offers.retain(|offer| game_time <= offer.deadline);
+    while contracts.offers.len() < MAX_CONTRACT_OFFERS && game_time >= contracts.next_offer_at {
+        let offer = generate_offer(contracts, rng, game_time);
+        contracts.offers.push(offer);
+        contracts.next_offer_at += CONTRACT_OFFER_INTERVAL;
+    }
+    // Don't bank offers while the board is full or after a long gap.
+    if contracts.next_offer_at < game_time {
+        contracts.next_offer_at = game_time + CONTRACT_OFFER_INTERVAL;
+    }
+}
+
+/// Moves an offer onto the active list and starts its clock.
+pub fn accept_contract(contracts: &mut ContractsSnapshot, contract_id: &str, game_time: f32) -> bool {
+    if contracts.active.len() >= MAX_ACTIVE_CONTRACTS {
+        return false;
+    }
+    let Some(position) = contracts.offers.iter().position(|c| c.id == contract_id) else {
+        return false;
+    };
+    let mut contract = contracts.offers.remove(position);
+    contract.deadline = game_time + contract.duration;
+    contracts.active.push(contract);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contracts_fill_from_deliveries_and_expire_on_their_deadline() {
+        let mut contracts = ContractsSnapshot::default();
+        let mut events = Vec::new();
+        let mut resources = Resources::default();
+        let mut prestige = Prestige { cores: 0 };
+        let mut rng = Mulberry32::new(11);
+        let none = BTreeMap::new();
+
+        sys_contracts(&mut contracts, &mut events, &none, &mut resources, &mut prestige, &mut rng, 0.0);
+        assert_eq!(contracts.offers.len(), 1);
+        sys_contracts(&mut contracts, &mut events, &none, &mut resources, &mut prestige, &mut rng, 200.0);
+        assert_eq!(contracts.offers.len(), MAX_CONTRACT_OFFERS, "the board refills up to its cap");
+
+        let first = contracts.offers[0].clone();
+        let second = contracts.offers[1].clone();
+        assert!(accept_contract(&mut contracts, &first.id, 200.0));
+        assert!(accept_contract(&mut contracts, &second.id, 200.0));
+
+        // Fill the first contract; the goods leave the warehouse.
+        *warehouse_stock_mut(&mut resources, &first.resource).unwrap() = first.amount + 5.0;
+        let deliveries = BTreeMap::from([(first.resource.clone(), first.amount + 5.0)]);
+        sys_contracts(&mut contracts, &mut events, &deliveries, &mut resources, &mut prestige, &mut rng, 210.0);
+        assert_eq!(events[0].contract_id, first.id);
+        assert_eq!(events[0].outcome, "completed");
+        assert_eq!(resources.credits, first.reward.credits);
+        if first.resource != second.resource {
+            assert_eq!(*warehouse_stock_mut(&mut resources, &first.resource).unwrap(), 5.0);
+        }
+
+        let deadline = 200.0 + second.duration;
+        sys_contracts(&mut contracts, &mut events, &none, &mut resources, &mut prestige, &mut rng, deadline + 1.0);
+        assert!(contracts.active.is_empty());
+        assert_eq!(events.last().unwrap().outcome, "expired");
+        assert!(contracts.offers.iter().all(|offer| offer.deadline >= deadline + 1.0));
+    }
+}
@@ -1,14 +1,18 @@
 use crate::constants::{
-    DRONE_MAX_BATTERY, DRONE_MAX_CARGO, DRONE_MINING_RATE, DRONE_SPEED, DRONE_STATE_IDLE,
-    DRONE_STATE_MINING, DRONE_STATE_RETURNING, DRONE_STATE_TO_ASTEROID,
+    DRONE_STATE_IDLE, DRONE_STATE_MINING, DRONE_STATE_RETURNING, DRONE_STATE_TO_ASTEROID,
 };
+use crate::loadouts::{drone_stats, DroneStats, LoadoutMultipliers};
 use crate::modifiers::ResourceModifierSnapshot;
 use crate::parity_debug;
 use crate::rng::Mulberry32;
-use crate::schema::{DroneFlight, FactorySnapshot, Modules, SimulationSnapshot, TravelSnapshot};
+use crate::schema::{
+    DroneFlight, DroneOrder, FactorySnapshot, Modules, SimulationSnapshot, TravelSnapshot,
+};
 use crate::sinks::SinkBonuses;
+use crate::spatial::{Neighbor, SpatialGrid};
+use crate::systems::asteroids::FractureState;
 use serde_json::json;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::f32::consts::PI;
 
 const NEARBY_LIMIT: usize = 4;
@@ -19,6 +23,13 @@ const MAX_OFFSET_DISTANCE: f32 = 3.0;
 const SEED_MIX: u32 = 0x9e37_79b9;
 const FRAC_PI_5: f32 = PI / 5.0;
 const TRAVEL_TIME_QUANTIZATION: f32 = 1000.0;
+/// Drones waiting on a full dock hold in rings of eight around the factory.
+const QUEUE_ORBIT_SLOTS_PER_RING: usize = 8;
+const QUEUE_ORBIT_RADIUS: f32 = 5.0;
+const QUEUE_ORBIT_RING_SPACING: f32 = 1.5;
+const QUEUE_ORBIT_HEIGHT: f32 = 2.0;
+/// A waiting drone this close to its holding spot does not move again.
+const QUEUE_ORBIT_TOLERANCE: f32 = 0.25;
 
 #[derive(Clone, Debug, Default)]
 pub struct AsteroidRegionMeta {
@@ -27,12 +38,45 @@ pub struct AsteroidRegionMeta {
     pub gravity_multiplier: f32,
     pub offset: [f32; 3],
     pub hazard_severity: Option<String>,
+    pub hazard_kind: Option<String>,
+    pub biome_id: Option<String>,
+    /// [ore, ice, metals, crystals, organics]
+    pub resource_profile: Option<[f32; 5]>,
+}
+
+impl AsteroidRegionMeta {
+    fn is_safe(&self) -> bool {
+        self.hazard_severity.as_deref() != Some("high")
+    }
+
+    /// Serializes into the snapshot region shape read by `asteroid_metadata_from_value`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let hazard = match (&self.hazard_kind, &self.hazard_severity) {
+            (Some(kind), Some(severity)) => json!({ "id": kind, "severity": severity }),
+            (None, Some(severity)) => json!({ "severity": severity }),
+            _ => serde_json::Value::Null,
+        };
+        let profile = self.resource_profile.map(|p| {
+            json!({ "ore": p[0], "ice": p[1], "metals": p[2], "crystals": p[3], "organics": p[4] })
+        });
+        json!({
+            "id": self.id,
+            "biomeId": self.biome_id,
+            "weight": self.weight,
+            "gravityMultiplier": self.gravity_multiplier,
+            "offset": self.offset,
+            "hazard": hazard,
+            "resourceProfile": profile,
+        })
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct AsteroidMetadata {
     pub gravity_multiplier: f32,
     pub regions: Vec<AsteroidRegionMeta>,
+    /// Present for asteroids that carry a biome fracture countdown.
+    pub fracture: Option<FractureState>,
 }
 
 #[derive(PartialEq, Eq)]
@@ -48,6 +92,17 @@ struct FactoryAssignment {
     factory_id: String,
     destination: [f32; 3],
     start_travel: bool,
+    /// Place among drones waiting for a free dock, when not cleared to travel in.
+    queue_slot: Option<usize>,
+}
+
+/// Asteroid buffers and the grid over their positions, as read by automatic target picks.
+struct AsteroidField<'a> {
+    positions: &'a [f32],
+    grid: &'a SpatialGrid,
+    ore: &'a [f32],
+    metadata: &'a [AsteroidMetadata],
+    index_to_id: &'a [String],
 }
 
 #[derive(Clone)]
@@ -70,64 +125,7 @@ pub fn extract_asteroid_metadata(
         for asteroid in asteroids {
             if let Some(id) = asteroid.get("id").and_then(|v| v.as_str()) {
                 if let Some(&idx) = asteroid_id_to_index.get(id) {
-                    let asteroid_id = id.to_string();
-                    let gravity = asteroid
-                        .get("gravityMultiplier")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(1.0) as f32;
-
-                    let mut entry = AsteroidMetadata {
-                        gravity_multiplier: gravity.max(0.01),
-                        regions: Vec::new(),
-                    };
-
-                    if let Some(regions) = asteroid.get("regions").and_then(|v| v.as_array()) {
-                        for (region_index, region) in regions.iter().enumerate() {
-                            let offset = region
-                                .get("offset")
-                                .and_then(|v| v.as_array())
-                                .and_then(|arr| {
-                                    if arr.len() >= 3 {
-                                        Some([
-                                            arr[0].as_f64().unwrap_or(0.0) as f32,
-                                            arr[1].as_f64().unwrap_or(0.0) as f32,
-                                            arr[2].as_f64().unwrap_or(0.0) as f32,
-                                        ])
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .unwrap_or([0.0, 0.0, 0.0]);
-                            let weight = region
-                                .get("weight")
-                                .and_then(|v| v.as_f64())
-                                .unwrap_or(1.0) as f32;
-                            let gravity_multiplier = region
-                                .get("gravityMultiplier")
-                                .and_then(|v| v.as_f64())
-                                .unwrap_or(entry.gravity_multiplier as f64) as f32;
-                            let hazard_severity = region
-                                .get("hazard")
-                                .and_then(|h| h.get("severity"))
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-                            let id = region
-                                .get("id")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| format!("{}-r{}", asteroid_id, region_index));
-
-                            entry.regions.push(AsteroidRegionMeta {
-                                id,
-                                weight,
-                                gravity_multiplier: gravity_multiplier.max(0.01),
-                                offset,
-                                hazard_severity,
-                            });
-                        }
-                    }
-
-                    metadata[idx] = entry;
+                    metadata[idx] = asteroid_metadata_from_value(asteroid);
                 }
             }
         }
@@ -136,6 +134,101 @@ pub fn extract_asteroid_metadata(
     metadata
 }
 
+/// Reads gravity and region metadata from a single serialized asteroid.
+pub fn asteroid_metadata_from_value(asteroid: &serde_json::Value) -> AsteroidMetadata {
+    let asteroid_id = asteroid
+        .get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let gravity = asteroid
+        .get("gravityMultiplier")
+        .and_then(|v| v.as_f64())
+        .unwrap_or(1.0) as f32;
+
+    let radius = asteroid.get("radius").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32;
+    let fracture = asteroid.get("biome").and_then(|biome| {
+        Some(FractureState {
+            biome_id: biome.get("biomeId")?.as_str()?.to_string(),
+            timer: biome.get("fractureTimer")?.as_f64()? as f32,
+            seed: biome.get("fractureSeed")?.as_u64()? as u32,
+            count: biome.get("fractureCount").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+            radius,
+        })
+    });
+
+    let mut entry = AsteroidMetadata {
+        gravity_multiplier: gravity.max(0.01),
+        regions: Vec::new(),
+        fracture,
+    };
+
+    if let Some(regions) = asteroid.get("regions").and_then(|v| v.as_array()) {
+        for (region_index, region) in regions.iter().enumerate() {
+            let offset = region
+                .get("offset")
+                .and_then(|v| v.as_array())
+                .and_then(|arr| {
+                    if arr.len() >= 3 {
+                        Some([
+                            arr[0].as_f64().unwrap_or(0.0) as f32,
+                            arr[1].as_f64().unwrap_or(0.0) as f32,
+                            arr[2].as_f64().unwrap_or(0.0) as f32,
+                        ])
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or([0.0, 0.0, 0.0]);
+            let weight = region
+                .get("weight")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(1.0) as f32;
+            let gravity_multiplier = region
+                .get("gravityMultiplier")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(entry.gravity_multiplier as f64) as f32;
+            let hazard_severity = region
+                .get("hazard")
+                .and_then(|h| h.get("severity"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let hazard_kind = region
+                .get("hazard")
+                .and_then(|h| h.get("id"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let biome_id = region
+                .get("biomeId")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let resource_profile = region.get("resourceProfile").and_then(|p| {
+                let get = |key: &str| p.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+                p.is_object()
+                    .then(|| [get("ore"), get("ice"), get("metals"), get("crystals"), get("organics")])
+            });
+            let id = region
+                .get("id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| format!("{}-r{}", asteroid_id, region_index));
+
+            entry.regions.push(AsteroidRegionMeta {
+                id,
+                weight,
+                gravity_multiplier: gravity_multiplier.max(0.01),
+                offset,
+                hazard_severity,
+                hazard_kind,
+                biome_id,
+                resource_profile,
+            });
+        }
+    }
+
+    entry
+}
+
 fn asteroid_array(snapshot: &SimulationSnapshot) -> Option<&Vec<serde_json::Value>> {
     snapshot
         .extra
@@ -164,13 +257,19 @@ pub fn sys_drone_ai(
     drone_target_factory_index: &mut [f32],
     drone_target_region_index: &mut [f32],
     drone_owner_factory_index: &[f32],
+    drone_loadouts: &[LoadoutMultipliers],
+    drone_orders: &BTreeMap<String, DroneOrder>,
+    fulfilled_orders: &mut Vec<String>,
+    assigned_drones: &BTreeSet<String>,
     drone_ids: &[String],
     _drone_id_to_index: &BTreeMap<String, usize>,
     factory_id_to_index: &BTreeMap<String, usize>,
     asteroid_index_to_id: &[String],
+    asteroid_id_to_index: &BTreeMap<String, usize>,
     factories: &mut [FactorySnapshot],
     factory_positions: &[f32],
     asteroid_positions: &[f32],
+    asteroid_grid: &SpatialGrid,
     asteroid_metadata: &[AsteroidMetadata],
     asteroid_ore: &[f32],
     rng: &mut Mulberry32,
@@ -184,6 +283,14 @@ pub fn sys_drone_ai(
     }
 
     let mut new_flights = Vec::new();
+    let asteroid_field = AsteroidField {
+        positions: asteroid_positions,
+        grid: asteroid_grid,
+        ore: asteroid_ore,
+        metadata: asteroid_metadata,
+        index_to_id: asteroid_index_to_id,
+    };
+    let mut dock_index = DockIndex::new(factories, factory_positions);
 
     // IMPORTANT: Miniplex queries iterate entities in *reverse* insertion order.
     // Drone target selection consumes RNG, so differing iteration order will swap
@@ -205,14 +312,13 @@ pub fn sys_drone_ai(
             *drone_positions.get(drone_idx * 3 + 2).unwrap_or(&0.0),
         ];
 
-        let speed_bonus = 1.0 + ((modules.drone_bay as f32 - 1.0).max(0.0)) * 0.05;
-        let base_speed = DRONE_SPEED * speed_bonus;
-        let speed = base_speed * modifiers.drone_production_speed_multiplier;
-        let capacity_base = DRONE_MAX_CARGO + modules.storage as f32 * 5.0;
-        let capacity = capacity_base * modifiers.drone_capacity_multiplier;
-        let max_battery = DRONE_MAX_BATTERY * modifiers.drone_battery_multiplier;
-        let mining_base = DRONE_MINING_RATE + modules.refinery as f32 * 0.5;
-        let mining_rate = mining_base * modifiers.drone_production_speed_multiplier;
+        let loadout = drone_loadouts.get(drone_idx).copied().unwrap_or_default();
+        let DroneStats {
+            speed,
+            capacity,
+            mining_rate,
+            max_battery,
+        } = drone_stats(modules, modifiers, &loadout);
 
         if let Some(slot) = drone_capacity.get_mut(drone_idx) {
             *slot = capacity;
@@ -232,17 +338,54 @@ pub fn sys_drone_ai(
             .cloned()
             .unwrap_or_else(|| drone_id.to_string());
 
+        let order = drone_orders.get(drone_id);
+        let recall_factory_index = match order {
+            Some(DroneOrder::Recall { factory_id }) => {
+                factory_id_to_index.get(factory_id).map(|&idx| idx as f32)
+            }
+            _ => None,
+        };
+
         let state = drone_states[drone_idx];
-        if state == DRONE_STATE_IDLE {
-            if let Some(target) = select_asteroid_target(
-                &drone_label,
-                position,
-                asteroid_positions,
-                asteroid_ore,
-                asteroid_metadata,
-                asteroid_index_to_id,
-                rng,
-            ) {
+        let recalled = recall_factory_index.is_some()
+            && (state == DRONE_STATE_IDLE
+                || state == DRONE_STATE_MINING
+                || state == DRONE_STATE_RETURNING);
+        if state == DRONE_STATE_IDLE && !recalled {
+            let ordered_target = match order {
+                Some(DroneOrder::Hold) => continue,
+                Some(DroneOrder::GoTo {
+                    asteroid_id,
+                    region_id,
+                }) => ordered_asteroid_target(
+                    asteroid_id,
+                    region_id.as_deref(),
+                    asteroid_id_to_index,
+                    asteroid_positions,
+                    asteroid_ore,
+                    asteroid_metadata,
+                    rng,
+                ),
+                Some(DroneOrder::Pin { asteroid_id }) => ordered_asteroid_target(
+                    asteroid_id,
+                    None,
+                    asteroid_id_to_index,
+                    asteroid_positions,
+                    asteroid_ore,
+                    asteroid_metadata,
+                    rng,
+                ),
+                _ => None,
+            };
+            let from_order = ordered_target.is_some();
+            let target = ordered_target.or_else(|| {
+                select_asteroid_target(&drone_label, position, &asteroid_field, rng)
+            });
+            if let Some(target) = target {
+                // A go-to order covers a single trip; pins stay until their asteroid is gone.
+                if from_order && matches!(order, Some(DroneOrder::GoTo { .. })) {
+                    fulfilled_orders.push(drone_id.clone());
+                }
                 let path_seed = next_path_seed(rng);
                 let travel = build_travel_snapshot(
                     position,
@@ -287,17 +430,26 @@ pub fn sys_drone_ai(
                     }
                 }
             }
-        } else if state == DRONE_STATE_MINING || state == DRONE_STATE_RETURNING {
+        } else if state == DRONE_STATE_MINING || state == DRONE_STATE_RETURNING || recalled {
             let cargo = *drone_cargo.get(drone_idx).unwrap_or(&0.0);
-            if state == DRONE_STATE_RETURNING || cargo >= capacity {
-                let current_factory_index =
-                    *drone_target_factory_index.get(drone_idx).unwrap_or(&TARGET_INDEX_NONE);
+            if recalled || state == DRONE_STATE_RETURNING || cargo >= capacity {
+                // A recall docks at the ordered factory the same way a drone rejoins its current
+                // one; drones assigned by command head back to their owner.
+                let owner_index = *drone_owner_factory_index
+                    .get(drone_idx)
+                    .unwrap_or(&TARGET_INDEX_NONE);
+                let home_index = (assigned_drones.contains(drone_id) && owner_index >= 0.0)
+                    .then_some(owner_index);
+                let current_factory_index = recall_factory_index.or(home_index).unwrap_or_else(|| {
+                    *drone_target_factory_index.get(drone_idx).unwrap_or(&TARGET_INDEX_NONE)
+                });
 
                 if let Some(assignment) = select_return_factory(
                     &drone_label,
                     current_factory_index,
                     position,
                     factories,
+                    &mut dock_index,
                     factory_positions,
                     rng,
                 ) {
@@ -340,6 +492,42 @@ pub fn sys_drone_ai(
                             cargo_profile: None,
                             charging: false,
                         });
+                    } else if let Some(slot) = assignment.queue_slot {
+                        // Wait in view of the factory rather than wherever the hold filled up.
+                        let orbit = queue_orbit_position(assignment.destination, slot);
+                        let dx = orbit[0] - position[0];
+                        let dy = orbit[1] - position[1];
+                        let dz = orbit[2] - position[2];
+                        if (dx * dx + dy * dy + dz * dz).sqrt() > QUEUE_ORBIT_TOLERANCE {
+                            // Seeded from the slot, not the RNG, so waiting never shifts later rolls.
+                            let path_seed = (slot as u32 + 1).wrapping_mul(SEED_MIX);
+                            let travel = build_travel_snapshot(
+                                position,
+                                orbit,
+                                path_seed,
+                                speed,
+                                sink_bonuses.drone_speed_multiplier,
+                                1.0,
+                            );
+
+                            new_flights.push(DroneFlight {
+                                drone_id: drone_id.clone(),
+                                state: "queued".to_string(),
+                                target_asteroid_id: None,
+                                target_region_id: None,
+                                target_factory_id: Some(assignment.factory_id),
+                                owner_factory_id: None,
+                                path_seed,
+                                travel,
+                                cargo,
+                                battery: *drone_battery.get(drone_idx).unwrap_or(&0.0),
+                                max_battery,
+                                capacity,
+                                mining_rate,
+                                cargo_profile: None,
+                                charging: false,
+                            });
+                        }
                     }
                 }
             }
@@ -352,69 +540,68 @@ pub fn sys_drone_ai(
 fn select_asteroid_target(
     drone_label: &str,
     drone_position: [f32; 3],
-    asteroid_positions: &[f32],
-    asteroid_ore: &[f32],
-    asteroid_metadata: &[AsteroidMetadata],
-    asteroid_index_to_id: &[String],
+    field: &AsteroidField,
     rng: &mut Mulberry32,
 ) -> Option<AsteroidTarget> {
+    let AsteroidField {
+        positions: asteroid_positions,
+        grid: asteroid_grid,
+        ore: asteroid_ore,
+        metadata: asteroid_metadata,
+        index_to_id: asteroid_index_to_id,
+    } = *field;
     let asteroid_count = asteroid_positions.len() / 3;
     if asteroid_count == 0 {
         return None;
     }
 
-    #[derive(Clone)]
-    struct Candidate {
-        index: usize,
-        distance: f32,
-    }
-
-    // IMPORTANT: iterate in snapshot/index order, not BTreeMap key order.
-    // TS builds/sorts candidates based on world insertion order (which matches
-    // snapshot array order in parity harness). When f32 distances tie, stable
-    // sorting will preserve this original order, so we must mirror it.
-    let mut candidates: Vec<Candidate> = (0..asteroid_count)
-        .filter_map(|idx| {
-            if *asteroid_ore.get(idx).unwrap_or(&0.0) <= 0.0 {
-                return None;
-            }
-
-            let id = asteroid_index_to_id.get(idx).map(|s| s.as_str()).unwrap_or("");
-            if id.is_empty() {
-                return None;
-            }
+    let is_candidate = |idx: usize| {
+        *asteroid_ore.get(idx).unwrap_or(&0.0) > 0.0
+            && asteroid_index_to_id
+                .get(idx)
+                .is_some_and(|id| !id.is_empty())
+    };
 
-            let pos = [
-                asteroid_positions[idx * 3],
-                asteroid_positions[idx * 3 + 1],
-                asteroid_positions[idx * 3 + 2],
-            ];
-            let dx = pos[0] - drone_position[0];
-            let dy = pos[1] - drone_position[1];
-            let dz = pos[2] - drone_position[2];
-            let distance = (dx * dx + dy * dy + dz * dz).sqrt();
-
-            Some(Candidate { index: idx, distance })
-        })
-        .collect();
+    // The grid returns the same (distance, index) ordering as a full sort; fall back to the
+    // scan if it is out of step with the buffer.
+    let candidates: Vec<Neighbor> = if asteroid_grid.len() == asteroid_count {
+        asteroid_grid.nearest(drone_position, asteroid_positions, NEARBY_LIMIT, is_candidate)
+    } else {
+        // IMPORTANT: iterate in snapshot/index order, not BTreeMap key order.
+        // TS builds/sorts candidates based on world insertion order (which matches
+        // snapshot array order in parity harness). When f32 distances tie, stable
+        // sorting will preserve this original order, so we must mirror it.
+        let mut all: Vec<Neighbor> = (0..asteroid_count)
+            .filter(|&idx| is_candidate(idx))
+            .map(|idx| {
+                let dx = asteroid_positions[idx * 3] - drone_position[0];
+                let dy = asteroid_positions[idx * 3 + 1] - drone_position[1];
+                let dz = asteroid_positions[idx * 3 + 2] - drone_position[2];
+                Neighbor {
+                    index: idx,
+                    distance: (dx * dx + dy * dy + dz * dz).sqrt(),
+                }
+            })
+            .collect();
+        all.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then_with(|| a.index.cmp(&b.index))
+        });
+        all.truncate(NEARBY_LIMIT.min(all.len()));
+        all
+    };
 
     if candidates.is_empty() {
         return None;
     }
 
-    candidates.sort_by(|a, b| {
-        a.distance
-            .total_cmp(&b.distance)
-            .then_with(|| a.index.cmp(&b.index))
-    });
-    candidates.truncate(NEARBY_LIMIT.min(candidates.len()));
-
     let mut total_weight = 0.0;
     let mut weighted = Vec::with_capacity(candidates.len());
     for candidate in candidates.iter() {
         let weight = 1.0 / candidate.distance.max(1.0);
         total_weight += weight;
-        weighted.push((candidate, weight));
+        weighted.push((*candidate, weight));
     }
 
     let raw_roll = rng.next_f32();
@@ -440,11 +627,11 @@ fn select_asteroid_target(
 
     // Match TS: roll is scaled by the actual totalWeight (fallback to 1 only if totalWeight is 0).
     let mut roll = raw_roll * if total_weight > 0.0 { total_weight } else { 1.0 };
-    let mut chosen = weighted.last().map(|(c, _)| (*c).clone());
+    let mut chosen = weighted.last().map(|(c, _)| *c);
     for (candidate, weight) in weighted {
         roll -= weight;
         if roll <= 0.0 {
-            chosen = Some(candidate.clone());
+            chosen = Some(candidate);
             break;
         }
     }
@@ -462,6 +649,58 @@ fn select_asteroid_target(
         .unwrap_or_default();
     let (region, region_index) = pick_region(&chosen_id, &metadata, rng);
 
+    Some(build_asteroid_target(
+        chosen_id,
+        chosen.index,
+        base_pos,
+        &metadata,
+        region,
+        region_index,
+    ))
+}
+
+/// Targets an asteroid named by a drone order, honoring the ordered region while it exists.
+/// Returns None once the asteroid is gone or mined out so the drone falls back to automatic picks.
+fn ordered_asteroid_target(
+    asteroid_id: &str,
+    region_id: Option<&str>,
+    asteroid_id_to_index: &BTreeMap<String, usize>,
+    asteroid_positions: &[f32],
+    asteroid_ore: &[f32],
+    asteroid_metadata: &[AsteroidMetadata],
+    rng: &mut Mulberry32,
+) -> Option<AsteroidTarget> {
+    let &index = asteroid_id_to_index.get(asteroid_id)?;
+    if *asteroid_ore.get(index).unwrap_or(&0.0) <= 0.0 {
+        return None;
+    }
+    let pos = asteroid_positions.get(index * 3..index * 3 + 3)?;
+    let metadata = asteroid_metadata.get(index).cloned().unwrap_or_default();
+    let ordered_region = region_id
+        .and_then(|id| metadata.regions.iter().position(|region| region.id == id));
+    let (region, region_index) = match ordered_region {
+        Some(idx) => (Some(metadata.regions[idx].clone()), Some(idx)),
+        None => pick_region(asteroid_id, &metadata, rng),
+    };
+
+    Some(build_asteroid_target(
+        asteroid_id.to_string(),
+        index,
+        [pos[0], pos[1], pos[2]],
+        &metadata,
+        region,
+        region_index,
+    ))
+}
+
+fn build_asteroid_target(
+    id: String,
+    index: usize,
+    base_pos: [f32; 3],
+    metadata: &AsteroidMetadata,
+    region: Option<AsteroidRegionMeta>,
+    region_index: Option<usize>,
+) -> AsteroidTarget {
     let mut destination = base_pos;
     let mut gravity_multiplier = metadata.gravity_multiplier.max(0.5);
     let mut region_id = None;
@@ -474,14 +713,14 @@ fn select_asteroid_target(
         region_id = Some(region_meta.id);
     }
 
-    Some(AsteroidTarget {
-        id: chosen_id,
-        index: chosen.index,
+    AsteroidTarget {
+        id,
+        index,
         destination,
         region_id,
         region_index,
         gravity_multiplier,
-    })
+    }
 }
 
 fn pick_region(
@@ -497,7 +736,7 @@ fn pick_region(
         .regions
         .iter()
         .enumerate()
-        .filter(|(_, region)| region.hazard_severity.as_deref() != Some("high"))
+        .filter(|(_, region)| region.is_safe())
         .collect();
     let pool: Vec<(usize, &AsteroidRegionMeta)> = if safe_regions.is_empty() {
         metadata.regions.iter().enumerate().collect()
@@ -569,6 +808,7 @@ fn select_return_factory(
     current_factory_index: f32,
     position: [f32; 3],
     factories: &mut [FactorySnapshot],
+    dock_index: &mut DockIndex,
     factory_positions: &[f32],
     rng: &mut Mulberry32,
 ) -> Option<FactoryAssignment> {
@@ -576,7 +816,7 @@ fn select_return_factory(
         return None;
     }
 
-    if let Some((existing_idx, queue_pos)) = find_existing_queue(factories, drone_id) {
+    if let Some((existing_idx, queue_pos)) = dock_index.find_existing(factories, drone_id) {
         let capacity = factories[existing_idx].docking_capacity.max(0) as usize;
         let destination = factory_position(factory_positions, existing_idx)?;
         let start_travel = queue_pos < capacity;
@@ -585,6 +825,7 @@ fn select_return_factory(
             factory_id: factories[existing_idx].id.clone(),
             destination,
             start_travel,
+            queue_slot: waiting_slot(start_travel, queue_pos, capacity),
         });
     }
 
@@ -592,7 +833,7 @@ fn select_return_factory(
         let idx = current_factory_index as usize;
         if let Some(factory) = factories.get_mut(idx) {
             if let Some(destination) = factory_position(factory_positions, idx) {
-                let docking_result = dock_drone_at_factory(factory, drone_id);
+                let docking_result = dock_index.dock(idx, factory, drone_id);
                 let capacity = factory.docking_capacity.max(0) as usize;
                 let queue_pos =
                     find_queue_index(factory, drone_id).unwrap_or(factory.queued_drones.len());
@@ -606,100 +847,69 @@ fn select_return_factory(
                     factory_id: factory.id.clone(),
                     destination,
                     start_travel,
+                    queue_slot: waiting_slot(start_travel, queue_pos, capacity),
                 });
             }
         }
     }
 
-    let with_distances: Vec<_> = factories
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, factory)| {
-            let destination = factory_position(factory_positions, idx)?;
+    let distance_to = |idx: usize| {
+        factory_position(factory_positions, idx).map_or(f32::INFINITY, |destination| {
             let dx = destination[0] - position[0];
             let dy = destination[1] - position[1];
             let dz = destination[2] - position[2];
-            let distance = (dx * dx + dy * dy + dz * dz).sqrt();
-            let queue_len = factory.queued_drones.len();
-            let capacity = factory.docking_capacity.max(0) as usize;
-            let occupied = queue_len.min(capacity);
-            let available = capacity.saturating_sub(occupied);
-            Some((idx, distance, available, queue_len))
+            (dx * dx + dy * dy + dz * dz).sqrt()
         })
-        .collect();
-
-    if with_distances.is_empty() {
-        return None;
-    }
+    };
 
-    let mut candidates: Vec<_> = with_distances
-        .iter()
-        .filter(|(_, _, available, _)| *available > 0)
-        .cloned()
-        .collect();
     let mut variety_roll: Option<f32> = None;
     let mut used_variety = false;
     let mut weighted_raw_roll: Option<f32> = None;
     let mut weighted_total: Option<f32> = None;
 
-    let selection_idx = if !candidates.is_empty() {
-        candidates.sort_by(|a, b| {
-            let queue_cmp = a.3.cmp(&b.3);
-            if queue_cmp == std::cmp::Ordering::Equal {
-                a.1.total_cmp(&b.1)
-            } else {
-                queue_cmp
-            }
-        });
-        let mut chosen = candidates[0].0;
-        if candidates.len() > 1 {
-            let roll = rng.next_f32();
-            variety_roll = Some(roll);
-            if roll < FACTORY_VARIETY_CHANCE {
-                used_variety = true;
-                let others = &candidates[1..];
-                let weights: Vec<f32> = others
-                    .iter()
-                    .map(|entry| 1.0 / entry.1.max(0.001))
-                    .collect();
-                let total: f32 = weights.iter().sum();
-                weighted_total = Some(total);
-                let raw_roll = rng.next_f32();
-                weighted_raw_roll = Some(raw_roll);
-                let mut roll = raw_roll * total.max(0.001);
-                for (i, entry) in others.iter().enumerate() {
-                    roll -= weights[i];
-                    if roll <= 0.0 {
-                        chosen = entry.0;
-                        break;
-                    }
+    // Factories rank by queue length, then distance. Without a free dock anywhere the shortest
+    // queue still wins.
+    let open_count = dock_index.open_count;
+    let mut selection_idx =
+        dock_index.nearest_shortest_queue(factories, open_count > 0, distance_to)?;
+    if open_count > 1 {
+        let roll = rng.next_f32();
+        variety_roll = Some(roll);
+        if roll < FACTORY_VARIETY_CHANCE {
+            used_variety = true;
+            let ranked = dock_index.ranked_open(factories, distance_to);
+            let others = &ranked[1..];
+            let weights: Vec<f32> = others
+                .iter()
+                .map(|entry| 1.0 / entry.1.max(0.001))
+                .collect();
+            let total: f32 = weights.iter().sum();
+            weighted_total = Some(total);
+            let raw_roll = rng.next_f32();
+            weighted_raw_roll = Some(raw_roll);
+            let mut roll = raw_roll * total.max(0.001);
+            for (i, entry) in others.iter().enumerate() {
+                roll -= weights[i];
+                if roll <= 0.0 {
+                    selection_idx = entry.0;
+                    break;
                 }
             }
         }
-        chosen
-    } else {
-        with_distances
-            .iter()
-            .cloned()
-            .min_by(|a, b| {
-                let queue_cmp = a.3.cmp(&b.3);
-                if queue_cmp == std::cmp::Ordering::Equal {
-                    a.1.total_cmp(&b.1)
-                } else {
-                    queue_cmp
-                }
-            })
-            .map(|entry| entry.0)?
-    };
+    }
 
     if parity_debug::enabled() {
-        let candidate_payload: Vec<serde_json::Value> = with_distances
+        let candidate_payload: Vec<serde_json::Value> = factories
             .iter()
-            .map(|(idx, distance, available, queue_len)| {
+            .enumerate()
+            .filter(|(idx, _)| factory_position(factory_positions, *idx).is_some())
+            .map(|(idx, factory)| {
+                let queue_len = factory.queued_drones.len();
+                let capacity = factory.docking_capacity.max(0) as usize;
                 json!({
                     "index": idx,
-                    "distance": distance,
-                    "available": available,
+                    "distance": distance_to(idx),
+                    "available": capacity.saturating_sub(queue_len.min(capacity)),
                     "queueLen": queue_len,
                 })
             })
@@ -722,7 +932,7 @@ fn select_return_factory(
 
     let destination = factory_position(factory_positions, selection_idx)?;
     let factory = factories.get_mut(selection_idx)?;
-    let docking_result = dock_drone_at_factory(factory, drone_id);
+    let docking_result = dock_index.dock(selection_idx, factory, drone_id);
 
     let capacity = factory.docking_capacity.max(0) as usize;
     let queue_pos = find_queue_index(factory, drone_id).unwrap_or(factory.queued_drones.len());
@@ -737,14 +947,143 @@ fn select_return_factory(
         factory_id: factory.id.clone(),
         destination,
         start_travel,
+        queue_slot: waiting_slot(start_travel, queue_pos, capacity),
     })
 }
 
-fn find_existing_queue(factories: &[FactorySnapshot], drone_id: &str) -> Option<(usize, usize)> {
-    factories
-        .iter()
-        .enumerate()
-        .find_map(|(idx, factory)| find_queue_index(factory, drone_id).map(|pos| (idx, pos)))
+fn waiting_slot(start_travel: bool, queue_pos: usize, capacity: usize) -> Option<usize> {
+    (!start_travel).then(|| queue_pos.saturating_sub(capacity))
+}
+
+/// Holding spot for the `slot`-th drone waiting on a factory's docks.
+fn queue_orbit_position(factory: [f32; 3], slot: usize) -> [f32; 3] {
+    let ring = slot / QUEUE_ORBIT_SLOTS_PER_RING;
+    let step = 2.0 * PI / QUEUE_ORBIT_SLOTS_PER_RING as f32;
+    // Offset alternate rings by half a step so outer drones sit between inner ones.
+    let angle = (slot % QUEUE_ORBIT_SLOTS_PER_RING) as f32 * step + (ring % 2) as f32 * step * 0.5;
+    let radius = QUEUE_ORBIT_RADIUS + ring as f32 * QUEUE_ORBIT_RING_SPACING;
+    [
+        factory[0] + angle.cos() * radius,
+        factory[1] + QUEUE_ORBIT_HEIGHT,
+        factory[2] + angle.sin() * radius,
+    ]
+}
+
+/// Factory-side index for return-factory picks, kept in step with the docking queues for one
+/// AI pass. Factories without a position are left out, as they can never be picked.
+struct DockIndex {
+    /// First factory whose queue holds each drone.
+    queued_at: HashMap<String, usize>,
+    /// Factory indices bucketed by queue length.
+    by_queue_len: BTreeMap<usize, BTreeSet<usize>>,
+    /// Indexed factories with a free dock.
+    open_count: usize,
+}
+
+impl DockIndex {
+    fn new(factories: &[FactorySnapshot], factory_positions: &[f32]) -> Self {
+        let mut index = Self {
+            queued_at: HashMap::new(),
+            by_queue_len: BTreeMap::new(),
+            open_count: 0,
+        };
+        for (idx, factory) in factories.iter().enumerate() {
+            for drone_id in &factory.queued_drones {
+                index.queued_at.entry(drone_id.clone()).or_insert(idx);
+            }
+            if factory_position(factory_positions, idx).is_none() {
+                continue;
+            }
+            let queue_len = factory.queued_drones.len();
+            index.by_queue_len.entry(queue_len).or_default().insert(idx);
+            if queue_len < factory.docking_capacity.max(0) as usize {
+                index.open_count += 1;
+            }
+        }
+        index
+    }
+
+    /// The factory already queueing `drone_id` and its place in that queue.
+    fn find_existing(
+        &self,
+        factories: &[FactorySnapshot],
+        drone_id: &str,
+    ) -> Option<(usize, usize)> {
+        let &idx = self.queued_at.get(drone_id)?;
+        find_queue_index(factories.get(idx)?, drone_id).map(|pos| (idx, pos))
+    }
+
+    /// Docks `drone_id` at `factory` and moves the factory to its new queue-length bucket.
+    fn dock(&mut self, idx: usize, factory: &mut FactorySnapshot, drone_id: &str) -> DockingResult {
+        let before = factory.queued_drones.len();
+        let result = dock_drone_at_factory(factory, drone_id);
+        self.queued_at.entry(drone_id.to_string()).or_insert(idx);
+        let after = factory.queued_drones.len();
+        if after == before {
+            return result;
+        }
+        let Some(bucket) = self.by_queue_len.get_mut(&before) else {
+            return result;
+        };
+        if !bucket.remove(&idx) {
+            return result;
+        }
+        if bucket.is_empty() {
+            self.by_queue_len.remove(&before);
+        }
+        self.by_queue_len.entry(after).or_default().insert(idx);
+        let capacity = factory.docking_capacity.max(0) as usize;
+        if before < capacity && after >= capacity {
+            self.open_count -= 1;
+        }
+        result
+    }
+
+    /// Nearest factory in the shortest queue, counting only factories with a free dock when
+    /// `open_only` is set. Ties go to the lower index, as a stable sort of a full scan would.
+    fn nearest_shortest_queue(
+        &self,
+        factories: &[FactorySnapshot],
+        open_only: bool,
+        distance_to: impl Fn(usize) -> f32,
+    ) -> Option<usize> {
+        self.by_queue_len.iter().find_map(|(&queue_len, bucket)| {
+            bucket
+                .iter()
+                .copied()
+                .filter(|&idx| {
+                    !open_only || queue_len < factories[idx].docking_capacity.max(0) as usize
+                })
+                .map(|idx| (idx, distance_to(idx)))
+                .fold(None, |best: Option<(usize, f32)>, entry| match best {
+                    Some(best) if best.1.total_cmp(&entry.1).is_le() => Some(best),
+                    _ => Some(entry),
+                })
+                .map(|(idx, _)| idx)
+        })
+    }
+
+    /// Every factory with a free dock and its distance, ranked by queue length, then distance,
+    /// then index.
+    fn ranked_open(
+        &self,
+        factories: &[FactorySnapshot],
+        distance_to: impl Fn(usize) -> f32,
+    ) -> Vec<(usize, f32)> {
+        let mut ranked = Vec::with_capacity(self.open_count);
+        for (&queue_len, bucket) in &self.by_queue_len {
+            let start = ranked.len();
+            ranked.extend(
+                bucket
+                    .iter()
+                    .copied()
+                    .filter(|&idx| queue_len < factories[idx].docking_capacity.max(0) as usize)
+                    .map(|idx| (idx, distance_to(idx))),
+            );
+            ranked[start..].sort_by(|a: &(usize, f32), b| a.1.total_cmp(&b.1));
+        }
+        ranked
+    }
 }
 
 fn find_queue_index(factory: &FactorySnapshot, drone_id: &str) -> Option<usize> {
@@ -881,6 +1220,114 @@ fn resolve_factory_id(
         .find_map(|(id, &idx)| if idx == owner_idx { Some(id.clone()) } else { None })
 }
 
+/// Drone buffer slices a fracture re-plan reads and rewrites, with the id map that indexes them.
+pub struct DroneTargetBuffers<'a> {
+    pub id_to_index: &'a BTreeMap<String, usize>,
+    pub positions: &'a [f32],
+    pub states: &'a mut [f32],
+    pub target_asteroid_index: &'a mut [f32],
+    pub target_region_index: &'a mut [f32],
+}
+
+/// Re-targets drones headed for an asteroid that just fractured (`handleDroneReassignment` in TS).
+/// Outbound flights are re-planned from where the drone is now toward the first safe region;
+/// with no safe region left, outbound and mining drones turn back.
+pub fn reassign_fractured_drones(
+    asteroid_index: usize,
+    asteroid_id: &str,
+    metadata: &AsteroidMetadata,
+    asteroid_positions: &[f32],
+    drone_flights: &mut Vec<DroneFlight>,
+    drones: &mut DroneTargetBuffers,
+    rng: &mut Mulberry32,
+) {
+    let DroneTargetBuffers {
+        id_to_index: drone_id_to_index,
+        positions: drone_positions,
+        states: drone_states,
+        target_asteroid_index: drone_target_asteroid_index,
+        target_region_index: drone_target_region_index,
+    } = drones;
+    let safe = metadata
+        .regions
+        .iter()
+        .enumerate()
+        .find(|(_, region)| region.is_safe());
+    let base = [
+        asteroid_positions.get(asteroid_index * 3).copied().unwrap_or(0.0),
+        asteroid_positions.get(asteroid_index * 3 + 1).copied().unwrap_or(0.0),
+        asteroid_positions.get(asteroid_index * 3 + 2).copied().unwrap_or(0.0),
+    ];
+
+    let mut recalled: Vec<usize> = Vec::new();
+    drone_flights.retain_mut(|flight| {
+        if flight.state != "toAsteroid" || flight.target_asteroid_id.as_deref() != Some(asteroid_id) {
+            return true;
+        }
+        let Some(&drone_idx) = drone_id_to_index.get(&flight.drone_id) else {
+            return true;
+        };
+        let current = flight
+            .target_region_id
+            .as_ref()
+            .and_then(|id| metadata.regions.iter().position(|r| &r.id == id));
+        if let Some(region_idx) = current.filter(|&i| metadata.regions[i].is_safe()) {
+            if let Some(slot) = drone_target_region_index.get_mut(drone_idx) {
+                *slot = region_idx as f32;
+            }
+            return true;
+        }
+        let Some((region_idx, region)) = safe else {
+            recalled.push(drone_idx);
+            return false;
+        };
+
+        let from = [
+            drone_positions.get(drone_idx * 3).copied().unwrap_or(0.0),
+            drone_positions.get(drone_idx * 3 + 1).copied().unwrap_or(0.0),
+            drone_positions.get(drone_idx * 3 + 2).copied().unwrap_or(0.0),
+        ];
+        let remaining = (flight.travel.duration - flight.travel.elapsed).max(0.1);
+        flight.path_seed = next_return_path_seed(rng);
+        flight.target_region_id = Some(region.id.clone());
+        flight.travel = TravelSnapshot {
+            from,
+            to: [
+                base[0] + region.offset[0],
+                base[1] + region.offset[1],
+                base[2] + region.offset[2],
+            ],
+            elapsed: 0.0,
+            duration: quantize_time(remaining),
+            control: None,
+        };
+        if let Some(slot) = drone_target_region_index.get_mut(drone_idx) {
+            *slot = region_idx as f32;
+        }
+        true
+    });
+
+    if safe.is_none() {
+        for (drone_idx, state) in drone_states.iter().enumerate() {
+            let target = drone_target_asteroid_index.get(drone_idx).copied().unwrap_or(TARGET_INDEX_NONE);
+            if *state == DRONE_STATE_MINING && target >= 0.0 && target as usize == asteroid_index {
+                recalled.push(drone_idx);
+            }
+        }
+    }
+    for drone_idx in recalled {
+        if let Some(state) = drone_states.get_mut(drone_idx) {
+            *state = DRONE_STATE_RETURNING;
+        }
+        if let Some(slot) = drone_target_asteroid_index.get_mut(drone_idx) {
+            *slot = TARGET_INDEX_NONE;
+        }
+        if let Some(slot) = drone_target_region_index.get_mut(drone_idx) {
+            *slot = TARGET_INDEX_NONE;
+        }
+    }
+}
+
 fn next_path_seed(rng: &mut Mulberry32) -> u32 {
     // Mirrors TS: Math.max(1, Math.floor(rng.next() * 0x7fffffff))
     let scaled = ((rng.next_u32() as u64) * 0x7fff_ffffu64) >> 32;
@@ -923,6 +1370,8 @@ mod tests {
                 hauler_depot: 0,
                 logistics_hub: 0,
                 routing_protocol: 0,
+                lab: 0,
+                data_core: 0,
             },
             prestige: crate::schema::Prestige { cores: 0 },
             save: crate::schema::SaveMeta {
@@ -947,6 +1396,7 @@ mod tests {
                 },
                 use_rust_sim: false,
                 shadow_mode: false,
+                auto_balance_drones: false,
             },
             rng_seed: Some(1),
             drone_flights: vec![],
@@ -957,6 +1407,16 @@ mod tests {
             spec_techs: None,
             spec_tech_spent: None,
             prestige_investments: None,
+            asteroid_hazards: Vec::new(),
+            lifetime_stats: None,
+            drone_loadouts: BTreeMap::new(),
+            drone_integrity: BTreeMap::new(),
+            disabled_drones: Default::default(),
+            drone_orders: BTreeMap::new(),
+            assigned_drones: Default::default(),
+            market: Default::default(),
+            contracts: Default::default(),
+            research: Default::default(),
             game_time: 0.0,
             extra: BTreeMap::new(),
             schema_version: crate::schema::SCHEMA_VERSION.to_string(),
@@ -1030,11 +1490,16 @@ mod tests {
             resources: Default::default(),
             upgrades: Default::default(),
             active_refines: Default::default(),
+            refine_recipes: Default::default(),
             upgrade_requests: Default::default(),
             haulers_assigned: None,
             hauler_config: None,
             hauler_upgrades: None,
             logistics_state: None,
+            heat: 0.0,
+            cooling: false,
+            overdrive: false,
+            docking: Default::default(),
         }];
 
         let factory_positions = vec![0.0, 0.0, 0.0];
@@ -1057,6 +1522,7 @@ mod tests {
             0,
             None,
             None,
+            &[],
         );
         let modules = Modules {
             drone_bay: 1,
@@ -1067,6 +1533,8 @@ mod tests {
             hauler_depot: 0,
             logistics_hub: 0,
             routing_protocol: 0,
+            lab: 0,
+            data_core: 0,
         };
         let sink_bonuses = SinkBonuses {
             ore_yield_multiplier: 1.0,
@@ -1090,13 +1558,19 @@ mod tests {
             &mut drone_target_factory_index,
             &mut drone_target_region_index,
             &drone_owner_factory_index,
+            &[],
+            &BTreeMap::new(),
+            &mut Vec::new(),
+            &BTreeSet::new(),
             &drone_ids,
             &drone_id_to_index,
             &factory_id_to_index,
             &asteroid_index_to_id,
+            &BTreeMap::new(),
             &mut factories,
             &factory_positions,
             &asteroid_positions,
+            &SpatialGrid::from_positions(crate::spatial::ASTEROID_GRID_CELL_SIZE, &asteroid_positions),
             &asteroid_metadata,
             &asteroid_ore,
             &mut rng,
@@ -1112,6 +1586,97 @@ mod tests {
         assert_eq!(drone_flights[0].target_factory_id, Some("f1".to_string()));
     }
 
+    #[test]
+    fn dock_index_finds_the_first_queue_holding_a_drone() {
+        let factory = |id: &str, queued: &[&str]| FactorySnapshot {
+            id: id.to_string(),
+            queued_drones: queued.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let factories = vec![factory("f0", &["a"]), factory("f1", &["b", "c"]), factory("f2", &["c"])];
+        let mut dock_index = DockIndex::new(&factories, &[0.0; 9]);
+        assert_eq!(dock_index.find_existing(&factories, "c"), Some((1, 1)));
+        assert_eq!(dock_index.find_existing(&factories, "a"), Some((0, 0)));
+        assert_eq!(dock_index.find_existing(&factories, "d"), None);
+
+        // A stale entry whose queue no longer holds the drone is not trusted.
+        dock_index.queued_at.insert("d".to_string(), 2);
+        assert_eq!(dock_index.find_existing(&factories, "d"), None);
+    }
+
+    /// The pick a full scan ranked by queue length, then distance, then index makes.
+    fn scan_return_factory(
+        factories: &[FactorySnapshot],
+        positions: &[f32],
+        from: [f32; 3],
+        rng: &mut Mulberry32,
+    ) -> usize {
+        let mut ranked: Vec<(usize, f32, usize, bool)> = factories
+            .iter()
+            .enumerate()
+            .map(|(idx, factory)| {
+                let p = &positions[idx * 3..idx * 3 + 3];
+                let (dx, dy, dz) = (p[0] - from[0], p[1] - from[1], p[2] - from[2]);
+                let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+                let queue_len = factory.queued_drones.len();
+                (idx, distance, queue_len, queue_len < factory.docking_capacity as usize)
+            })
+            .collect();
+        ranked.sort_by(|a, b| a.2.cmp(&b.2).then(a.1.total_cmp(&b.1)));
+        let open: Vec<_> = ranked.iter().filter(|entry| entry.3).collect();
+        let Some(first) = open.first() else {
+            return ranked[0].0;
+        };
+        if open.len() > 1 && rng.next_f32() < FACTORY_VARIETY_CHANCE {
+            let weights: Vec<f32> =
+                open[1..].iter().map(|entry| 1.0 / entry.1.max(0.001)).collect();
+            let mut roll = rng.next_f32() * weights.iter().sum::<f32>().max(0.001);
+            for (entry, weight) in open[1..].iter().zip(weights) {
+                roll -= weight;
+                if roll <= 0.0 {
+                    return entry.0;
+                }
+            }
+        }
+        first.0
+    }
+
+    #[test]
+    fn dock_index_picks_the_return_factory_a_full_scan_would() {
+        let mut rng = Mulberry32::new(7);
+        let mut factories: Vec<FactorySnapshot> = (0..12)
+            .map(|i| FactorySnapshot {
+                id: format!("f{i}"),
+                docking_capacity: 1 + (i % 3),
+                ..Default::default()
+            })
+            .collect();
+        // Pairs of factories share a spot so distance ties fall back to the index.
+        let positions: Vec<f32> = (0..12)
+            .flat_map(|i| [(i / 2) as f32 * 7.0 - 20.0, 0.0, (i / 2 % 3) as f32 * 5.0])
+            .collect();
+        let mut dock_index = DockIndex::new(&factories, &positions);
+
+        for d in 0..40 {
+            let from = [rng.next_f32() * 60.0 - 30.0, 0.0, rng.next_f32() * 20.0 - 10.0];
+            let mut reference_rng = rng.clone();
+            let expected = scan_return_factory(&factories, &positions, from, &mut reference_rng);
+            let drone_id = format!("d{d}");
+            let assignment = select_return_factory(
+                &drone_id,
+                TARGET_INDEX_NONE,
+                from,
+                &mut factories,
+                &mut dock_index,
+                &positions,
+                &mut rng,
+            )
+            .expect("a factory is always picked");
+            assert_eq!(assignment.factory_index, expected);
+            assert_eq!(rng.next_u32(), reference_rng.next_u32());
+        }
+    }
+
     #[test]
     fn travel_snapshot_varies_with_seed() {
         let from = [0.0, 0.0, 0.0];
@@ -0,0 +1,27 @@
+/// Oldest events are dropped past this many so an unread queue cannot grow during offline runs.
+pub const MAX_PENDING_EVENTS: usize = 128;
+
+/// Queues an event for the host to display, dropping the oldest past `MAX_PENDING_EVENTS`.
+pub fn push_event<T>(events: &mut Vec<T>, event: T) {
+    events.push(event);
+    if events.len() > MAX_PENDING_EVENTS {
+        let excess = events.len() - MAX_PENDING_EVENTS;
+        events.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_event_drops_the_oldest_past_the_limit() {
+        let mut events = Vec::new();
+        for i in 0..MAX_PENDING_EVENTS + 3 {
+            push_event(&mut events, i);
+        }
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert_eq!(events[0], 3);
+        assert_eq!(events.last(), Some(&(MAX_PENDING_EVENTS + 2)));
+    }
+}
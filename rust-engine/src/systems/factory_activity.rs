@@ -0,0 +1,189 @@
+use std::f32::consts::TAU;
+
+use crate::buffers::MAX_REFINE_SLOTS;
+use crate::constants::{DRONE_STATE_RETURNING, DRONE_STATE_UNLOADING};
+use crate::schema::FactorySnapshot;
+
+/// Resting yaw for a freshly built factory (mirrors the TS default of TAU / 8 around +Y).
+pub const FACTORY_DEFAULT_YAW: f32 = TAU / 8.0;
+const ACTIVITY_RESPONSE_PER_SEC: f32 = 2.0;
+const HEADING_TURN_RATE: f32 = 0.6; // radians per second
+const HEADING_MIN_DISTANCE: f32 = 0.5;
+
+/// Writes a unit quaternion for a rotation of `yaw` radians around +Y.
+pub fn write_yaw_quaternion(orientations: &mut [f32], index: usize, yaw: f32) {
+    let half = yaw * 0.5;
+    let base = index * 4;
+    orientations[base] = 0.0;
+    orientations[base + 1] = half.sin();
+    orientations[base + 2] = 0.0;
+    orientations[base + 3] = half.cos();
+}
+
+fn read_yaw(orientations: &[f32], index: usize) -> f32 {
+    let base = index * 4;
+    let y = orientations[base + 1];
+    let w = orientations[base + 3];
+    if y == 0.0 && w == 0.0 {
+        return FACTORY_DEFAULT_YAW;
+    }
+    2.0 * y.atan2(w)
+}
+
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + std::f32::consts::PI).rem_euclid(TAU);
+    wrapped - std::f32::consts::PI
+}
+
+/// Drone buffer slices read for each factory's charging load and inbound traffic.
+pub struct DroneActivityBuffers<'a> {
+    pub positions: &'a [f32],
+    pub states: &'a [f32],
+    pub charging: &'a [f32],
+    pub owner_factory_index: &'a [f32],
+    pub target_factory_index: &'a [f32],
+}
+
+/// Keeps the per-factory activity level (0..1) and heading quaternion current.
+///
+/// Activity blends refinery slot usage, docking occupancy and charging load. The heading
+/// turns toward the centroid of drones that are returning to or unloading at the factory.
+pub fn sys_factory_activity(
+    factory_orientations: &mut [f32], // [x, y, z, w] * N
+    factory_activity: &mut [f32],     // [level] * N
+    factory_positions: &[f32],        // [x, y, z] * N
+    refinery_state: &[f32],           // [active, amount, progress, speed] * MAX_REFINE_SLOTS * N
+    factories: &[FactorySnapshot],
+    drones: &DroneActivityBuffers,
+    dt: f32,
+) {
+    let factory_count = factory_activity.len();
+    if factory_count == 0 {
+        return;
+    }
+
+    let mut charging_load = vec![0u32; factory_count];
+    let mut inbound_sum = vec![[0.0f32; 3]; factory_count];
+    let mut inbound_count = vec![0u32; factory_count];
+
+    for (i, &charging) in drones.charging.iter().enumerate() {
+        let owner = drones.owner_factory_index.get(i).copied().unwrap_or(-1.0);
+        let target = drones.target_factory_index.get(i).copied().unwrap_or(-1.0);
+
+        if charging > 0.5 {
+            // Match sys_power: owner pays first, then the target factory.
+            let source = if owner >= 0.0 { owner } else { target };
+            if source >= 0.0 && (source as usize) < factory_count {
+                charging_load[source as usize] += 1;
+            }
+        }
+
+        let state = drones.states.get(i).copied().unwrap_or(0.0);
+        if (state == DRONE_STATE_RETURNING || state == DRONE_STATE_UNLOADING)
+            && target >= 0.0
+            && (target as usize) < factory_count
+        {
+            let f = target as usize;
+            inbound_sum[f][0] += drones.positions.get(i * 3).copied().unwrap_or(0.0);
+            inbound_sum[f][1] += drones.positions.get(i * 3 + 1).copied().unwrap_or(0.0);
+            inbound_sum[f][2] += drones.positions.get(i * 3 + 2).copied().unwrap_or(0.0);
+            inbound_count[f] += 1;
+        }
+    }
+
+    for i in 0..factory_count {
+        let factory = factories.get(i);
+        let refine_slots = factory
+            .map(|f| f.refine_slots.max(0) as usize)
+            .unwrap_or(0)
+            .min(MAX_REFINE_SLOTS);
+        let docking_capacity = factory.map(|f| f.docking_capacity.max(0) as usize).unwrap_or(0);
+        let queued = factory.map(|f| f.queued_drones.len()).unwrap_or(0);
+
+        let ref_base = i * MAX_REFINE_SLOTS * 4;
+        let busy_slots = (0..refine_slots)
+            .filter(|s| refinery_state.get(ref_base + s * 4).copied().unwrap_or(0.0) > 0.5)
+            .count();
+        let refining = if refine_slots > 0 {
+            busy_slots as f32 / refine_slots as f32
+        } else {
+            0.0
+        };
+        let (docking, charging) = if docking_capacity > 0 {
+            (
+                queued.min(docking_capacity) as f32 / docking_capacity as f32,
+                (charging_load[i] as f32 / docking_capacity as f32).min(1.0),
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        let target_activity = ((refining + docking + charging) / 3.0).clamp(0.0, 1.0);
+        let blend = (dt * ACTIVITY_RESPONSE_PER_SEC).clamp(0.0, 1.0);
+        let current = factory_activity[i];
+        factory_activity[i] = current + (target_activity - current) * blend;
+
+        let yaw = read_yaw(factory_orientations, i);
+        let mut next_yaw = yaw;
+        if inbound_count[i] > 0 {
+            let count = inbound_count[i] as f32;
+            let dx = inbound_sum[i][0] / count - factory_positions.get(i * 3).copied().unwrap_or(0.0);
+            let dz =
+                inbound_sum[i][2] / count - factory_positions.get(i * 3 + 2).copied().unwrap_or(0.0);
+            if dx * dx + dz * dz > HEADING_MIN_DISTANCE * HEADING_MIN_DISTANCE {
+                // Yaw that rotates local +X onto the inbound direction (three.js Y-up convention).
+                let desired = (-dz).atan2(dx);
+                let delta = wrap_angle(desired - yaw);
+                let max_turn = HEADING_TURN_RATE * dt.max(0.0);
+                next_yaw = yaw + delta.clamp(-max_turn, max_turn);
+            }
+        }
+        write_yaw_quaternion(factory_orientations, i, wrap_angle(next_yaw));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_tracks_busy_slots_and_heading_turns_toward_inbound() {
+        let factories = vec![FactorySnapshot {
+            id: "f1".to_string(),
+            refine_slots: 2,
+            docking_capacity: 2,
+            queued_drones: vec!["d1".to_string()],
+            ..Default::default()
+        }];
+        let mut orientations = vec![0.0; 4];
+        write_yaw_quaternion(&mut orientations, 0, 0.0);
+        let mut activity = vec![0.0];
+        let factory_positions = vec![0.0, 0.0, 0.0];
+        let mut refinery_state = vec![0.0; MAX_REFINE_SLOTS * 4];
+        refinery_state[0] = 1.0;
+        let drones = DroneActivityBuffers {
+            positions: &[0.0, 0.0, -10.0],
+            states: &[DRONE_STATE_RETURNING],
+            charging: &[0.0],
+            owner_factory_index: &[0.0],
+            target_factory_index: &[0.0],
+        };
+
+        for _ in 0..20 {
+            sys_factory_activity(
+                &mut orientations,
+                &mut activity,
+                &factory_positions,
+                &refinery_state,
+                &factories,
+                &drones,
+                0.25,
+            );
+        }
+
+        // refining 0.5, docking 0.5, charging 0.0
+        assert!((activity[0] - 1.0 / 3.0).abs() < 0.01);
+        let yaw = read_yaw(&orientations, 0);
+        assert!(yaw > 0.0 && yaw <= std::f32::consts::FRAC_PI_2 + 1e-4);
+    }
+}
@@ -1,6 +1,5 @@
-use crate::constants::{
-    DRONE_MAX_BATTERY, DRONE_MAX_CARGO, DRONE_MINING_RATE, DRONE_SPEED,
-};
+use crate::constants::DRONE_MAX_BATTERY;
+use crate::loadouts::{drone_stats, LoadoutMultipliers};
 use crate::modifiers::ResourceModifierSnapshot;
 use crate::schema::Modules;
 
@@ -9,27 +8,25 @@ pub fn sys_fleet(
     max_battery: &mut [f32],
     capacity: &mut [f32],
     mining_rate: &mut [f32],
+    speed: &mut [f32],
     cargo: &mut [f32],
     cargo_profile: &mut [f32], // 5 floats per drone
+    loadouts: &[LoadoutMultipliers], // per drone; missing entries use no equipment
     modules: &Modules,
     modifiers: &ResourceModifierSnapshot,
 ) {
     let drone_count = battery.len();
 
-    // Calculate base stats
-    let speed_bonus = 1.0 + (modules.drone_bay as f32 - 1.0).max(0.0) * 0.05;
-    let _base_speed = DRONE_SPEED * speed_bonus;
-    // let target_speed = base_speed * modifiers.drone_production_speed_multiplier;
-
-    let capacity_base = DRONE_MAX_CARGO + (modules.storage as f32) * 5.0;
-    let target_capacity = capacity_base * modifiers.drone_capacity_multiplier;
-
-    let mining_base = DRONE_MINING_RATE + (modules.refinery as f32) * 0.5;
-    let target_mining_rate = mining_base * modifiers.drone_production_speed_multiplier;
-
-    let target_max_battery = DRONE_MAX_BATTERY * modifiers.drone_battery_multiplier;
-
     for i in 0..drone_count {
+        let loadout = loadouts.get(i).copied().unwrap_or_default();
+        let stats = drone_stats(modules, modifiers, &loadout);
+        let target_capacity = stats.capacity;
+        let target_mining_rate = stats.mining_rate;
+        let target_max_battery = stats.max_battery;
+        if let Some(slot) = speed.get_mut(i) {
+            *slot = stats.speed;
+        }
+
         // Update capacity
         if cargo[i] > target_capacity {
             let scale = if target_capacity > 0.0 { target_capacity / cargo[i] } else { 0.0 };
@@ -0,0 +1,167 @@
+use crate::schema::{FactorySnapshot, HaulerFlight, PendingTransfer};
+use crate::spatial::distance;
+use crate::systems::logistics::{WAREHOUSE_NODE_ID, WAREHOUSE_POSITION};
+
+/// Route index standing in for the warehouse.
+pub const WAREHOUSE_ROUTE_INDEX: f32 = -1.0;
+const MIN_TRAVEL_TIME: f32 = 1e-3;
+/// Keeps a hauler on a zero-length route able to fly on if its transfer is rerouted.
+const MIN_HAULER_SPEED: f32 = 0.1;
+
+/// Transfers with a hauler in flight; slot `i` of the hauler buffers is the `i`-th of these.
+pub fn scheduled_transfers(transfers: &[PendingTransfer]) -> impl Iterator<Item = &PendingTransfer> {
+    transfers.iter().filter(|transfer| transfer.status == "scheduled")
+}
+
+fn route_end(factories: &[FactorySnapshot], node_id: &str) -> (f32, [f32; 3]) {
+    if node_id == WAREHOUSE_NODE_ID {
+        return (WAREHOUSE_ROUTE_INDEX, WAREHOUSE_POSITION);
+    }
+    factories
+        .iter()
+        .position(|factory| factory.id == node_id)
+        .map(|idx| (idx as f32, factories[idx].position))
+        .unwrap_or((WAREHOUSE_ROUTE_INDEX, WAREHOUSE_POSITION))
+}
+
+/// When the hauler finishes unloading and its cargo lands, once it has reached the destination.
+pub fn landed_at(transfer: &PendingTransfer) -> Option<f32> {
+    let arrived_at = transfer.hauler.as_ref()?.arrived_at?;
+    Some(arrived_at + transfer.dropoff_overhead.max(0.0))
+}
+
+/// Moves every in-flight hauler towards its destination up to `game_time`. A hauler stays at the
+/// source for the pickup overhead, then launches at the speed that would land it at `eta` and
+/// flies straight at the destination, which may change under it (a rerouted transfer). Large time
+/// steps are exact: the arrival time is interpolated within the step.
+pub fn advance_haulers(transfers: &mut [PendingTransfer], factories: &[FactorySnapshot], game_time: f32) {
+    for transfer in transfers.iter_mut().filter(|transfer| transfer.status == "scheduled") {
+        let launch = transfer.departed_at + transfer.pickup_overhead.max(0.0);
+        if game_time < launch && transfer.hauler.is_none() {
+            continue;
+        }
+        let (_, from) = route_end(factories, &transfer.from_factory_id);
+        let (_, to) = route_end(factories, &transfer.to_factory_id);
+        let hauler = transfer.hauler.get_or_insert_with(|| {
+            let flight = (transfer.eta - transfer.dropoff_overhead.max(0.0) - launch).max(MIN_TRAVEL_TIME);
+            HaulerFlight {
+                position: from,
+                velocity: [0.0; 3],
+                speed: (distance(&from, &to) / flight).max(MIN_HAULER_SPEED),
+                moved_at: launch,
+                arrived_at: None,
+            }
+        });
+        if hauler.arrived_at.is_some() || game_time <= hauler.moved_at {
+            continue;
+        }
+
+        let remaining = distance(&hauler.position, &to);
+        let reach = hauler.speed * (game_time - hauler.moved_at);
+        if reach >= remaining {
+            hauler.arrived_at = Some(hauler.moved_at + remaining / hauler.speed);
+            hauler.position = to;
+            hauler.velocity = [0.0; 3];
+        } else {
+            for (axis, target) in to.iter().enumerate() {
+                let direction = (target - hauler.position[axis]) / remaining;
+                hauler.position[axis] += direction * reach;
+                hauler.velocity[axis] = direction * hauler.speed;
+            }
+        }
+        hauler.moved_at = game_time;
+    }
+}
+
+/// Writes every in-flight hauler's position, velocity, cargo and route into its buffer slot.
+/// Haulers still loading sit at the source.
+pub fn sys_haulers(
+    hauler_positions: &mut [f32],
+    hauler_velocities: &mut [f32],
+    hauler_cargo: &mut [f32],
+    hauler_routes: &mut [f32],
+    transfers: &[PendingTransfer],
+    factories: &[FactorySnapshot],
+) {
+    for (i, transfer) in scheduled_transfers(transfers).enumerate().take(hauler_cargo.len()) {
+        let (from_idx, from) = route_end(factories, &transfer.from_factory_id);
+        let (to_idx, _) = route_end(factories, &transfer.to_factory_id);
+        let (position, velocity) = transfer
+            .hauler
+            .as_ref()
+            .map(|hauler| (hauler.position, hauler.velocity))
+            .unwrap_or((from, [0.0; 3]));
+
+        hauler_positions[i * 3..i * 3 + 3].copy_from_slice(&position);
+        hauler_velocities[i * 3..i * 3 + 3].copy_from_slice(&velocity);
+        hauler_cargo[i] = transfer.amount;
+        hauler_routes[i * 2] = from_idx;
+        hauler_routes[i * 2 + 1] = to_idx;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn haulers_wait_out_pickup_then_fly_and_follow_reroutes() {
+        let factories = vec![
+            FactorySnapshot {
+                id: "factory-a".to_string(),
+                position: [100.0, 0.0, 0.0],
+                ..Default::default()
+            },
+            FactorySnapshot {
+                id: "factory-b".to_string(),
+                position: [50.0, 40.0, 0.0],
+                ..Default::default()
+            },
+        ];
+        let mut transfers = vec![PendingTransfer {
+            id: "t1".to_string(),
+            from_factory_id: "factory-a".to_string(),
+            to_factory_id: WAREHOUSE_NODE_ID.to_string(),
+            resource: "ore".to_string(),
+            amount: 40.0,
+            status: "scheduled".to_string(),
+            eta: 14.0,
+            departed_at: 0.0,
+            pickup_overhead: 2.0,
+            dropoff_overhead: 2.0,
+            hauler: None,
+            carrier_id: None,
+        }];
+        assert_eq!(WAREHOUSE_POSITION, [0.0; 3]);
+
+        advance_haulers(&mut transfers, &factories, 1.0);
+        assert!(transfers[0].hauler.is_none(), "still loading");
+
+        // 100 units in the 10s between pickup and dropoff.
+        advance_haulers(&mut transfers, &factories, 7.0);
+        let hauler = transfers[0].hauler.clone().expect("launched");
+        assert_eq!(hauler.position, [50.0, 0.0, 0.0]);
+        assert_eq!(hauler.velocity, [-10.0, 0.0, 0.0]);
+
+        let mut positions = vec![0.0; 3];
+        let mut velocities = vec![0.0; 3];
+        let mut cargo = vec![0.0];
+        let mut routes = vec![0.0; 2];
+        sys_haulers(&mut positions, &mut velocities, &mut cargo, &mut routes, &transfers, &factories);
+        assert_eq!(positions, vec![50.0, 0.0, 0.0]);
+        assert_eq!(cargo, vec![40.0]);
+        assert_eq!(routes, vec![0.0, WAREHOUSE_ROUTE_INDEX]);
+
+        // Rerouted mid-flight, the hauler turns towards the new destination 40 units away.
+        transfers[0].to_factory_id = "factory-b".to_string();
+        advance_haulers(&mut transfers, &factories, 9.0);
+        assert_eq!(transfers[0].hauler.as_ref().unwrap().position, [50.0, 20.0, 0.0]);
+        assert_eq!(landed_at(&transfers[0]), None, "cargo waits for the hauler");
+
+        advance_haulers(&mut transfers, &factories, 20.0);
+        let hauler = transfers[0].hauler.as_ref().unwrap();
+        assert_eq!(hauler.position, [50.0, 40.0, 0.0]);
+        assert_eq!(hauler.arrived_at, Some(11.0));
+        assert_eq!(landed_at(&transfers[0]), Some(13.0));
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::constants::DRONE_STATE_MINING;
+use crate::schema::{AsteroidHazard, DroneFlight};
+use crate::systems::asteroids::HazardRoll;
+use crate::systems::drone_ai::AsteroidRegionMeta;
+use crate::systems::events::push_event;
+
+const PHASE_OFFSET_STEPS: u32 = 1000;
+const REGION_PHASE_MIX: u32 = 0x9e37_79b9;
+
+/// What a hazard does to drones at its asteroid while active, at medium severity.
+struct HazardProfile {
+    kind: &'static str,
+    active_duration: f32,
+    dormant_duration: f32,
+    battery_drain_per_sec: f32,
+    yield_penalty: f32,
+    cargo_loss_per_sec: f32,
+    flight_slowdown: f32,
+}
+
+const HAZARD_PROFILES: [HazardProfile; 4] = [
+    HazardProfile {
+        kind: "ionStorm",
+        active_duration: 12.0,
+        dormant_duration: 36.0,
+        battery_drain_per_sec: 0.8,
+        yield_penalty: 0.0,
+        cargo_loss_per_sec: 0.0,
+        flight_slowdown: 0.0,
+    },
+    HazardProfile {
+        kind: "solarFlare",
+        active_duration: 8.0,
+        dormant_duration: 40.0,
+        battery_drain_per_sec: 0.0,
+        yield_penalty: 0.0,
+        cargo_loss_per_sec: 0.0,
+        flight_slowdown: 0.5,
+    },
+    HazardProfile {
+        kind: "microQuakes",
+        active_duration: 6.0,
+        dormant_duration: 30.0,
+        battery_drain_per_sec: 0.0,
+        yield_penalty: 0.0,
+        cargo_loss_per_sec: 0.04,
+        flight_slowdown: 0.0,
+    },
+    HazardProfile {
+        kind: "sporeBurst",
+        active_duration: 10.0,
+        dormant_duration: 30.0,
+        battery_drain_per_sec: 0.0,
+        yield_penalty: 0.4,
+        cargo_loss_per_sec: 0.0,
+        flight_slowdown: 0.0,
+    },
+];
+
+fn profile(kind: &str) -> Option<&'static HazardProfile> {
+    HAZARD_PROFILES.iter().find(|p| p.kind == kind)
+}
+
+fn severity_scale(severity: &str) -> f32 {
+    match severity {
+        "low" => 0.5,
+        "high" => 1.5,
+        _ => 1.0,
+    }
+}
+
+/// Per-asteroid effect of whatever hazard is currently active there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HazardEffect {
+    pub battery_drain_per_sec: f32,
+    pub yield_multiplier: f32,
+    pub cargo_loss_per_sec: f32,
+    pub flight_slowdown: f32,
+}
+
+impl Default for HazardEffect {
+    fn default() -> Self {
+        Self {
+            battery_drain_per_sec: 0.0,
+            yield_multiplier: 1.0,
+            cargo_loss_per_sec: 0.0,
+            flight_slowdown: 0.0,
+        }
+    }
+}
+
+/// A hazard phase change.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HazardEvent {
+    pub asteroid_id: String,
+    pub kind: String,
+    pub severity: String,
+    /// "started" or "ended".
+    pub phase: String,
+    pub game_time: f32,
+}
+
+/// Creates the dormant hazard record for a freshly spawned asteroid.
+/// The first dormant phase is shortened by the biome seed so storms across the field do not sync.
+pub fn new_hazard(asteroid_id: &str, roll: &HazardRoll) -> AsteroidHazard {
+    dormant_hazard(asteroid_id, None, roll.kind, roll.severity, roll.seed)
+}
+
+fn dormant_hazard(
+    asteroid_id: &str,
+    region_id: Option<&str>,
+    kind: &str,
+    severity: &str,
+    seed: u32,
+) -> AsteroidHazard {
+    let dormant = profile(kind).map(|p| p.dormant_duration).unwrap_or(0.0);
+    let offset = (seed % PHASE_OFFSET_STEPS) as f32 / PHASE_OFFSET_STEPS as f32;
+    AsteroidHazard {
+        asteroid_id: asteroid_id.to_string(),
+        region_id: region_id.map(str::to_string),
+        kind: kind.to_string(),
+        severity: severity.to_string(),
+        active: false,
+        remaining: dormant * (1.0 - offset).max(0.05),
+    }
+}
+
+/// One dormant hazard per region that rolled a known hazard, phased apart by region.
+pub fn region_hazards(asteroid_id: &str, regions: &[AsteroidRegionMeta], seed: u32) -> Vec<AsteroidHazard> {
+    regions
+        .iter()
+        .enumerate()
+        .filter_map(|(i, region)| {
+            let kind = region.hazard_kind.as_deref().filter(|kind| profile(kind).is_some())?;
+            let severity = region.hazard_severity.as_deref().unwrap_or("medium");
+            let region_seed = seed ^ (i as u32 + 1).wrapping_mul(REGION_PHASE_MIX);
+            Some(dormant_hazard(asteroid_id, Some(&region.id), kind, severity, region_seed))
+        })
+        .collect()
+}
+
+/// Hazards for an asteroid loaded without any: its regions' hazards once it has fractured,
+/// otherwise the asteroid-wide one under `biome.hazard`.
+pub fn seed_hazards(asteroid_id: &str, asteroid: &Value, regions: &[AsteroidRegionMeta]) -> Vec<AsteroidHazard> {
+    let biome = asteroid.get("biome");
+    let seed = biome
+        .and_then(|b| b.get("fractureSeed"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if !regions.is_empty() {
+        return region_hazards(asteroid_id, regions, seed);
+    }
+    let Some(hazard) = biome.and_then(|b| b.get("hazard")) else {
+        return Vec::new();
+    };
+    let kind = hazard.get("id").and_then(|v| v.as_str()).filter(|kind| profile(kind).is_some());
+    let severity = hazard.get("severity").and_then(|v| v.as_str()).unwrap_or("medium");
+    kind.map(|kind| dormant_hazard(asteroid_id, None, kind, severity, seed))
+        .into_iter()
+        .collect()
+}
+
+/// Advances hazard phase timers, queuing an event for every start and end.
+pub fn sys_hazards(
+    hazards: &mut [AsteroidHazard],
+    events: &mut Vec<HazardEvent>,
+    game_time: f32,
+    dt: f32,
+) {
+    if dt <= 0.0 {
+        return;
+    }
+    for hazard in hazards.iter_mut() {
+        let Some(profile) = profile(&hazard.kind) else {
+            continue;
+        };
+        hazard.remaining -= dt;
+        while hazard.remaining <= 0.0 {
+            hazard.active = !hazard.active;
+            hazard.remaining += if hazard.active {
+                profile.active_duration
+            } else {
+                profile.dormant_duration
+            };
+            push_event(
+                events,
+                HazardEvent {
+                    asteroid_id: hazard.asteroid_id.clone(),
+                    kind: hazard.kind.clone(),
+                    severity: hazard.severity.clone(),
+                    phase: if hazard.active { "started" } else { "ended" }.to_string(),
+                    game_time,
+                },
+            );
+        }
+    }
+}
+
+/// Collects the effect of active hazards per asteroid index.
+pub fn asteroid_hazard_effects(
+    hazards: &[AsteroidHazard],
+    asteroid_id_to_index: &BTreeMap<String, usize>,
+    asteroid_count: usize,
+) -> Vec<HazardEffect> {
+    let mut effects = vec![HazardEffect::default(); asteroid_count];
+    for hazard in hazards.iter().filter(|h| h.active) {
+        let (Some(profile), Some(&idx)) = (
+            profile(&hazard.kind),
+            asteroid_id_to_index.get(&hazard.asteroid_id),
+        ) else {
+            continue;
+        };
+        let Some(effect) = effects.get_mut(idx) else {
+            continue;
+        };
+        let scale = severity_scale(&hazard.severity);
+        effect.battery_drain_per_sec += profile.battery_drain_per_sec * scale;
+        effect.yield_multiplier *= (1.0 - profile.yield_penalty * scale).clamp(0.0, 1.0);
+        effect.cargo_loss_per_sec += profile.cargo_loss_per_sec * scale;
+        effect.flight_slowdown = (effect.flight_slowdown + profile.flight_slowdown * scale).min(0.9);
+    }
+    effects
+}
+
+/// Holds back outbound flights heading into an active flare. Runs before `sys_movement`.
+pub fn delay_hazard_flights(
+    flights: &mut [DroneFlight],
+    effects: &[HazardEffect],
+    asteroid_id_to_index: &BTreeMap<String, usize>,
+    dt: f32,
+) {
+    for flight in flights.iter_mut() {
+        if flight.state != "toAsteroid" {
+            continue;
+        }
+        let Some(effect) = flight
+            .target_asteroid_id
+            .as_ref()
+            .and_then(|id| asteroid_id_to_index.get(id))
+            .and_then(|&idx| effects.get(idx))
+        else {
+            continue;
+        };
+        if effect.flight_slowdown > 0.0 {
+            flight.travel.elapsed = (flight.travel.elapsed - dt * effect.flight_slowdown).max(0.0);
+        }
+    }
+}
+
+/// Drains batteries and shakes cargo loose for drones mining inside an active hazard.
+pub fn sys_hazard_effects(
+    effects: &[HazardEffect],
+    drone_states: &[f32],
+    drone_target_asteroid_index: &[f32],
+    drone_battery: &mut [f32],
+    drone_cargo: &mut [f32],
+    drone_cargo_profile: &mut [f32], // [ore, ice, metals, crystals, organics] * N
+    dt: f32,
+) {
+    if dt <= 0.0 || effects.is_empty() {
+        return;
+    }
+    for i in 0..drone_states.len() {
+        if drone_states[i] != DRONE_STATE_MINING {
+            continue;
+        }
+        let target = drone_target_asteroid_index.get(i).copied().unwrap_or(-1.0);
+        if target < 0.0 {
+            continue;
+        }
+        let Some(effect) = effects.get(target as usize) else {
+            continue;
+        };
+
+        if effect.battery_drain_per_sec > 0.0 {
+            drone_battery[i] = (drone_battery[i] - effect.battery_drain_per_sec * dt).max(0.0);
+        }
+        if effect.cargo_loss_per_sec > 0.0 && drone_cargo[i] > 0.0 {
+            let keep = 1.0 - (effect.cargo_loss_per_sec * dt).clamp(0.0, 1.0);
+            drone_cargo[i] *= keep;
+            for value in drone_cargo_profile[i * 5..i * 5 + 5].iter_mut() {
+                *value *= keep;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hazard_cycles_and_applies_effects() {
+        let roll = HazardRoll {
+            kind: "ionStorm",
+            severity: "high",
+            seed: 0,
+        };
+        let mut hazards = vec![new_hazard("a1", &roll)];
+        let mut events = Vec::new();
+        sys_hazards(&mut hazards, &mut events, 40.0, 40.0);
+        assert!(hazards[0].active);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].phase, "started");
+
+        let mut index = BTreeMap::new();
+        index.insert("a1".to_string(), 0);
+        let effects = asteroid_hazard_effects(&hazards, &index, 1);
+        assert!((effects[0].battery_drain_per_sec - 1.2).abs() < 1e-6);
+
+        let mut battery = vec![10.0, 10.0];
+        let mut cargo = vec![0.0, 0.0];
+        let mut profile = vec![0.0; 10];
+        sys_hazard_effects(
+            &effects,
+            &[DRONE_STATE_MINING, DRONE_STATE_MINING],
+            &[0.0, -1.0],
+            &mut battery,
+            &mut cargo,
+            &mut profile,
+            1.0,
+        );
+        assert!((battery[0] - 8.8).abs() < 1e-5);
+        assert_eq!(battery[1], 10.0);
+
+        sys_hazards(&mut hazards, &mut events, 52.0, 12.0);
+        assert!(!hazards[0].active);
+        assert_eq!(events[1].phase, "ended");
+    }
+}
@@ -19,25 +19,43 @@ use crate::constants::{
     WAREHOUSE_STORAGE_MULTIPLIER,
 };
 use crate::modifiers::ResourceModifierSnapshot;
+use crate::spatial::distance;
+use crate::systems::haulers::{advance_haulers, landed_at};
+use crate::systems::transport::{solve_transport, TransportArc};
 use crate::schema::{
     FactoryLogisticsState,
     FactoryResourceSnapshot,
     FactorySnapshot,
     FactoryUpgradeRequestSnapshot,
     InboundSchedule,
+    LogisticsAnalytics,
     LogisticsQueues,
     Modules,
     PendingTransfer,
     Resources,
 };
 
-const WAREHOUSE_NODE_ID: &str = "warehouse";
-const WAREHOUSE_POSITION: [f32; 3] = [0.0, 0.0, 0.0];
+pub const WAREHOUSE_NODE_ID: &str = "warehouse";
+pub const WAREHOUSE_POSITION: [f32; 3] = [0.0, 0.0, 0.0];
 const RESOURCE_TYPES: [&str; 6] = ["ore", "bars", "metals", "crystals", "organics", "ice"];
 const ETA_MATCH_EPS: f32 = 0.001;
 const MIN_AMOUNT_EPS: f32 = 0.001;
 
-#[allow(dead_code)]
+/// Highest hauler priority; the scheduler serves higher priorities first.
+pub const HAULER_PRIORITY_MAX: i32 = 10;
+/// Hauler modes a factory can be set to; anything else schedules like `auto`.
+pub const HAULER_MODES: [&str; 7] = [
+    "auto",
+    "manual",
+    "demand-first",
+    "supply-first",
+    "export-only",
+    "import-only",
+    "warehouse-only",
+];
+/// Highest scheduling rank: every priority splits into a plain tier and a `*-first` tier above it.
+const HAULER_RANK_MAX: i32 = HAULER_PRIORITY_MAX * 2 + 1;
+
 #[derive(Clone)]
 struct ResolvedHaulerConfig {
     capacity: f32,
@@ -47,7 +65,7 @@ struct ResolvedHaulerConfig {
     resource_filters: Vec<String>,
     mode: String,
     priority: i32,
-    routing_efficiency_bonus: f32,
+    routing_bonus: f32,
 }
 
 impl ResolvedHaulerConfig {
@@ -58,8 +76,50 @@ impl ResolvedHaulerConfig {
                 .iter()
                 .any(|entry| entry == resource)
     }
+
+    /// Whether the scheduler moves goods for this factory at all; `manual` factories opt out.
+    fn automatic(&self) -> bool {
+        self.mode != "manual"
+    }
+
+    /// Whether the factory ships its surplus out; `import-only` factories keep it.
+    fn exports(&self) -> bool {
+        self.automatic() && self.mode != "import-only"
+    }
+
+    /// Whether the factory takes deliveries; `export-only` factories don't.
+    fn imports(&self) -> bool {
+        self.automatic() && self.mode != "export-only"
+    }
+
+    /// Rank of the factory's needs: its priority, with `demand-first` ahead of the same priority.
+    fn need_rank(&self) -> i32 {
+        self.priority.clamp(0, HAULER_PRIORITY_MAX) * 2 + i32::from(self.mode == "demand-first")
+    }
+
+    /// Rank of the factory's surplus: its priority, with `supply-first` ahead of the same priority.
+    fn supply_rank(&self) -> i32 {
+        self.priority.clamp(0, HAULER_PRIORITY_MAX) * 2 + i32::from(self.mode == "supply-first")
+    }
+
+    /// Whether the factory trades with other factories rather than only the warehouse.
+    fn trades_with_factories(&self) -> bool {
+        self.mode != "warehouse-only"
+    }
 }
 
+/// Factory indices by descending `rank`, ties kept in network order.
+fn priority_order(
+    resolved_configs: &[ResolvedHaulerConfig],
+    rank: fn(&ResolvedHaulerConfig) -> i32,
+) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..resolved_configs.len()).collect();
+    order.sort_by_key(|&idx| std::cmp::Reverse(rank(&resolved_configs[idx])));
+    order
+}
+
+/// Runs the scheduler when due, advances haulers and lands arrived transfers. Returns the
+/// amount of each resource that landed in the warehouse.
 pub fn sys_logistics(
     logistics_queues: &mut LogisticsQueues,
     factories: &mut [FactorySnapshot],
@@ -68,7 +128,7 @@ pub fn sys_logistics(
     modifiers: &ResourceModifierSnapshot,
     game_time: f32,
     run_scheduler: bool,
-) {
+) -> BTreeMap<String, f32> {
     let warehouse_capacity = compute_warehouse_capacity(modules, modifiers);
 
     if run_scheduler {
@@ -82,13 +142,94 @@ pub fn sys_logistics(
         );
     }
 
-    process_completions(
+    advance_haulers(&mut logistics_queues.pending_transfers, factories, game_time);
+    let warehouse_deliveries = process_completions(
         logistics_queues,
         factories,
         resources,
         warehouse_capacity,
         game_time,
     );
+
+    refresh_hauler_usage(logistics_queues, factories, game_time);
+    warehouse_deliveries
+}
+
+/// The factory whose haulers fly a transfer: a lending factory when one is recorded, else the
+/// source, or the destination when the warehouse ships (the warehouse has no haulers of its own).
+pub fn transfer_carrier_id(transfer: &PendingTransfer) -> &str {
+    if let Some(carrier_id) = &transfer.carrier_id {
+        carrier_id
+    } else if transfer.from_factory_id == WAREHOUSE_NODE_ID {
+        &transfer.to_factory_id
+    } else {
+        &transfer.from_factory_id
+    }
+}
+
+/// Counts each factory's busy haulers (carrying or flying home), records utilization and returns
+/// how many are idle.
+fn refresh_hauler_usage(
+    logistics_queues: &LogisticsQueues,
+    factories: &mut [FactorySnapshot],
+    game_time: f32,
+) -> Vec<i32> {
+    let mut carrying: BTreeMap<&str, i32> = BTreeMap::new();
+    for transfer in &logistics_queues.pending_transfers {
+        if transfer.status == "scheduled" {
+            *carrying.entry(transfer_carrier_id(transfer)).or_default() += 1;
+        }
+    }
+
+    factories
+        .iter_mut()
+        .map(|factory| {
+            let assigned = factory.haulers_assigned.unwrap_or(0).max(0);
+            let carrying = carrying.get(factory.id.as_str()).copied().unwrap_or(0);
+            if assigned == 0 && carrying == 0 && factory.logistics_state.is_none() {
+                return 0;
+            }
+            let logistics_state = ensure_logistics_state(factory);
+            logistics_state.returning_haulers.retain(|&back_at| back_at > game_time);
+            let busy = carrying + logistics_state.returning_haulers.len() as i32;
+            logistics_state.haulers_busy = busy;
+            logistics_state.hauler_utilization = if assigned > 0 {
+                (busy as f32 / assigned as f32).min(1.0)
+            } else {
+                0.0
+            };
+            (assigned - busy).max(0)
+        })
+        .collect()
+}
+
+/// Seconds the hauler spent flying the loaded leg, between pickup and dropoff.
+fn transfer_flight_time(transfer: &PendingTransfer) -> f32 {
+    let arrived_at = transfer
+        .hauler
+        .as_ref()
+        .and_then(|hauler| hauler.arrived_at)
+        .unwrap_or(transfer.eta - transfer.dropoff_overhead);
+    (arrived_at - transfer.departed_at - transfer.pickup_overhead).max(0.0)
+}
+
+fn record_completion(analytics: &mut LogisticsAnalytics, transfer: &PendingTransfer) {
+    analytics.transfers_completed += 1;
+    analytics.units_delivered += transfer.amount;
+    analytics.travel_seconds += transfer_flight_time(transfer);
+    analytics.overhead_seconds += transfer.pickup_overhead + transfer.dropoff_overhead;
+}
+
+/// Books the carrier's hauler for the empty flight home, which takes as long as the loaded leg.
+fn send_hauler_home(factories: &mut [FactorySnapshot], transfer: &PendingTransfer) {
+    let carrier_id = transfer_carrier_id(transfer);
+    let Some(carrier) = factories.iter_mut().find(|factory| factory.id == carrier_id) else {
+        return;
+    };
+    let flight = transfer_flight_time(transfer);
+    ensure_logistics_state(carrier)
+        .returning_haulers
+        .push(landed_at(transfer).unwrap_or(transfer.eta) + flight);
 }
 
 fn process_completions(
@@ -97,11 +238,12 @@ fn process_completions(
     resources: &mut Resources,
     warehouse_capacity: f32,
     game_time: f32,
-) {
+) -> BTreeMap<String, f32> {
+    let mut warehouse_deliveries = BTreeMap::new();
     let mut completed_indices = Vec::new();
 
     for (idx, transfer) in logistics_queues.pending_transfers.iter().enumerate() {
-        if transfer.status == "scheduled" && game_time >= transfer.eta {
+        if transfer.status == "scheduled" && landed_at(transfer).is_some_and(|landed| game_time >= landed) {
             completed_indices.push(idx);
         }
     }
@@ -120,6 +262,10 @@ fn process_completions(
                 status: String::new(),
                 eta: 0.0,
                 departed_at: 0.0,
+                pickup_overhead: 0.0,
+                dropoff_overhead: 0.0,
+                hauler: None,
+                carrier_id: None,
             });
 
         if transfer.to_factory_id == WAREHOUSE_NODE_ID {
@@ -136,6 +282,9 @@ fn process_completions(
                 let current = get_global_resource(resources, &transfer.resource);
                 let updated = (current + transfer.amount).min(warehouse_capacity);
                 set_global_resource(resources, &transfer.resource, updated);
+                if updated > current {
+                    *warehouse_deliveries.entry(transfer.resource.clone()).or_insert(0.0) += updated - current;
+                }
             }
         } else if transfer.from_factory_id == WAREHOUSE_NODE_ID {
             if let Some(dest_factory) = factories
@@ -188,8 +337,12 @@ fn process_completions(
             }
         }
 
+        record_completion(&mut logistics_queues.analytics, &transfer);
+        send_hauler_home(factories, &transfer);
         logistics_queues.pending_transfers.remove(idx);
     }
+
+    warehouse_deliveries
 }
 
 fn process_scheduler(
@@ -220,6 +373,7 @@ fn process_scheduler(
     let network_has_haulers = factories
         .iter()
         .any(|factory| factory.haulers_assigned.unwrap_or(0) > 0);
+    let mut idle_haulers = refresh_hauler_usage(logistics_queues, factories, game_time);
 
     for resource in RESOURCE_TYPES {
         let warehouse_stock = get_global_resource(resources, resource);
@@ -234,6 +388,7 @@ fn process_scheduler(
             resource,
             &resolved_configs,
             logistics_queues,
+            &mut idle_haulers,
             game_time,
         );
 
@@ -247,6 +402,7 @@ fn process_scheduler(
                 resource,
                 &resolved_configs,
                 logistics_queues,
+                &mut idle_haulers,
                 &mut warehouse_space,
                 game_time,
             );
@@ -258,6 +414,7 @@ fn process_scheduler(
                 resource,
                 &resolved_configs,
                 logistics_queues,
+                &mut idle_haulers,
                 &mut warehouse_available,
                 game_time,
             );
@@ -269,6 +426,7 @@ fn process_scheduler(
                 resource,
                 &resolved_configs,
                 logistics_queues,
+                &mut idle_haulers,
                 &mut warehouse_available,
                 game_time,
             );
@@ -281,12 +439,13 @@ fn schedule_factory_to_factory_transfers(
     resource: &str,
     resolved_configs: &[ResolvedHaulerConfig],
     logistics_queues: &mut LogisticsQueues,
+    idle_haulers: &mut [i32],
     game_time: f32,
 ) {
-    let proposals = match_surplus_to_need(factories, resource, resolved_configs, game_time);
+    let proposals = match_surplus_to_need(factories, resource, resolved_configs, idle_haulers, game_time);
 
     for proposal in proposals {
-        if proposal.amount <= MIN_AMOUNT_EPS {
+        if proposal.amount <= MIN_AMOUNT_EPS || idle_haulers[proposal.from_idx] <= 0 {
             continue;
         }
 
@@ -304,8 +463,13 @@ fn schedule_factory_to_factory_transfers(
                 status: "scheduled".to_string(),
                 eta: proposal.eta,
                 departed_at: game_time,
+                pickup_overhead: resolved_configs[proposal.from_idx].pickup_overhead,
+                dropoff_overhead: resolved_configs[proposal.from_idx].dropoff_overhead,
+                hauler: None,
+                carrier_id: None,
             };
             logistics_queues.pending_transfers.push(transfer);
+            idle_haulers[proposal.from_idx] -= 1;
         }
     }
 }
@@ -315,18 +479,20 @@ fn schedule_factory_to_warehouse_transfers(
     resource: &str,
     resolved_configs: &[ResolvedHaulerConfig],
     logistics_queues: &mut LogisticsQueues,
+    idle_haulers: &mut [i32],
     warehouse_space: &mut f32,
     game_time: f32,
 ) {
-    for (idx, factory) in factories.iter_mut().enumerate() {
+    for idx in priority_order(resolved_configs, ResolvedHaulerConfig::supply_rank) {
         if *warehouse_space <= MIN_AMOUNT_EPS {
             break;
         }
-        if factory.haulers_assigned.unwrap_or(0) <= 0 {
+        let factory = &mut factories[idx];
+        let config = &resolved_configs[idx];
+        if factory.haulers_assigned.unwrap_or(0) <= 0 || !config.exports() {
             continue;
         }
 
-        let config = &resolved_configs[idx];
         let target = compute_buffer_target(factory, resource);
         let current = get_factory_resource(&factory.resources, resource);
         let reserved_outbound = factory
@@ -339,7 +505,7 @@ fn schedule_factory_to_warehouse_transfers(
 
         let mut available = (current - target - min_reserve - reserved_outbound).max(0.0);
 
-        while available > MIN_AMOUNT_EPS && *warehouse_space > MIN_AMOUNT_EPS {
+        while available > MIN_AMOUNT_EPS && *warehouse_space > MIN_AMOUNT_EPS && idle_haulers[idx] > 0 {
             let transfer_amount = available
                 .min(config.capacity)
                 .min(*warehouse_space);
@@ -361,9 +527,14 @@ fn schedule_factory_to_warehouse_transfers(
                 status: "scheduled".to_string(),
                 eta,
                 departed_at: game_time,
+                pickup_overhead: config.pickup_overhead,
+                dropoff_overhead: config.dropoff_overhead,
+                hauler: None,
+                carrier_id: None,
             };
 
             logistics_queues.pending_transfers.push(transfer);
+            idle_haulers[idx] -= 1;
 
             *warehouse_space = (*warehouse_space - transfer_amount).max(0.0);
             available -= transfer_amount;
@@ -376,15 +547,19 @@ fn schedule_warehouse_to_factory_transfers(
     resource: &str,
     resolved_configs: &[ResolvedHaulerConfig],
     logistics_queues: &mut LogisticsQueues,
+    idle_haulers: &mut [i32],
     warehouse_available: &mut f32,
     game_time: f32,
 ) {
-    for (idx, factory) in factories.iter_mut().enumerate() {
+    for idx in priority_order(resolved_configs, ResolvedHaulerConfig::need_rank) {
         if *warehouse_available <= MIN_AMOUNT_EPS {
             break;
         }
 
-        let config = &resolved_configs[idx];
+        let factory = &factories[idx];
+        if !resolved_configs[idx].imports() {
+            continue;
+        }
         let target = compute_buffer_target(factory, resource);
         let current = get_factory_resource(&factory.resources, resource);
         let reserved_inbound: f32 = factory
@@ -402,6 +577,11 @@ fn schedule_warehouse_to_factory_transfers(
         let mut remaining_need = (target - current - reserved_inbound).max(0.0);
 
         while remaining_need > MIN_AMOUNT_EPS && *warehouse_available > MIN_AMOUNT_EPS {
+            let Some(carrier_idx) = warehouse_leg_carrier(factories, resolved_configs, idle_haulers, idx)
+            else {
+                break;
+            };
+            let config = &resolved_configs[carrier_idx];
             let transfer_amount = remaining_need
                 .min(config.capacity)
                 .min(*warehouse_available);
@@ -409,20 +589,25 @@ fn schedule_warehouse_to_factory_transfers(
                 break;
             }
 
-            let eta = game_time + compute_travel_time(&WAREHOUSE_POSITION, &factory.position, config);
+            let eta = game_time + compute_travel_time(&WAREHOUSE_POSITION, &factories[idx].position, config);
             let transfer = PendingTransfer {
                 id: generate_transfer_id(logistics_queues.pending_transfers.len(), game_time),
                 from_factory_id: WAREHOUSE_NODE_ID.to_string(),
-                to_factory_id: factory.id.clone(),
+                to_factory_id: factories[idx].id.clone(),
                 resource: resource.to_string(),
                 amount: transfer_amount,
                 status: "scheduled".to_string(),
                 eta,
                 departed_at: game_time,
+                pickup_overhead: config.pickup_overhead,
+                dropoff_overhead: config.dropoff_overhead,
+                hauler: None,
+                carrier_id: borrowed_carrier_id(factories, idx, carrier_idx),
             };
 
-            add_inbound_schedule(factory, &transfer.from_factory_id, resource, transfer_amount, eta);
+            add_inbound_schedule(&mut factories[idx], &transfer.from_factory_id, resource, transfer_amount, eta);
             logistics_queues.pending_transfers.push(transfer);
+            idle_haulers[carrier_idx] -= 1;
 
             *warehouse_available = (*warehouse_available - transfer_amount).max(0.0);
             remaining_need -= transfer_amount;
@@ -435,12 +620,16 @@ fn schedule_upgrade_requests(
     resource: &str,
     resolved_configs: &[ResolvedHaulerConfig],
     logistics_queues: &mut LogisticsQueues,
+    idle_haulers: &mut [i32],
     warehouse_available: &mut f32,
     game_time: f32,
 ) {
     let mut requests: Vec<(usize, usize, i64)> = Vec::new();
 
     for (factory_idx, factory) in factories.iter().enumerate() {
+        if !resolved_configs[factory_idx].imports() {
+            continue;
+        }
         for (req_idx, request) in factory.upgrade_requests.iter().enumerate() {
             if request.status == "pending" || request.status == "partially_fulfilled" {
                 requests.push((factory_idx, req_idx, request.created_at));
@@ -448,15 +637,23 @@ fn schedule_upgrade_requests(
         }
     }
 
-    requests.sort_by_key(|(_, _, created_at)| *created_at);
+    requests.sort_by_key(|&(factory_idx, _, created_at)| {
+        (std::cmp::Reverse(resolved_configs[factory_idx].need_rank()), created_at)
+    });
 
     for (factory_idx, req_idx, _) in requests {
         if *warehouse_available <= MIN_AMOUNT_EPS {
             break;
         }
 
+        let Some(carrier_idx) =
+            warehouse_leg_carrier(factories, resolved_configs, idle_haulers, factory_idx)
+        else {
+            break;
+        };
+        let carrier_id = borrowed_carrier_id(factories, factory_idx, carrier_idx);
         let factory = &mut factories[factory_idx];
-        let config = &resolved_configs[factory_idx];
+        let config = &resolved_configs[carrier_idx];
         let request = factory
             .upgrade_requests
             .get(req_idx)
@@ -490,15 +687,46 @@ fn schedule_upgrade_requests(
             status: "scheduled".to_string(),
             eta,
             departed_at: game_time,
+            pickup_overhead: config.pickup_overhead,
+            dropoff_overhead: config.dropoff_overhead,
+            hauler: None,
+            carrier_id,
         };
 
         add_inbound_schedule(factory, &transfer.from_factory_id, resource, transfer_amount, eta);
         logistics_queues.pending_transfers.push(transfer);
+        idle_haulers[carrier_idx] -= 1;
 
         *warehouse_available = (*warehouse_available - transfer_amount).max(0.0);
     }
 }
 
+/// Picks whose hauler flies a warehouse leg into `to_idx`: the factory's own when one is idle,
+/// otherwise the idle hauler of the automatic factory nearest the warehouse, so factories without
+/// haulers are still supplied. `None` when every hauler in the network is busy.
+fn warehouse_leg_carrier(
+    factories: &[FactorySnapshot],
+    resolved_configs: &[ResolvedHaulerConfig],
+    idle_haulers: &[i32],
+    to_idx: usize,
+) -> Option<usize> {
+    if idle_haulers[to_idx] > 0 {
+        return Some(to_idx);
+    }
+    (0..factories.len())
+        .filter(|&idx| idle_haulers[idx] > 0 && resolved_configs[idx].automatic())
+        .min_by(|&a, &b| {
+            let distance_a = distance(&factories[a].position, &WAREHOUSE_POSITION);
+            let distance_b = distance(&factories[b].position, &WAREHOUSE_POSITION);
+            distance_a.partial_cmp(&distance_b).unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// The lending factory's id when a transfer is flown by a hauler from neither end.
+fn borrowed_carrier_id(factories: &[FactorySnapshot], to_idx: usize, carrier_idx: usize) -> Option<String> {
+    (carrier_idx != to_idx).then(|| factories[carrier_idx].id.clone())
+}
+
 struct ProposedTransfer {
     from_idx: usize,
     to_idx: usize,
@@ -506,10 +734,17 @@ struct ProposedTransfer {
     eta: f32,
 }
 
+/// Solves factory-to-factory matching as a transportation problem: every need that can be
+/// filled is, at the least total travel time x amount. Each idle hauler flies one load to one
+/// destination; priority outranks distance, needs first, then surpluses.
+///
+/// The routing protocol adds its bonus share of each route's flight time to the arc cost, so
+/// distance outweighs dock overhead and a need is paired with its nearest surplus.
 fn match_surplus_to_need(
     factories: &[FactorySnapshot],
     resource: &str,
     resolved_configs: &[ResolvedHaulerConfig],
+    idle_haulers: &[i32],
     game_time: f32,
 ) -> Vec<ProposedTransfer> {
     let mut transfers = Vec::new();
@@ -525,95 +760,153 @@ fn match_surplus_to_need(
         return transfers;
     }
 
-    struct NeedEntry {
-        idx: usize,
-        need: f32,
-        config: ResolvedHaulerConfig,
-    }
-
-    struct SurplusEntry {
+    struct Entry {
         idx: usize,
-        surplus: f32,
-        config: ResolvedHaulerConfig,
+        amount: f32,
+        rank: i32,
     }
 
-    let mut needs: Vec<NeedEntry> = Vec::new();
-    let mut surpluses: Vec<SurplusEntry> = Vec::new();
+    let mut needs: Vec<Entry> = Vec::new();
+    let mut surpluses: Vec<Entry> = Vec::new();
 
     for (idx, factory) in factories.iter().enumerate() {
+        let config = &resolved_configs[idx];
+        if !config.trades_with_factories() || !config.matches_resource(resource) {
+            continue;
+        }
         let target = compute_buffer_target(factory, resource);
         let current = get_factory_resource(&factory.resources, resource);
         let need = (target - current).max(0.0);
-        if need > MIN_AMOUNT_EPS {
-            needs.push(NeedEntry {
-                idx,
-                need,
-                config: resolved_configs[idx].clone(),
-            });
+        if need > MIN_AMOUNT_EPS && config.imports() {
+            needs.push(Entry { idx, amount: need, rank: config.need_rank() });
         }
 
-        if factory.haulers_assigned.unwrap_or(0) <= 0 {
+        if factory.haulers_assigned.unwrap_or(0) <= 0 || !config.exports() {
             continue;
         }
 
         let min_reserve = compute_min_reserve(factory, resource);
-        let surplus = (current - target - min_reserve).max(0.0);
+        let carry_limit = config.capacity * idle_haulers[idx].max(0) as f32;
+        let surplus = (current - target - min_reserve).max(0.0).min(carry_limit);
         if surplus > MIN_AMOUNT_EPS {
-            surpluses.push(SurplusEntry {
-                idx,
-                surplus,
-                config: resolved_configs[idx].clone(),
-            });
+            surpluses.push(Entry { idx, amount: surplus, rank: config.supply_rank() });
         }
     }
 
-    needs.sort_by(|a, b| b.need.partial_cmp(&a.need).unwrap_or(std::cmp::Ordering::Equal));
-    surpluses.sort_by(|a, b| b.surplus.partial_cmp(&a.surplus).unwrap_or(std::cmp::Ordering::Equal));
+    if needs.is_empty() || surpluses.is_empty() {
+        return transfers;
+    }
 
-    for need_entry in needs.iter_mut() {
-        for surplus_entry in surpluses.iter_mut() {
-            if need_entry.need <= MIN_AMOUNT_EPS {
-                break;
-            }
-            if surplus_entry.surplus <= MIN_AMOUNT_EPS {
-                continue;
-            }
+    let by_rank_then_amount = |a: &Entry, b: &Entry| {
+        b.rank
+            .cmp(&a.rank)
+            .then(b.amount.partial_cmp(&a.amount).unwrap_or(std::cmp::Ordering::Equal))
+    };
+    needs.sort_by(by_rank_then_amount);
+    surpluses.sort_by(by_rank_then_amount);
+
+    let mut routes: Vec<(usize, usize, f32)> = Vec::new();
+    for (need_pos, need) in needs.iter().enumerate() {
+        for (surplus_pos, surplus) in surpluses.iter().enumerate() {
+            let travel = compute_travel_time(
+                &factories[surplus.idx].position,
+                &factories[need.idx].position,
+                &resolved_configs[surplus.idx],
+            );
+            routes.push((surplus_pos, need_pos, travel));
+        }
+    }
 
-            if !need_entry.config.matches_resource(resource) || !surplus_entry.config.matches_resource(resource) {
-                continue;
+    let route_costs: Vec<f64> = routes
+        .iter()
+        .map(|&(from, _, travel)| {
+            let config = &resolved_configs[surpluses[from].idx];
+            let flight = (travel - config.pickup_overhead - config.dropoff_overhead).max(0.0);
+            (travel + config.routing_bonus * flight) as f64
+        })
+        .collect();
+    // One rank step costs more than any route, so it only breaks ties between tiers.
+    let rank_step = route_costs.iter().copied().fold(0.0, f64::max) + 1.0;
+    let tiers = (HAULER_RANK_MAX + 1) as f64;
+    let arcs: Vec<TransportArc> = routes
+        .iter()
+        .zip(&route_costs)
+        .map(|(&(from, to, _), &route_cost)| {
+            let need_rank = (HAULER_RANK_MAX - needs[to].rank) as f64;
+            let surplus_rank = (HAULER_RANK_MAX - surpluses[from].rank) as f64;
+            TransportArc {
+                from,
+                to,
+                capacity: resolved_configs[surpluses[from].idx].capacity as f64,
+                cost: route_cost + rank_step * (need_rank * tiers + surplus_rank),
             }
+        })
+        .collect();
+    let supply: Vec<f64> = surpluses.iter().map(|entry| entry.amount as f64).collect();
+    let demand: Vec<f64> = needs.iter().map(|entry| entry.amount as f64).collect();
+    let trips: Vec<usize> = surpluses.iter().map(|entry| idle_haulers[entry.idx].max(0) as usize).collect();
+    let (routes, flows) = solve_with_trip_limits(routes, arcs, &supply, &demand, &trips);
+
+    for (&(from, to, travel), flow) in routes.iter().zip(flows) {
+        let amount = flow as f32;
+        if amount <= MIN_AMOUNT_EPS {
+            continue;
+        }
+        transfers.push(ProposedTransfer {
+            from_idx: surpluses[from].idx,
+            to_idx: needs[to].idx,
+            amount,
+            eta: game_time + travel,
+        });
+    }
 
-            let transfer_amount = need_entry
-                .need
-                .min(surplus_entry.surplus)
-                .min(surplus_entry.config.capacity);
-            if transfer_amount <= MIN_AMOUNT_EPS {
+    transfers
+}
+
+/// Solves the transport problem so that no source uses more routes than it has hauler `trips`;
+/// each arc carries at most one load, so one route is one hauler. A source over its count keeps
+/// its biggest flows, loses its other routes and the network is solved again, which takes at
+/// most one extra pass per source.
+fn solve_with_trip_limits(
+    mut routes: Vec<(usize, usize, f32)>,
+    mut arcs: Vec<TransportArc>,
+    supply: &[f64],
+    demand: &[f64],
+    trips: &[usize],
+) -> (Vec<(usize, usize, f32)>, Vec<f64>) {
+    loop {
+        let flows = solve_transport(supply, demand, &arcs);
+        let mut keep = vec![true; arcs.len()];
+        let mut over_limit = false;
+        for (source, &limit) in trips.iter().enumerate() {
+            let mut used: Vec<usize> = (0..arcs.len())
+                .filter(|&idx| arcs[idx].from == source && flows[idx] > MIN_AMOUNT_EPS as f64)
+                .collect();
+            if used.len() <= limit {
                 continue;
             }
-
-            let eta = game_time
-                + compute_travel_time(
-                    &factories[surplus_entry.idx].position,
-                    &factories[need_entry.idx].position,
-                    &surplus_entry.config,
-                );
-
-            transfers.push(ProposedTransfer {
-                from_idx: surplus_entry.idx,
-                to_idx: need_entry.idx,
-                amount: transfer_amount,
-                eta,
-            });
-
-            need_entry.need -= transfer_amount;
-            surplus_entry.surplus -= transfer_amount;
+            over_limit = true;
+            used.sort_by(|&a, &b| flows[b].partial_cmp(&flows[a]).unwrap_or(std::cmp::Ordering::Equal));
+            used.truncate(limit);
+            for (idx, arc) in arcs.iter().enumerate() {
+                if arc.from == source && !used.contains(&idx) {
+                    keep[idx] = false;
+                }
+            }
+        }
+        if !over_limit {
+            return (routes, flows);
         }
-    }
 
-    transfers
+        let mut kept = keep.iter();
+        routes.retain(|_| *kept.next().unwrap_or(&false));
+        let mut kept = keep.iter();
+        arcs.retain(|_| *kept.next().unwrap_or(&false));
+    }
 }
 
-fn compute_warehouse_capacity(
+/// Room the warehouse has for each resource.
+pub fn compute_warehouse_capacity(
     modules: &Modules,
     modifiers: &ResourceModifierSnapshot,
 ) -> f32 {
@@ -643,11 +936,7 @@ fn compute_travel_time(
     dest: &[f32; 3],
     config: &ResolvedHaulerConfig,
 ) -> f32 {
-    let dx = source[0] - dest[0];
-    let dy = source[1] - dest[1];
-    let dz = source[2] - dest[2];
-    let distance = (dx * dx + dy * dy + dz * dz).sqrt();
-    let travel = distance / config.speed.max(0.1);
+    let travel = distance(source, dest) / config.speed.max(0.1);
     config.pickup_overhead + travel + config.dropoff_overhead
 }
 
@@ -696,15 +985,20 @@ fn resolve_factory_hauler_config(
 
     let efficiency_multiplier = (1.0 - FACTORY_HAULER_EFFICIENCY_PER_LEVEL * efficiency_levels)
         .max(0.2);
+    // Better routing batches loads and trims time spent at each end.
+    let routing_multiplier = (1.0 - routing_bonus).max(0.5);
 
-    let capacity = (base_capacity + capacity_bonus
+    let capacity = ((base_capacity + capacity_bonus
         + capacity_boost_levels * FACTORY_HAULER_CAPACITY_PER_LEVEL)
+        * (1.0 + routing_bonus))
         .max(1.0);
     let speed = (base_speed * speed_multiplier
         + speed_boost_levels * FACTORY_HAULER_SPEED_PER_LEVEL)
         .max(0.05);
-    let pickup_overhead = (base_pickup * overhead_multiplier * efficiency_multiplier).max(0.0);
-    let dropoff_overhead = (base_dropoff * overhead_multiplier * efficiency_multiplier).max(0.0);
+    let pickup_overhead =
+        (base_pickup * overhead_multiplier * efficiency_multiplier * routing_multiplier).max(0.0);
+    let dropoff_overhead =
+        (base_dropoff * overhead_multiplier * efficiency_multiplier * routing_multiplier).max(0.0);
 
     ResolvedHaulerConfig {
         capacity,
@@ -718,7 +1012,7 @@ fn resolve_factory_hauler_config(
             .map(|c| c.mode.clone())
             .unwrap_or_else(|| "auto".to_string()),
         priority: base_config.map(|c| c.priority).unwrap_or(5),
-        routing_efficiency_bonus: routing_bonus,
+        routing_bonus,
     }
 }
 
@@ -0,0 +1,165 @@
+use crate::rng::Mulberry32;
+use crate::schema::{MarketSnapshot, Resources};
+
+/// Seconds between price drift and standing-order passes.
+pub const MARKET_TICK_INTERVAL: f32 = 5.0;
+/// Buying costs this much more than selling the same volume would earn.
+pub const MARKET_BUY_MARKUP: f32 = 1.25;
+/// Share of the gap to the base price closed each market tick.
+const PRICE_RECOVERY_PER_TICK: f32 = 0.1;
+/// Largest random swing per tick, as a fraction of the base price.
+const PRICE_VOLATILITY: f32 = 0.05;
+const MIN_PRICE_FRACTION: f32 = 0.2;
+const MAX_PRICE_FRACTION: f32 = 3.0;
+
+/// A tradeable resource. `depth` is how many units it takes to move the price by a factor of e.
+pub struct MarketGood {
+    pub resource: &'static str,
+    pub base_price: f32,
+    pub depth: f32,
+}
+
+pub const MARKET_GOODS: [MarketGood; 8] = [
+    MarketGood { resource: "ore", base_price: 1.0, depth: 500.0 },
+    MarketGood { resource: "ice", base_price: 1.5, depth: 400.0 },
+    MarketGood { resource: "metals", base_price: 3.0, depth: 300.0 },
+    MarketGood { resource: "crystals", base_price: 5.0, depth: 200.0 },
+    MarketGood { resource: "organics", base_price: 4.0, depth: 250.0 },
+    MarketGood { resource: "bars", base_price: 12.0, depth: 150.0 },
+    MarketGood { resource: "alloys", base_price: 30.0, depth: 80.0 },
+    MarketGood { resource: "coolant", base_price: 8.0, depth: 200.0 },
+];
+
+pub fn market_good(resource: &str) -> Option<&'static MarketGood> {
+    MARKET_GOODS.iter().find(|good| good.resource == resource)
+}
+
+/// Credits per unit the market pays right now; untraded goods sit at their base price.
+pub fn current_price(market: &MarketSnapshot, good: &MarketGood) -> f32 {
+    market
+        .prices
+        .get(good.resource)
+        .copied()
+        .unwrap_or(good.base_price)
+}
+
+/// Sells `amount` units, walking the price down as the volume lands. Returns the credits earned.
+pub fn sell(market: &mut MarketSnapshot, good: &MarketGood, amount: f32) -> f32 {
+    if amount <= 0.0 {
+        return 0.0;
+    }
+    let price = current_price(market, good);
+    let next = (price * (-amount / good.depth).exp()).max(good.base_price * MIN_PRICE_FRACTION);
+    market.prices.insert(good.resource.to_string(), next);
+    amount * (price + next) * 0.5
+}
+
+/// Buys `amount` units if `credits` cover it, pushing the price up. Returns the credits spent.
+pub fn buy(market: &mut MarketSnapshot, good: &MarketGood, amount: f32, credits: f32) -> Option<f32> {
+    if amount <= 0.0 {
+        return None;
+    }
+    let price = current_price(market, good);
+    let next = (price * (amount / good.depth).exp()).min(good.base_price * MAX_PRICE_FRACTION);
+    let cost = amount * (price + next) * 0.5 * MARKET_BUY_MARKUP;
+    if cost > credits {
+        return None;
+    }
+    market.prices.insert(good.resource.to_string(), next);
+    Some(cost)
+}
+
+/// Warehouse stock of a good, for goods the warehouse can hold.
+pub fn warehouse_stock_mut<'a>(resources: &'a mut Resources, resource: &str) -> Option<&'a mut f32> {
+    match resource {
+        "ore" => Some(&mut resources.ore),
+        "ice" => Some(&mut resources.ice),
+        "metals" => Some(&mut resources.metals),
+        "crystals" => Some(&mut resources.crystals),
+        "organics" => Some(&mut resources.organics),
+        "bars" => Some(&mut resources.bars),
+        _ => None,
+    }
+}
+
+/// One market tick: prices drift back toward base with a random swing per good, then standing
+/// orders sell whatever the warehouse holds above their reserve.
+pub fn sys_market(market: &mut MarketSnapshot, resources: &mut Resources, rng: &mut Mulberry32) {
+    for good in MARKET_GOODS.iter() {
+        let price = current_price(market, good);
+        let swing = (rng.next_f32() * 2.0 - 1.0) * PRICE_VOLATILITY * good.base_price;
+        let next = (price + (good.base_price - price) * PRICE_RECOVERY_PER_TICK + swing).clamp(
+            good.base_price * MIN_PRICE_FRACTION,
+            good.base_price * MAX_PRICE_FRACTION,
+        );
+        market.prices.insert(good.resource.to_string(), next);
+    }
+
+    let orders: Vec<(String, f32)> = market
+        .auto_sell
+        .iter()
+        .map(|(resource, reserve)| (resource.clone(), *reserve))
+        .collect();
+    for (resource, reserve) in orders {
+        let Some(good) = market_good(&resource) else {
+            continue;
+        };
+        let Some(stock) = warehouse_stock_mut(resources, &resource) else {
+            continue;
+        };
+        let surplus = *stock - reserve.max(0.0);
+        if surplus <= 0.0 {
+            continue;
+        }
+        *stock -= surplus;
+        resources.credits += sell(market, good, surplus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selling_depresses_prices_that_recover_over_time() {
+        let mut market = MarketSnapshot::default();
+        let bars = market_good("bars").unwrap();
+
+        let first = sell(&mut market, bars, 50.0);
+        let second = sell(&mut market, bars, 50.0);
+        assert!(second < first, "later volume sells for less");
+        let depressed = current_price(&market, bars);
+        assert!(depressed < bars.base_price);
+
+        let mut resources = Resources {
+            ore: 120.0,
+            ..Default::default()
+        };
+        market.auto_sell.insert("ore".to_string(), 20.0);
+        let mut rng = Mulberry32::new(3);
+        for _ in 0..20 {
+            sys_market(&mut market, &mut resources, &mut rng);
+        }
+        assert!(current_price(&market, bars) > depressed);
+        assert_eq!(resources.ore, 20.0);
+        assert!(resources.credits > 0.0);
+
+        let mut replay = MarketSnapshot::default();
+        sell(&mut replay, bars, 50.0);
+        sell(&mut replay, bars, 50.0);
+        replay.auto_sell.insert("ore".to_string(), 20.0);
+        let mut replay_resources = Resources {
+            ore: 120.0,
+            ..Default::default()
+        };
+        let mut rng = Mulberry32::new(3);
+        for _ in 0..20 {
+            sys_market(&mut replay, &mut replay_resources, &mut rng);
+        }
+        assert_eq!(replay, market, "same seed, same prices");
+
+        assert!(buy(&mut market, bars, 10.0, 1.0).is_none());
+        let spent = buy(&mut market, bars, 10.0, 1_000.0).expect("affordable");
+        assert!(spent > 10.0 * bars.base_price * MIN_PRICE_FRACTION);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::constants::*;
 use crate::systems::energy::consume_drone_energy;
+use crate::systems::wear::integrity_efficiency;
 
 const ORE_QUANTIZATION: f32 = 100.0; // 0.01-unit steps
 
@@ -18,8 +19,10 @@ pub fn sys_mining(
     drone_mining_rate: &[f32],
     drone_battery: &mut [f32],
     drone_max_battery: &[f32],
+    drone_integrity: &[f32], // missing entries mine at full efficiency
     asteroid_ore_remaining: &mut [f32],
     asteroid_resource_profile: &[f32],
+    asteroid_yield_multiplier: &[f32], // [multiplier] * M (hazards); missing entries are 1.0
     dt: f32,
     throttle_floor: f32,
     energy_drain_multiplier: f32,
@@ -71,8 +74,13 @@ pub fn sys_mining(
             continue;
         }
 
-        let base_extraction = drone_mining_rate[i] * fraction.fraction * dt;
-        let boosted_extraction = base_extraction * ore_yield_multiplier;
+        let wear_efficiency = drone_integrity
+            .get(i)
+            .map(|&integrity| integrity_efficiency(integrity))
+            .unwrap_or(1.0);
+        let base_extraction = drone_mining_rate[i] * fraction.fraction * dt * wear_efficiency;
+        let hazard_yield = asteroid_yield_multiplier.get(asteroid_idx).copied().unwrap_or(1.0);
+        let boosted_extraction = base_extraction * ore_yield_multiplier * hazard_yield;
         let ore_remaining = asteroid_ore_remaining[asteroid_idx];
         let mined = boosted_extraction.min(capacity_left).min(ore_remaining);
 
@@ -1,12 +1,23 @@
 pub mod asteroids;
+pub mod balance;
+pub mod contracts;
 pub mod drone_ai;
 pub mod energy;
+pub mod events;
+pub mod factory_activity;
 pub mod fleet;
+pub mod haulers;
+pub mod hazards;
 pub mod logistics;
+pub mod market;
 pub mod mining;
 pub mod movement;
+pub mod orders;
 pub mod power;
 pub mod refinery;
+pub mod research;
+pub mod transport;
 pub mod global_refinery;
 pub mod unload;
+pub mod wear;
 
@@ -19,6 +19,7 @@ pub fn sys_movement(
     factory_id_to_index: &BTreeMap<String, usize>,
     asteroid_id_to_index: &BTreeMap<String, usize>,
     positions: &mut [f32],     // [x, y, z] * N
+    velocities: &mut [f32],    // [vx, vy, vz] * N
     states: &mut [f32],        // [state] * N
     battery: &mut [f32],       // [energy] * N
     target_asteroid_index: &mut [f32],  // [target_idx] * N
@@ -33,6 +34,9 @@ pub fn sys_movement(
     }
     let drain_rate = DRONE_ENERGY_COST * energy_drain_multiplier;
 
+    // Drones without an active flight are stationary (mining, unloading, idle).
+    velocities.fill(0.0);
+
     let mut finished_indices = Vec::new();
 
     for (i, flight) in drone_flights.iter_mut().enumerate() {
@@ -44,7 +48,7 @@ pub fn sys_movement(
         // Update state in SoA
         let state_val = match flight.state.as_str() {
             "toAsteroid" => DRONE_STATE_TO_ASTEROID,
-            "returning" => DRONE_STATE_RETURNING,
+            "returning" | "queued" => DRONE_STATE_RETURNING,
             "mining" => DRONE_STATE_MINING,
             "unloading" => DRONE_STATE_UNLOADING,
             _ => DRONE_STATE_IDLE,
@@ -72,6 +76,13 @@ pub fn sys_movement(
         // Compute position
         let pos = compute_travel_position(travel);
 
+        // Velocity follows the path derivative, scaled by the throttle applied this tick.
+        let rate = consumption.fraction / travel.duration;
+        let tangent = compute_travel_tangent(travel);
+        velocities[drone_idx * 3] = tangent[0] * rate;
+        velocities[drone_idx * 3 + 1] = tangent[1] * rate;
+        velocities[drone_idx * 3 + 2] = tangent[2] * rate;
+
         // Update SoA
         positions[drone_idx * 3] = pos[0];
         positions[drone_idx * 3 + 1] = pos[1];
@@ -117,6 +128,10 @@ pub fn sys_movement(
         }
 
         if arrived {
+            velocities[drone_idx * 3] = 0.0;
+            velocities[drone_idx * 3 + 1] = 0.0;
+            velocities[drone_idx * 3 + 2] = 0.0;
+
             // Update state to next state
             let next_state = if flight.state == "toAsteroid" {
                 // If the target asteroid was recycled/removed, keep the target index invalid.
@@ -130,6 +145,9 @@ pub fn sys_movement(
                 DRONE_STATE_MINING
             } else if flight.state == "returning" {
                 DRONE_STATE_UNLOADING
+            } else if flight.state == "queued" {
+                // Reached its holding spot; the AI sends it in once a dock frees up.
+                DRONE_STATE_RETURNING
             } else {
                 DRONE_STATE_IDLE
             };
@@ -175,6 +193,33 @@ fn compute_travel_position(travel: &TravelSnapshot) -> Vector3 {
     }
 }
 
+/// Derivative of the travel path with respect to normalized time `t` (world units per unit `t`).
+fn compute_travel_tangent(travel: &TravelSnapshot) -> Vector3 {
+    let duration = if travel.duration > 0.0 {
+        travel.duration
+    } else {
+        1.0
+    };
+    let t = (travel.elapsed / duration).clamp(0.0, 1.0);
+
+    if let Some(control) = travel.control {
+        // B'(t) = 2(1 - t)(P1 - P0) + 2t(P2 - P1)
+        let a = 2.0 * (1.0 - t);
+        let b = 2.0 * t;
+        [
+            (control[0] - travel.from[0]) * a + (travel.to[0] - control[0]) * b,
+            (control[1] - travel.from[1]) * a + (travel.to[1] - control[1]) * b,
+            (control[2] - travel.from[2]) * a + (travel.to[2] - control[2]) * b,
+        ]
+    } else {
+        [
+            travel.to[0] - travel.from[0],
+            travel.to[1] - travel.from[1],
+            travel.to[2] - travel.from[2],
+        ]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +253,7 @@ mod tests {
         drone_map.insert("d1".to_string(), 0);
         let factory_map = BTreeMap::new();
         let mut positions = vec![0.0, 0.0, 0.0];
+        let mut velocities = vec![0.0, 0.0, 0.0];
         let mut battery = vec![100.0];
         let mut states = vec![0.0];
         let mut target_asteroid_index = vec![0.0];
@@ -221,6 +267,7 @@ mod tests {
             &factory_map,
             &asteroid_map,
             &mut positions,
+            &mut velocities,
             &mut states,
             &mut battery,
             &mut target_asteroid_index,
@@ -235,5 +282,23 @@ mod tests {
         assert!(flights[0].travel.elapsed > 0.0);
         assert!(positions[0] > 0.0);
         assert!(positions[0] < 10.0);
+        assert!((velocities[0] - 1.0).abs() < 1e-4);
+        assert_eq!(velocities[1], 0.0);
+    }
+
+    #[test]
+    fn bezier_tangent_matches_endpoints() {
+        let mut travel = TravelSnapshot {
+            from: [0.0, 0.0, 0.0],
+            to: [10.0, 0.0, 0.0],
+            elapsed: 0.0,
+            duration: 10.0,
+            control: Some([5.0, 5.0, 0.0]),
+        };
+        let start = compute_travel_tangent(&travel);
+        assert_eq!(start, [10.0, 10.0, 0.0]);
+        travel.elapsed = 10.0;
+        let end = compute_travel_tangent(&travel);
+        assert_eq!(end, [10.0, -10.0, 0.0]);
     }
 }
@@ -0,0 +1,131 @@
+use crate::constants::DRONE_STATE_UNLOADING;
+use crate::schema::DroneOrder;
+use crate::systems::events::push_event;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A drone order leaving the books.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroneOrderEvent {
+    pub drone_id: String,
+    /// The order's kind: "goTo", "recall", "hold" or "pin".
+    pub kind: String,
+    /// "completed", "targetLost", "replaced" or "cleared".
+    pub reason: String,
+    pub game_time: f32,
+}
+
+/// Queues an event for an order that was just removed or superseded.
+pub fn push_order_event(
+    events: &mut Vec<DroneOrderEvent>,
+    drone_id: &str,
+    order: &DroneOrder,
+    reason: &str,
+    game_time: f32,
+) {
+    push_event(
+        events,
+        DroneOrderEvent {
+            drone_id: drone_id.to_string(),
+            kind: order.kind().to_string(),
+            reason: reason.to_string(),
+            game_time,
+        },
+    );
+}
+
+/// Drops orders whose asteroid was depleted or respawned, or whose factory is gone.
+/// Runs after the asteroid lifecycle so respawns are seen before drones pick targets.
+pub fn sys_drone_orders(
+    orders: &mut BTreeMap<String, DroneOrder>,
+    events: &mut Vec<DroneOrderEvent>,
+    asteroid_id_to_index: &BTreeMap<String, usize>,
+    asteroid_ore: &[f32],
+    factory_id_to_index: &BTreeMap<String, usize>,
+    game_time: f32,
+) {
+    let asteroid_live = |id: &str| {
+        asteroid_id_to_index
+            .get(id)
+            .is_some_and(|&idx| asteroid_ore.get(idx).copied().unwrap_or(0.0) > 0.0)
+    };
+    orders.retain(|drone_id, order| {
+        let valid = match order {
+            DroneOrder::GoTo { asteroid_id, .. } | DroneOrder::Pin { asteroid_id } => {
+                asteroid_live(asteroid_id)
+            }
+            DroneOrder::Recall { factory_id } => factory_id_to_index.contains_key(factory_id),
+            DroneOrder::Hold => true,
+        };
+        if !valid {
+            push_order_event(events, drone_id, order, "targetLost", game_time);
+        }
+        valid
+    });
+}
+
+/// Completes recalls whose drone has docked and started unloading at the ordered factory.
+/// Runs after movement so a recall stays on the books for the whole flight home.
+pub fn complete_arrived_recalls(
+    orders: &mut BTreeMap<String, DroneOrder>,
+    events: &mut Vec<DroneOrderEvent>,
+    drone_id_to_index: &BTreeMap<String, usize>,
+    factory_id_to_index: &BTreeMap<String, usize>,
+    drone_states: &[f32],
+    drone_target_factory_index: &[f32],
+    game_time: f32,
+) {
+    orders.retain(|drone_id, order| {
+        let DroneOrder::Recall { factory_id } = order else {
+            return true;
+        };
+        let (Some(&drone_idx), Some(&factory_idx)) =
+            (drone_id_to_index.get(drone_id), factory_id_to_index.get(factory_id))
+        else {
+            return true;
+        };
+        let arrived = drone_states.get(drone_idx) == Some(&DRONE_STATE_UNLOADING)
+            && drone_target_factory_index.get(drone_idx) == Some(&(factory_idx as f32));
+        if arrived {
+            push_order_event(events, drone_id, order, "completed", game_time);
+        }
+        !arrived
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_expire_when_their_target_is_gone() {
+        let mut orders = BTreeMap::new();
+        orders.insert(
+            "drone-1".to_string(),
+            DroneOrder::Pin {
+                asteroid_id: "asteroid-1".to_string(),
+            },
+        );
+        orders.insert(
+            "drone-2".to_string(),
+            DroneOrder::Recall {
+                factory_id: "factory-9".to_string(),
+            },
+        );
+        orders.insert("drone-3".to_string(), DroneOrder::Hold);
+        let asteroids = BTreeMap::from([("asteroid-1".to_string(), 0)]);
+        let factories = BTreeMap::from([("factory-0".to_string(), 0)]);
+        let mut events = Vec::new();
+
+        sys_drone_orders(&mut orders, &mut events, &asteroids, &[25.0], &factories, 3.0);
+        assert_eq!(orders.len(), 2);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].drone_id, "drone-2");
+        assert_eq!(events[0].reason, "targetLost");
+
+        sys_drone_orders(&mut orders, &mut events, &asteroids, &[0.0], &factories, 4.0);
+        assert_eq!(orders.keys().collect::<Vec<_>>(), vec!["drone-3"]);
+        assert_eq!(events[1].kind, "pin");
+    }
+}
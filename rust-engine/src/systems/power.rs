@@ -1,7 +1,15 @@
+use crate::buffers::{FACTORY_RESOURCE_STRIDE, FACTORY_UPGRADE_STRIDE};
 use crate::constants::*;
 use crate::schema::{Modules, Resources};
 use std::collections::BTreeMap;
 
+/// Integrity restored per second at a factory, and what each point costs it.
+const REPAIR_RATE: f32 = 4.0;
+const REPAIR_METALS_PER_POINT: f32 = 0.25;
+const REPAIR_ENERGY_PER_POINT: f32 = 0.5;
+/// A disabled drone returns to service once repaired past this much integrity.
+const REENABLE_INTEGRITY: f32 = DRONE_MAX_INTEGRITY * 0.5;
+
 pub fn sys_power(
     resources: &mut Resources,
     modules: &Modules,
@@ -10,10 +18,12 @@ pub fn sys_power(
     factory_upgrades: &[f32],
     drone_battery: &mut [f32],
     drone_max_battery: &[f32],
-    drone_states: &[f32],
+    drone_states: &mut [f32],
     drone_owner_factory_index: &[f32],
     drone_target_factory_index: &[f32],
     drone_charging: &mut [f32],
+    drone_integrity: &mut [f32],
+    factory_resources: &mut [f32], // [ore, ice, metals, crystals, organics, bars, credits, alloys, coolant] * M
     _drone_id_to_index: &BTreeMap<String, usize>,
     _factory_id_to_index: &BTreeMap<String, usize>,
     dt: f32,
@@ -28,7 +38,7 @@ pub fn sys_power(
 
     // 2. Factory Solar Generation
     let factory_count = factory_energy.len();
-    let stride_upg = 5;
+    let stride_upg = FACTORY_UPGRADE_STRIDE;
     let solar_array_level = modules.solar as f32;
     let array_bonus_regen = SOLAR_ARRAY_LOCAL_REGEN_PER_LEVEL * solar_array_level;
     let array_bonus_cap = SOLAR_ARRAY_LOCAL_MAX_ENERGY_PER_LEVEL * solar_array_level;
@@ -119,11 +129,54 @@ pub fn sys_power(
             drone_charging[i] = 0.0;
         }
     }
+
+    // 4. Drone Repair (docked or disabled drones, paid from their factory's metals and energy)
+    for i in 0..drone_count.min(drone_integrity.len()) {
+        let state = drone_states[i];
+        let docked = state == DRONE_STATE_IDLE
+            || state == DRONE_STATE_UNLOADING
+            || state == DRONE_STATE_DISABLED;
+        let deficit = DRONE_MAX_INTEGRITY - drone_integrity[i];
+        if !docked || deficit <= 0.000001 {
+            continue;
+        }
+
+        let owner_idx = drone_owner_factory_index[i];
+        let target_idx = drone_target_factory_index[i];
+        let f_idx = if owner_idx >= 0.0 {
+            owner_idx as usize
+        } else if target_idx >= 0.0 {
+            target_idx as usize
+        } else {
+            continue;
+        };
+        if f_idx >= factory_count || f_idx * FACTORY_RESOURCE_STRIDE + 2 >= factory_resources.len() {
+            continue;
+        }
+
+        let metals = &mut factory_resources[f_idx * FACTORY_RESOURCE_STRIDE + 2];
+        let points = (REPAIR_RATE * dt)
+            .min(deficit)
+            .min(*metals / REPAIR_METALS_PER_POINT)
+            .min(factory_energy[f_idx] / REPAIR_ENERGY_PER_POINT)
+            .max(0.0);
+        if points <= 0.0 {
+            continue;
+        }
+        *metals -= points * REPAIR_METALS_PER_POINT;
+        factory_energy[f_idx] -= points * REPAIR_ENERGY_PER_POINT;
+        drone_integrity[i] += points;
+
+        if state == DRONE_STATE_DISABLED && drone_integrity[i] >= REENABLE_INTEGRITY {
+            drone_states[i] = DRONE_STATE_IDLE;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::recipes::factory_resource_index;
     use crate::schema::{Modules, Resources};
     use std::collections::BTreeMap;
 
@@ -133,12 +186,14 @@ mod tests {
         let modules = Modules::default();
         let mut factory_energy = vec![0.0];
         let factory_max_energy = vec![100.0];
-        let factory_upgrades = vec![0.0, 0.0, 0.0, 0.0, 0.0];
+        let factory_upgrades = vec![0.0; FACTORY_UPGRADE_STRIDE];
         let mut drone_battery = vec![24.0];
         let drone_max_battery = vec![24.0];
-        let drone_states = vec![0.0];
+        let mut drone_states = vec![0.0];
         let drone_owner_factory_index = vec![0.0];
         let mut drone_charging = vec![0.0];
+        let mut drone_integrity = vec![DRONE_MAX_INTEGRITY];
+        let mut factory_resources = vec![0.0; FACTORY_RESOURCE_STRIDE];
         let drone_target_factory_index = vec![0.0];
         let drone_id_to_index = BTreeMap::new();
         let factory_id_to_index = BTreeMap::new();
@@ -152,10 +207,12 @@ mod tests {
             &factory_upgrades,
             &mut drone_battery,
             &drone_max_battery,
-            &drone_states,
+            &mut drone_states,
             &drone_owner_factory_index,
             &drone_target_factory_index,
             &mut drone_charging,
+            &mut drone_integrity,
+            &mut factory_resources,
             &drone_id_to_index,
             &factory_id_to_index,
             dt,
@@ -166,4 +223,44 @@ mod tests {
         assert!(resources.energy > 0.0);
         assert!(factory_energy[0] > 0.0);
     }
+
+    #[test]
+    fn disabled_drone_is_repaired_from_factory_stock() {
+        let mut resources = Resources::default();
+        let mut factory_energy = vec![100.0];
+        let mut drone_battery = vec![24.0];
+        let mut drone_states = vec![DRONE_STATE_DISABLED];
+        let mut drone_charging = vec![0.0];
+        let mut drone_integrity = vec![0.0];
+        let metals = factory_resource_index("metals").expect("metals slot");
+        let mut factory_resources = vec![0.0; FACTORY_RESOURCE_STRIDE];
+        factory_resources[metals] = 100.0;
+
+        for _ in 0..13 {
+            sys_power(
+                &mut resources,
+                &Modules::default(),
+                &mut factory_energy,
+                &[100.0],
+                &[0.0; FACTORY_UPGRADE_STRIDE],
+                &mut drone_battery,
+                &[24.0],
+                &mut drone_states,
+                &[0.0],
+                &[-1.0],
+                &mut drone_charging,
+                &mut drone_integrity,
+                &mut factory_resources,
+                &BTreeMap::new(),
+                &BTreeMap::new(),
+                1.0,
+                1.0,
+                1.0,
+            );
+        }
+
+        assert!((drone_integrity[0] - 52.0).abs() < 1e-4);
+        assert!((factory_resources[metals] - 87.0).abs() < 1e-4);
+        assert_eq!(drone_states[0], DRONE_STATE_IDLE);
+    }
 }
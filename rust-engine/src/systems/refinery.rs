@@ -1,20 +1,38 @@
-use crate::buffers::MAX_REFINE_SLOTS;
+use crate::buffers::{FACTORY_RESOURCE_STRIDE, FACTORY_UPGRADE_STRIDE, MAX_REFINE_SLOTS};
 use crate::constants::*;
+use crate::recipes::{factory_resource_index, recipe_at};
 
 const MIN_BATCH_SIZE: f32 = 10.0;
 const ENERGY_FLOOR_THRESHOLD: f32 = 0.2;
 const MIN_SPEED: f32 = 0.1;
+/// Index of the cooler level within a factory's upgrade stride.
+const COOLER_UPGRADE_INDEX: usize = 6;
+
+/// Heat shed per second by a factory with the given cooler level.
+pub fn factory_heat_dissipation(factory_upgrades: &[f32], factory_idx: usize) -> f32 {
+    let level = factory_upgrades
+        .get(factory_idx * FACTORY_UPGRADE_STRIDE + COOLER_UPGRADE_INDEX)
+        .copied()
+        .unwrap_or(0.0)
+        .max(0.0);
+    FACTORY_HEAT_DISSIPATION + FACTORY_COOLER_DISSIPATION_PER_LEVEL * level
+}
 
 pub fn sys_refinery(
-    resources: &mut [f32],                // [ore, bars, metals, crystals, organics, ice, credits] * N
+    resources: &mut [f32],                // [ore, ice, metals, crystals, organics, bars, credits, alloys, coolant] * N
     refinery_state: &mut [f32],           // [active, amount, progress, speed] * MAX_REFINE_SLOTS * N
+    refine_recipes: &[f32],               // [recipe index] * MAX_REFINE_SLOTS * N
     haulers_assigned: &[f32],             // [count] * N
     energy: &mut [f32],                   // [energy] * N
+    heat: &mut [f32],                     // [heat] * N
+    cooling: &mut [f32],                  // [flag] * N
     idle_energy_per_sec: &[f32],          // per factory
     energy_per_refine: &[f32],            // per factory
     refine_slots: &[i32],                 // per factory
     storage_capacity: &[f32],             // per factory
     effective_energy_capacity: &[f32],    // per factory (includes solar array + modifiers)
+    factory_upgrades: &[f32],             // [docking, refine, storage, energy, solar, unload, cooler] * N
+    overdrive: &[bool],                   // per factory
     dt: f32,
     energy_drain_multiplier: f32,
     storage_capacity_multiplier: f32,
@@ -22,7 +40,7 @@ pub fn sys_refinery(
     refinery_yield_multiplier: f32,
 ) {
     let factory_count = energy.len();
-    let stride_res = 7;
+    let stride_res = FACTORY_RESOURCE_STRIDE;
     let stride_ref = MAX_REFINE_SLOTS * 4;
 
     for i in 0..factory_count {
@@ -47,42 +65,45 @@ pub fn sys_refinery(
         let hauler_drain = hauler_count * 0.5 * dt * energy_drain_multiplier;
         current_energy = (current_energy - hauler_drain).max(0.0);
 
-        // Count active processes
-        let mut active_count = 0;
+        let overdriven = overdrive.get(i).copied().unwrap_or(false);
+        let mut current_heat = heat.get(i).copied().unwrap_or(0.0).max(0.0);
+        let mut cooling_down = cooling.get(i).copied().unwrap_or(0.0) > 0.5;
+
+        let resource_slot = |resource: &str| res_idx + factory_resource_index(resource).unwrap_or(0);
+        let slot_recipe = |s: usize| recipe_at(refine_recipes.get(i * MAX_REFINE_SLOTS + s).copied().unwrap_or(0.0));
+
+        // Start new processes on empty slots (local energy + storage gate)
+        let slot_target = slots_limit.max(1) as f32;
         for s in 0..slots_limit {
-            if refinery_state[ref_idx + s * 4] > 0.5 {
-                active_count += 1;
+            if cooling_down || current_energy <= 0.0 {
+                break;
+            }
+            let slot_offset = ref_idx + s * 4;
+            if refinery_state[slot_offset] > 0.5 {
+                continue;
             }
-        }
 
-        // Start new processes (local energy + storage gate)
-        let mut ore = resources[res_idx]; // Ore is at index 0
-        while active_count < slots_limit && ore > 0.0 && current_energy > 0.0 {
-            let slot_target = slots_limit.max(1) as f32;
-            let batch_size = ore.min((storage_cap / slot_target).max(MIN_BATCH_SIZE));
-
-            // Find empty slot
-            let mut slot_found = false;
-            for s in 0..slots_limit {
-                let slot_offset = ref_idx + s * 4;
-                if refinery_state[slot_offset] < 0.5 {
-                    // Start process
-                    refinery_state[slot_offset] = 1.0; // Active
-                    refinery_state[slot_offset + 1] = batch_size; // Amount
-                    refinery_state[slot_offset + 2] = 0.0; // Progress
-                    refinery_state[slot_offset + 3] = production_speed_multiplier.max(0.0); // Base speed multiplier
-
-                    ore -= batch_size;
-                    active_count += 1;
-                    slot_found = true;
-                    break;
-                }
+            // Batches are counted in units of the recipe; every input must cover the batch.
+            let recipe = slot_recipe(s);
+            let available = recipe
+                .inputs
+                .iter()
+                .map(|&(resource, ratio)| resources[resource_slot(resource)] / ratio)
+                .fold(f32::MAX, f32::min);
+            if available <= 0.0 {
+                continue;
             }
-            if !slot_found {
-                break;
+            let batch_size = available.min((storage_cap / slot_target).max(MIN_BATCH_SIZE));
+            for &(resource, ratio) in recipe.inputs {
+                let stock = &mut resources[resource_slot(resource)];
+                *stock = (*stock - batch_size * ratio).max(0.0);
             }
+
+            refinery_state[slot_offset] = 1.0; // Active
+            refinery_state[slot_offset + 1] = batch_size; // Amount
+            refinery_state[slot_offset + 2] = 0.0; // Progress
+            refinery_state[slot_offset + 3] = production_speed_multiplier.max(0.0); // Base speed multiplier
         }
-        resources[res_idx] = ore;
 
         // Enforce min-one refining semantics under low energy
         let energy_fraction = if effective_energy_cap > 0.0 {
@@ -93,8 +114,11 @@ pub fn sys_refinery(
         let low_energy = energy_fraction < ENERGY_FLOOR_THRESHOLD;
 
         // Tick processes
-        let mut bars_produced = 0.0;
         let mut first_active_seen = false;
+        let mut heat_generated = 0.0;
+        let overdrive_speed = if overdriven { REFINERY_OVERDRIVE_SPEED_MULTIPLIER } else { 1.0 };
+        let heat_per_slot = FACTORY_HEAT_PER_ACTIVE_SLOT
+            * if overdriven { REFINERY_OVERDRIVE_HEAT_MULTIPLIER } else { 1.0 };
 
         for s in 0..slots_limit {
             let slot_offset = ref_idx + s * 4;
@@ -112,22 +136,33 @@ pub fn sys_refinery(
 
                 refinery_state[slot_offset + 3] = speed_mult;
 
+                // A cooling refinery holds its batches where they are.
+                if cooling_down {
+                    continue;
+                }
+                heat_generated += heat_per_slot * speed_mult * dt;
+                let speed_mult = speed_mult * overdrive_speed;
+
+                let recipe = slot_recipe(s);
                 let amount = refinery_state[slot_offset + 1];
                 let mut progress = refinery_state[slot_offset + 2];
 
-                let drain = energy_per_refine * dt * speed_mult * energy_drain_multiplier;
+                let drain = energy_per_refine * recipe.energy_multiplier * dt * speed_mult
+                    * energy_drain_multiplier;
                 let consumed = drain.min(current_energy);
                 current_energy = (current_energy - consumed).max(0.0);
 
                 let adjusted_dt = dt * speed_mult;
                 let prev_progress = progress;
-                progress = (progress + adjusted_dt / FACTORY_REFINE_TIME).min(1.0);
+                progress = (progress + adjusted_dt / recipe.refine_time).min(1.0);
                 let delta = (progress - prev_progress).max(0.0);
 
                 refinery_state[slot_offset + 2] = progress;
 
                 let refined_this_tick = amount * delta;
-                bars_produced += refined_this_tick * refinery_yield_multiplier;
+                for &(resource, ratio) in recipe.outputs {
+                    resources[resource_slot(resource)] += refined_this_tick * ratio * refinery_yield_multiplier;
+                }
 
                 if progress >= 1.0 {
                     refinery_state[slot_offset] = 0.0;
@@ -138,9 +173,33 @@ pub fn sys_refinery(
             }
         }
 
+        // Heat: slots warm the refinery, coolers shed it. Hitting the limit forces a cooldown,
+        // and an overdriven refinery also loses its least-finished batch.
+        let dissipation = factory_heat_dissipation(factory_upgrades, i) * dt;
+        current_heat = (current_heat + heat_generated - dissipation).clamp(0.0, FACTORY_MAX_HEAT);
+        if !cooling_down && current_heat >= FACTORY_MAX_HEAT {
+            cooling_down = true;
+            if overdriven {
+                let failed = (0..slots_limit)
+                    .map(|s| ref_idx + s * 4)
+                    .filter(|&offset| refinery_state[offset] > 0.5)
+                    .min_by(|&a, &b| refinery_state[a + 2].total_cmp(&refinery_state[b + 2]));
+                if let Some(offset) = failed {
+                    refinery_state[offset..offset + 4].fill(0.0);
+                }
+            }
+        } else if cooling_down && current_heat <= FACTORY_MAX_HEAT * FACTORY_HEAT_RESUME_FRACTION {
+            cooling_down = false;
+        }
+        if let Some(slot) = heat.get_mut(i) {
+            *slot = current_heat;
+        }
+        if let Some(slot) = cooling.get_mut(i) {
+            *slot = if cooling_down { 1.0 } else { 0.0 };
+        }
+
         let capped_energy = effective_energy_cap.max(0.0);
         energy[i] = current_energy.min(capped_energy);
-        resources[res_idx + 5] += bars_produced; // Bars are at index 5
     }
 }
 
@@ -151,7 +210,7 @@ mod tests {
     #[test]
     fn test_refinery_basic() {
         let mut resources = vec![
-            100.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, // Factory 1
+            100.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, // Factory 1
         ];
         let _upgrades = vec![
             0.0, 0.0, 0.0, 0.0, 0.0, // Factory 1
@@ -159,6 +218,8 @@ mod tests {
         let mut refinery_state = vec![0.0; MAX_REFINE_SLOTS * 4];
         let haulers_assigned = vec![0.0];
         let mut energy = vec![100.0];
+        let mut heat = vec![0.0];
+        let mut cooling = vec![0.0];
         let dt = 1.0;
         let idle_energy_per_sec = vec![FACTORY_IDLE_ENERGY_PER_SEC];
         let energy_per_refine = vec![FACTORY_ENERGY_PER_REFINE];
@@ -169,13 +230,18 @@ mod tests {
         sys_refinery(
             &mut resources,
             &mut refinery_state,
+            &[0.0; MAX_REFINE_SLOTS],
             &haulers_assigned,
             &mut energy,
+            &mut heat,
+            &mut cooling,
             &idle_energy_per_sec,
             &energy_per_refine,
             &refine_slots,
             &storage_capacity,
             &effective_energy_capacity,
+            &[0.0; FACTORY_UPGRADE_STRIDE],
+            &[false],
             dt,
             1.0,
             1.0,
@@ -194,4 +260,74 @@ mod tests {
         // Energy should be consumed (idle + refine)
         assert!(energy[0] < 100.0);
     }
+
+    #[test]
+    fn overdrive_overheats_into_cooldown_and_fails_a_batch() {
+        let mut resources = vec![10_000.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let mut refinery_state = vec![0.0; MAX_REFINE_SLOTS * 4];
+        let mut energy = vec![10_000.0];
+        let mut heat = vec![0.0];
+        let mut cooling = vec![0.0];
+        let mut run = |heat: &mut Vec<f32>, cooling: &mut Vec<f32>, overdrive: bool, upgrades: &[f32]| {
+            sys_refinery(
+                &mut resources,
+                &mut refinery_state,
+                &[0.0; MAX_REFINE_SLOTS],
+                &[0.0],
+                &mut energy,
+                heat,
+                cooling,
+                &[0.0],
+                &[FACTORY_ENERGY_PER_REFINE],
+                &[FACTORY_REFINE_SLOTS as i32],
+                &[FACTORY_STORAGE_CAPACITY],
+                &[10_000.0],
+                upgrades,
+                &[overdrive],
+                0.5,
+                1.0,
+                1.0,
+                1.0,
+                1.0,
+            );
+            refinery_state.chunks(4).filter(|slot| slot[0] > 0.5).count()
+        };
+        let no_coolers = [0.0; FACTORY_UPGRADE_STRIDE];
+
+        // Two slots at normal speed shed heat as fast as they make it.
+        for _ in 0..100 {
+            run(&mut heat, &mut cooling, false, &no_coolers);
+        }
+        assert_eq!(heat[0], 0.0);
+
+        let mut active = FACTORY_REFINE_SLOTS;
+        let mut ticks = 0;
+        while cooling[0] < 0.5 {
+            active = run(&mut heat, &mut cooling, true, &no_coolers);
+            ticks += 1;
+            assert!(ticks < 200, "overdrive never overheated");
+        }
+        assert_eq!(heat[0], FACTORY_MAX_HEAT);
+        assert_eq!(active, FACTORY_REFINE_SLOTS - 1);
+
+        // Cooling pauses refining until heat falls to the resume point.
+        let mut ticks = 0;
+        while cooling[0] > 0.5 {
+            assert_eq!(run(&mut heat, &mut cooling, true, &no_coolers), active);
+            ticks += 1;
+        }
+        assert!(heat[0] <= FACTORY_MAX_HEAT * FACTORY_HEAT_RESUME_FRACTION);
+
+        // Coolers shed heat faster, so the same cooldown finishes sooner.
+        let mut coolers = no_coolers;
+        coolers[6] = 2.0;
+        let mut cooled_heat = vec![FACTORY_MAX_HEAT];
+        let mut cooled_flag = vec![1.0];
+        let mut cooled_ticks = 0;
+        while cooled_flag[0] > 0.5 {
+            run(&mut cooled_heat, &mut cooled_flag, false, &coolers);
+            cooled_ticks += 1;
+        }
+        assert!(cooled_ticks < ticks);
+    }
 }
@@ -0,0 +1,221 @@
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::error::SimulationError;
+use crate::schema::{ActiveResearchSnapshot, Modules, ResearchSnapshot};
+
+/// Research points per second each lab level produces.
+pub const RESEARCH_POINTS_PER_LAB: f32 = 0.5;
+/// Research points bought by each crystal handed to the labs.
+pub const RESEARCH_POINTS_PER_CRYSTAL: f32 = 0.1;
+/// Extra lab output per data core level.
+const DATA_CORE_OUTPUT_BONUS: f32 = 0.25;
+
+/// Modifier a completed node adds to, as a fraction of the base value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResearchBonus {
+    RefineryYield,
+    DroneCapacity,
+    DroneBattery,
+    StorageCapacity,
+    EnergyGeneration,
+    EnergyStorage,
+}
+
+/// What finishing a node makes available.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ResearchUnlock {
+    /// A module type that can't be bought until the node completes.
+    Module { module: String },
+    /// A cost variant for a factory upgrade.
+    UpgradeVariant { upgrade: String, variant: String },
+    Bonus { bonus: ResearchBonus, amount: f32 },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResearchNode {
+    pub id: String,
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    /// Research points paid up front when the node is started.
+    pub cost: f32,
+    /// Seconds the node takes once started.
+    pub time: f32,
+    #[serde(default)]
+    pub unlocks: Vec<ResearchUnlock>,
+}
+
+static RESEARCH_NODES: OnceLock<Vec<ResearchNode>> = OnceLock::new();
+
+/// The research tree bundled from `data/research_nodes.json`.
+pub fn research_nodes() -> &'static [ResearchNode] {
+    RESEARCH_NODES.get_or_init(|| {
+        parse_research_nodes(include_str!("../../data/research_nodes.json"))
+            .expect("bundled research tree is valid")
+    })
+}
+
+/// Parses a research tree. Ids must be unique and every prerequisite must be listed before the
+/// node that needs it, which also rules out cycles.
+pub fn parse_research_nodes(json: &str) -> Result<Vec<ResearchNode>, SimulationError> {
+    let nodes: Vec<ResearchNode> = serde_json::from_str(json).map_err(SimulationError::parse)?;
+    for (index, node) in nodes.iter().enumerate() {
+        let earlier = &nodes[..index];
+        if earlier.iter().any(|other| other.id == node.id) {
+            return Err(SimulationError::ParseFailure(format!(
+                "research node {} is listed twice",
+                node.id
+            )));
+        }
+        if let Some(missing) = node
+            .prerequisites
+            .iter()
+            .find(|req| !earlier.iter().any(|other| other.id == **req))
+        {
+            return Err(SimulationError::ParseFailure(format!(
+                "research node {} needs {}, which is unknown or listed after it",
+                node.id, missing
+            )));
+        }
+    }
+    Ok(nodes)
+}
+
+pub fn research_node(id: &str) -> Option<&'static ResearchNode> {
+    research_nodes().iter().find(|node| node.id == id)
+}
+
+fn is_completed(completed: &[String], id: &str) -> bool {
+    completed.iter().any(|done| done == id)
+}
+
+/// Modules no node gates are always available.
+pub fn module_unlocked(module_type: &str, completed: &[String]) -> bool {
+    let mut gated = false;
+    for node in research_nodes() {
+        for unlock in &node.unlocks {
+            if matches!(unlock, ResearchUnlock::Module { module } if module == module_type) {
+                if is_completed(completed, &node.id) {
+                    return true;
+                }
+                gated = true;
+            }
+        }
+    }
+    !gated
+}
+
+/// Cost variants no node gates are always available.
+pub fn upgrade_variant_unlocked(upgrade: &str, variant: &str, completed: &[String]) -> bool {
+    let mut gated = false;
+    for node in research_nodes() {
+        for unlock in &node.unlocks {
+            if matches!(unlock, ResearchUnlock::UpgradeVariant { upgrade: u, variant: v } if u == upgrade && v == variant) {
+                if is_completed(completed, &node.id) {
+                    return true;
+                }
+                gated = true;
+            }
+        }
+    }
+    !gated
+}
+
+/// Sum of a bonus across completed nodes.
+pub fn research_bonus(completed: &[String], bonus: ResearchBonus) -> f32 {
+    research_nodes()
+        .iter()
+        .filter(|node| is_completed(completed, &node.id))
+        .flat_map(|node| node.unlocks.iter())
+        .map(|unlock| match unlock {
+            ResearchUnlock::Bonus { bonus: kind, amount } if *kind == bonus => *amount,
+            _ => 0.0,
+        })
+        .sum()
+}
+
+/// Starts a node if it's known, not already done, its prerequisites are done, nothing else is
+/// running and the points cover its cost.
+pub fn start_research(research: &mut ResearchSnapshot, node_id: &str) -> bool {
+    let Some(node) = research_node(node_id) else {
+        return false;
+    };
+    if research.active.is_some()
+        || is_completed(&research.completed, &node.id)
+        || !node.prerequisites.iter().all(|req| is_completed(&research.completed, req))
+        || research.points < node.cost
+    {
+        return false;
+    }
+    research.points -= node.cost;
+    research.active = Some(ActiveResearchSnapshot {
+        id: node.id.clone(),
+        progress: 0.0,
+    });
+    true
+}
+
+/// Labs produce points and the active node advances; a finished node joins `completed`.
+pub fn sys_research(research: &mut ResearchSnapshot, modules: &Modules, dt: f32) {
+    let labs = modules.lab.max(0) as f32;
+    let data_cores = modules.data_core.max(0) as f32;
+    research.points += labs * RESEARCH_POINTS_PER_LAB * (1.0 + DATA_CORE_OUTPUT_BONUS * data_cores) * dt;
+
+    let Some(active) = research.active.as_mut() else {
+        return;
+    };
+    active.progress += dt;
+    let done = research_node(&active.id).is_none_or(|node| active.progress >= node.time);
+    if done {
+        let finished = research.active.take().map(|active| active.id).unwrap_or_default();
+        if research_node(&finished).is_some() && !is_completed(&research.completed, &finished) {
+            research.completed.push(finished);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn research_follows_prerequisites_and_unlocks_its_rewards() {
+        let mut research = ResearchSnapshot::default();
+        let modules = Modules {
+            lab: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(research_nodes().len(), 6);
+        assert!(parse_research_nodes(r#"[{"id": "a", "prerequisites": ["b"], "cost": 1, "time": 1}]"#).is_err());
+        assert!(parse_research_nodes(
+            r#"[{"id": "a", "cost": 1, "time": 1}, {"id": "a", "cost": 1, "time": 1}]"#
+        )
+        .is_err());
+
+        assert!(!start_research(&mut research, "refiningTheory"), "no points yet");
+        sys_research(&mut research, &modules, 60.0);
+        assert_eq!(research.points, 60.0);
+        assert!(!start_research(&mut research, "alloyEngineering"), "prerequisite missing");
+        assert!(start_research(&mut research, "refiningTheory"));
+        assert!(!start_research(&mut research, "lightweightFrames"), "one node at a time");
+        assert_eq!(research.points, 10.0);
+
+        assert!(!upgrade_variant_unlocked("storage", "alloys", &research.completed));
+        assert!(!module_unlocked("dataCore", &research.completed));
+        assert!(module_unlocked("lab", &research.completed));
+        sys_research(&mut research, &modules, 60.0);
+        assert!(research.active.is_none());
+        assert_eq!(research.completed, vec!["refiningTheory".to_string()]);
+        assert_eq!(research_bonus(&research.completed, ResearchBonus::RefineryYield), 0.05);
+
+        research.points = 150.0;
+        assert!(start_research(&mut research, "alloyEngineering"));
+        sys_research(&mut research, &Modules::default(), 180.0);
+        assert!(upgrade_variant_unlocked("storage", "alloys", &research.completed));
+        assert!(upgrade_variant_unlocked("storage", "organics", &research.completed));
+    }
+}
@@ -0,0 +1,164 @@
+/// Residual capacity below this counts as saturated.
+const FLOW_EPS: f64 = 1e-6;
+
+/// A route from supply `from` to demand `to` that can carry up to `capacity` at `cost` per unit.
+pub struct TransportArc {
+    pub from: usize,
+    pub to: usize,
+    pub capacity: f64,
+    pub cost: f64,
+}
+
+struct Edge {
+    to: usize,
+    capacity: f64,
+    cost: f64,
+}
+
+struct FlowGraph {
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
+        }
+    }
+
+    /// Adds an edge and its residual twin; the forward edge's index is returned and the twin is
+    /// always at `index ^ 1`.
+    fn add_edge(&mut self, from: usize, to: usize, capacity: f64, cost: f64) -> usize {
+        let index = self.edges.len();
+        self.edges.push(Edge { to, capacity, cost });
+        self.adjacency[from].push(index);
+        self.edges.push(Edge { to: from, capacity: 0.0, cost: -cost });
+        self.adjacency[to].push(index + 1);
+        index
+    }
+}
+
+/// Ships as much supply to demand as the arcs allow at the least total cost and returns the
+/// flow on each arc. Costs must be non-negative. Successive shortest paths with a dense Dijkstra
+/// keep each pass at O(V^2) per augmentation, and ties resolve by index so results are
+/// deterministic.
+pub fn solve_transport(supply: &[f64], demand: &[f64], arcs: &[TransportArc]) -> Vec<f64> {
+    let source = 0;
+    let supply_base = 1;
+    let demand_base = supply_base + supply.len();
+    let sink = demand_base + demand.len();
+    let nodes = sink + 1;
+
+    let mut graph = FlowGraph::new(nodes);
+    for (i, &amount) in supply.iter().enumerate() {
+        graph.add_edge(source, supply_base + i, amount.max(0.0), 0.0);
+    }
+    for (j, &amount) in demand.iter().enumerate() {
+        graph.add_edge(demand_base + j, sink, amount.max(0.0), 0.0);
+    }
+    let arc_edges: Vec<usize> = arcs
+        .iter()
+        .map(|arc| {
+            graph.add_edge(
+                supply_base + arc.from,
+                demand_base + arc.to,
+                arc.capacity.max(0.0),
+                arc.cost.max(0.0),
+            )
+        })
+        .collect();
+
+    let mut potential = vec![0.0f64; nodes];
+    // Each augmentation saturates an edge; the cap only guards against float churn.
+    let max_augmentations = graph.edges.len() * 2 + 1;
+    for _ in 0..max_augmentations {
+        let mut dist = vec![f64::INFINITY; nodes];
+        let mut via: Vec<Option<usize>> = vec![None; nodes];
+        let mut done = vec![false; nodes];
+        dist[source] = 0.0;
+
+        loop {
+            let mut current = None;
+            for node in 0..nodes {
+                if !done[node] && dist[node].is_finite() && current.is_none_or(|best: usize| dist[node] < dist[best]) {
+                    current = Some(node);
+                }
+            }
+            let Some(node) = current else {
+                break;
+            };
+            done[node] = true;
+
+            for &edge_idx in &graph.adjacency[node] {
+                let edge = &graph.edges[edge_idx];
+                if edge.capacity <= FLOW_EPS || done[edge.to] {
+                    continue;
+                }
+                let reduced = (edge.cost + potential[node] - potential[edge.to]).max(0.0);
+                let candidate = dist[node] + reduced;
+                if candidate < dist[edge.to] {
+                    dist[edge.to] = candidate;
+                    via[edge.to] = Some(edge_idx);
+                }
+            }
+        }
+
+        if !dist[sink].is_finite() {
+            break;
+        }
+        for node in 0..nodes {
+            if dist[node].is_finite() {
+                potential[node] += dist[node];
+            }
+        }
+
+        let mut bottleneck = f64::INFINITY;
+        let mut node = sink;
+        while let Some(edge_idx) = via[node] {
+            bottleneck = bottleneck.min(graph.edges[edge_idx].capacity);
+            node = graph.edges[edge_idx ^ 1].to;
+        }
+        let mut node = sink;
+        while let Some(edge_idx) = via[node] {
+            graph.edges[edge_idx].capacity -= bottleneck;
+            graph.edges[edge_idx ^ 1].capacity += bottleneck;
+            node = graph.edges[edge_idx ^ 1].to;
+        }
+    }
+
+    arc_edges
+        .into_iter()
+        .map(|edge_idx| graph.edges[edge_idx ^ 1].capacity)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_ships_everything_it_can_at_the_least_cost() {
+        // Two sources and two sinks where the greedy cheapest-first choice is not optimal:
+        // sending source 0 to sink 0 would force source 1 onto its expensive arc.
+        let supply = [10.0, 10.0];
+        let demand = [10.0, 10.0];
+        let arcs = [
+            TransportArc { from: 0, to: 0, capacity: 10.0, cost: 1.0 },
+            TransportArc { from: 0, to: 1, capacity: 10.0, cost: 2.0 },
+            TransportArc { from: 1, to: 0, capacity: 10.0, cost: 2.0 },
+            TransportArc { from: 1, to: 1, capacity: 10.0, cost: 100.0 },
+        ];
+        assert_eq!(solve_transport(&supply, &demand, &arcs), vec![0.0, 10.0, 10.0, 0.0]);
+
+        // Arc capacity caps what a single route carries, and spare supply stays put.
+        let arcs = [
+            TransportArc { from: 0, to: 0, capacity: 4.0, cost: 1.0 },
+            TransportArc { from: 1, to: 0, capacity: 10.0, cost: 5.0 },
+        ];
+        let flows = solve_transport(&[10.0, 10.0], &[8.0], &arcs);
+        assert_eq!(flows, vec![4.0, 4.0]);
+        assert_eq!(flows, solve_transport(&[10.0, 10.0], &[8.0], &arcs), "deterministic");
+    }
+}
@@ -1,6 +1,30 @@
-use crate::constants::{DRONE_STATE_IDLE, DRONE_STATE_UNLOADING};
+use std::collections::BTreeMap;
+
+use crate::buffers::{FACTORY_RESOURCE_STRIDE, FACTORY_UPGRADE_STRIDE};
+use crate::constants::{
+    DOCKING_METRICS_WINDOW, DRONE_STATE_IDLE, DRONE_STATE_UNLOADING, DRONE_UNLOAD_RATE,
+    FACTORY_UNLOAD_RATE_PER_LEVEL,
+};
 use crate::schema::{FactorySnapshot, Resources};
 
+/// Index of the unload level within a factory's upgrade stride.
+const UNLOAD_UPGRADE_INDEX: usize = 5;
+const CARGO_EPSILON: f32 = 1e-4;
+
+/// Cargo per second a drone docked at this factory hands over.
+pub fn factory_unload_rate(factory_upgrades: &[f32], factory_idx: usize) -> f32 {
+    let level = factory_upgrades
+        .get(factory_idx * FACTORY_UPGRADE_STRIDE + UNLOAD_UPGRADE_INDEX)
+        .copied()
+        .unwrap_or(0.0)
+        .max(0.0);
+    DRONE_UNLOAD_RATE * (1.0 + FACTORY_UNLOAD_RATE_PER_LEVEL * level)
+}
+
+/// Transfers docked drones' cargo into their factory at the factory's unload rate.
+/// A drone keeps its docking slot until its hold is empty, so docking capacity caps throughput.
+/// Without factories the cargo lands in the warehouse and is tallied in `warehouse_deliveries`.
+/// Each factory's `docking` metrics are refreshed from this tick's unloads and its queue.
 pub fn sys_unload(
     drone_states: &mut [f32],
     drone_cargo: &mut [f32],
@@ -11,13 +35,16 @@ pub fn sys_unload(
     drone_positions: &mut [f32],
     factory_positions: &[f32],
     factory_resources: &mut [f32],
+    factory_upgrades: &[f32],
     global_resources: &mut Resources,
     factories: &mut [FactorySnapshot],
     drone_ids: &[String],
-    _dt: f32,
+    warehouse_deliveries: &mut BTreeMap<String, f32>,
+    dt: f32,
 ) {
     let drone_count = drone_states.len();
-    let factory_count = if !factory_resources.is_empty() { factory_resources.len() / 7 } else { 0 };
+    let factory_count = if !factory_resources.is_empty() { factory_resources.len() / FACTORY_RESOURCE_STRIDE } else { 0 };
+    let mut unloaded = vec![0.0f32; factories.len()];
 
     for i in (0..drone_count).rev() {
         if drone_states[i] != DRONE_STATE_UNLOADING {
@@ -44,22 +71,44 @@ pub fn sys_unload(
             factory_idx = 0;
         }
 
-        // Transfer cargo
+        // Snap position to factory
+        if factory_count > 0 {
+            let pos_base = factory_idx * 3;
+            drone_positions[i * 3] = factory_positions[pos_base];
+            drone_positions[i * 3 + 1] = factory_positions[pos_base + 1];
+            drone_positions[i * 3 + 2] = factory_positions[pos_base + 2];
+        }
+
+        // Transfer this tick's share of the cargo, keeping the profile mix
         if cargo > 0.0 {
+            let rate = if factory_count > 0 {
+                factory_unload_rate(factory_upgrades, factory_idx)
+            } else {
+                DRONE_UNLOAD_RATE
+            };
+            let moved = (rate * dt.max(0.0)).min(cargo);
+            let share = moved / cargo;
             let profile_base = i * 5;
-            let ore = drone_cargo_profile[profile_base];
-            let ice = drone_cargo_profile[profile_base + 1];
-            let metals = drone_cargo_profile[profile_base + 2];
-            let crystals = drone_cargo_profile[profile_base + 3];
-            let organics = drone_cargo_profile[profile_base + 4];
+            let profile = &mut drone_cargo_profile[profile_base..profile_base + 5];
 
             // Calculate remainder (ore) if profile doesn't sum to cargo
-            let profile_sum = ore + ice + metals + crystals + organics;
+            let profile_sum: f32 = profile.iter().sum();
             let remainder = (cargo - profile_sum).max(0.0);
-            let total_ore = ore + remainder;
+            let total_ore = (profile[0] + remainder) * share;
+            let ice = profile[1] * share;
+            let metals = profile[2] * share;
+            let crystals = profile[3] * share;
+            let organics = profile[4] * share;
+            for value in profile.iter_mut() {
+                *value -= *value * share;
+            }
+            drone_cargo[i] = cargo - moved;
 
             if factory_count > 0 {
-                let res_base = factory_idx * 7;
+                if let Some(total) = unloaded.get_mut(factory_idx) {
+                    *total += moved;
+                }
+                let res_base = factory_idx * FACTORY_RESOURCE_STRIDE;
                 factory_resources[res_base] += total_ore;
                 factory_resources[res_base + 1] += ice;
                 factory_resources[res_base + 2] += metals;
@@ -71,9 +120,24 @@ pub fn sys_unload(
                 global_resources.metals += metals;
                 global_resources.crystals += crystals;
                 global_resources.organics += organics;
+                for (resource, amount) in [
+                    ("ore", total_ore),
+                    ("ice", ice),
+                    ("metals", metals),
+                    ("crystals", crystals),
+                    ("organics", organics),
+                ] {
+                    if amount > 0.0 {
+                        *warehouse_deliveries.entry(resource.to_string()).or_insert(0.0) += amount;
+                    }
+                }
             }
         }
 
+        if drone_cargo[i] > CARGO_EPSILON {
+            continue;
+        }
+
         // Clear cargo
         drone_cargo[i] = 0.0;
         let profile_base = i * 5;
@@ -100,13 +164,99 @@ pub fn sys_unload(
                 factory.queued_drones.retain(|queued| queued != drone_id);
             }
         }
+    }
 
-        // Snap position to factory
-        if factory_count > 0 {
-            let pos_base = factory_idx * 3;
-            drone_positions[i * 3] = factory_positions[pos_base];
-            drone_positions[i * 3 + 1] = factory_positions[pos_base + 1];
-            drone_positions[i * 3 + 2] = factory_positions[pos_base + 2];
-        }
+    record_docking_metrics(factories, &unloaded, dt);
+}
+
+fn record_docking_metrics(factories: &mut [FactorySnapshot], unloaded: &[f32], dt: f32) {
+    if dt <= 0.0 {
+        return;
+    }
+    let blend = (dt / DOCKING_METRICS_WINDOW).min(1.0);
+    for (factory, &moved) in factories.iter_mut().zip(unloaded) {
+        let capacity = factory.docking_capacity.max(0) as usize;
+        let waiting = factory.queued_drones.len().saturating_sub(capacity) as u32;
+        let metrics = &mut factory.docking;
+        metrics.unload_rate += (moved / dt - metrics.unload_rate) * blend;
+        metrics.units_unloaded += moved;
+        metrics.waiting = waiting;
+        metrics.peak_waiting = metrics.peak_waiting.max(waiting);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factories::create_factory;
+
+    #[test]
+    fn unloading_holds_the_dock_until_cargo_is_transferred() {
+        let mut factories = vec![create_factory("factory-0".to_string(), [0.0, 0.0, 0.0])];
+        factories[0].queued_drones = vec!["drone-0".to_string()];
+        let drone_ids = vec!["drone-0".to_string()];
+        let mut states = vec![DRONE_STATE_UNLOADING];
+        let mut cargo = vec![40.0];
+        let mut profile = vec![10.0, 10.0, 0.0, 0.0, 0.0];
+        let mut target_factory = vec![0.0];
+        let mut owner = vec![-1.0];
+        let mut target_region = vec![-1.0];
+        let mut positions = vec![3.0, 0.0, 0.0];
+        let mut resources = vec![0.0; FACTORY_RESOURCE_STRIDE];
+        let mut upgrades = vec![0.0; FACTORY_UPGRADE_STRIDE];
+        let mut globals = Resources::default();
+        let mut deliveries = BTreeMap::new();
+
+        let mut run = |upgrades: &[f32], factories: &mut [FactorySnapshot], states: &mut [f32]| {
+            sys_unload(
+                states,
+                &mut cargo,
+                &mut profile,
+                &mut target_factory,
+                &mut owner,
+                &mut target_region,
+                &mut positions,
+                &[0.0, 0.0, 0.0],
+                &mut resources,
+                upgrades,
+                &mut globals,
+                factories,
+                &drone_ids,
+                &mut deliveries,
+                1.0,
+            );
+            (cargo[0], resources[0], resources[1])
+        };
+
+        // 20 cargo per second at level 0: half the hold, in the same ore/ice mix.
+        assert_eq!(run(&upgrades, &mut factories, &mut states), (20.0, 15.0, 5.0));
+        assert_eq!(states[0], DRONE_STATE_UNLOADING);
+        assert_eq!(factories[0].queued_drones.len(), 1);
+
+        upgrades[UNLOAD_UPGRADE_INDEX] = 4.0;
+        assert_eq!(run(&upgrades, &mut factories, &mut states), (0.0, 30.0, 10.0));
+        assert_eq!(states[0], DRONE_STATE_IDLE);
+        assert!(factories[0].queued_drones.is_empty());
+        assert!(deliveries.is_empty(), "factory unloads never reach the warehouse");
+        assert_eq!(factories[0].docking.units_unloaded, 40.0);
+    }
+
+    #[test]
+    fn docking_metrics_smooth_throughput_and_track_the_wait() {
+        let mut factories = vec![create_factory("factory-0".to_string(), [0.0, 0.0, 0.0])];
+        factories[0].docking_capacity = 1;
+        factories[0].queued_drones = ["drone-0", "drone-1", "drone-2"].map(String::from).to_vec();
+
+        record_docking_metrics(&mut factories, &[50.0], 5.0);
+        let metrics = &factories[0].docking;
+        assert_eq!(metrics.unload_rate, 5.0, "half the window toward 10 per second");
+        assert_eq!((metrics.waiting, metrics.peak_waiting), (2, 2));
+
+        factories[0].queued_drones.truncate(1);
+        record_docking_metrics(&mut factories, &[0.0], 5.0);
+        let metrics = &factories[0].docking;
+        assert_eq!(metrics.unload_rate, 2.5);
+        assert_eq!(metrics.units_unloaded, 50.0);
+        assert_eq!((metrics.waiting, metrics.peak_waiting), (0, 2));
     }
 }
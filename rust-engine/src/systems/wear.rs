@@ -0,0 +1,172 @@
+use crate::constants::{
+    DRONE_MAX_INTEGRITY, DRONE_STATE_DISABLED, DRONE_STATE_MINING, DRONE_STATE_RETURNING,
+    DRONE_STATE_TO_ASTEROID,
+};
+use crate::systems::drone_ai::AsteroidMetadata;
+use crate::systems::hazards::HazardEffect;
+
+/// Integrity lost per second while mining a region with a standing hazard, by severity.
+const REGION_WEAR_LOW: f32 = 0.2;
+const REGION_WEAR_MEDIUM: f32 = 0.5;
+const REGION_WEAR_HIGH: f32 = 1.0;
+/// Extra wear per second while the asteroid's own hazard is in its active phase.
+const ACTIVE_HAZARD_WEAR: f32 = 1.5;
+/// Below this battery fraction, working drones strain their hull.
+const DEEP_DISCHARGE_FRACTION: f32 = 0.1;
+const DEEP_DISCHARGE_WEAR: f32 = 0.5;
+/// Integrity fraction under which mining efficiency starts to drop.
+const EFFICIENCY_KNEE: f32 = 0.5;
+
+/// Mining efficiency at a given integrity: full above half, falling to 50% at zero.
+pub fn integrity_efficiency(integrity: f32) -> f32 {
+    let fraction = (integrity / DRONE_MAX_INTEGRITY).clamp(0.0, 1.0);
+    if fraction >= EFFICIENCY_KNEE {
+        1.0
+    } else {
+        0.5 + fraction
+    }
+}
+
+fn region_wear(severity: Option<&str>) -> f32 {
+    match severity {
+        Some("low") => REGION_WEAR_LOW,
+        Some("medium") => REGION_WEAR_MEDIUM,
+        Some("high") => REGION_WEAR_HIGH,
+        _ => 0.0,
+    }
+}
+
+/// Drone buffer slices the wear pass reads, with the states and hulls it rewrites.
+pub struct DroneWearBuffers<'a> {
+    pub states: &'a mut [f32],
+    pub target_asteroid_index: &'a [f32],
+    pub target_region_index: &'a [f32],
+    pub battery: &'a [f32],
+    pub max_battery: &'a [f32],
+    pub integrity: &'a mut [f32],
+}
+
+/// Wears drone hulls down from hazardous mining and deep battery discharge.
+/// Drones that reach zero are marked disabled and reported so their flights and cargo can be cleared.
+pub fn sys_wear(
+    drones: &mut DroneWearBuffers,
+    asteroid_metadata: &[AsteroidMetadata],
+    hazard_effects: &[HazardEffect],
+    disabled_indices: &mut Vec<usize>,
+    dt: f32,
+) {
+    let DroneWearBuffers {
+        states: drone_states,
+        target_asteroid_index: drone_target_asteroid_index,
+        target_region_index: drone_target_region_index,
+        battery: drone_battery,
+        max_battery: drone_max_battery,
+        integrity: drone_integrity,
+    } = drones;
+    disabled_indices.clear();
+    if dt <= 0.0 {
+        return;
+    }
+    for i in 0..drone_states.len().min(drone_integrity.len()) {
+        let state = drone_states[i];
+        let working = state == DRONE_STATE_MINING
+            || state == DRONE_STATE_TO_ASTEROID
+            || state == DRONE_STATE_RETURNING;
+        if !working {
+            continue;
+        }
+
+        let mut wear = 0.0;
+        if state == DRONE_STATE_MINING {
+            let target = drone_target_asteroid_index.get(i).copied().unwrap_or(-1.0);
+            if target >= 0.0 {
+                let asteroid_idx = target as usize;
+                let region = drone_target_region_index.get(i).copied().unwrap_or(-1.0);
+                if region >= 0.0 {
+                    let severity = asteroid_metadata
+                        .get(asteroid_idx)
+                        .and_then(|meta| meta.regions.get(region as usize))
+                        .and_then(|r| r.hazard_severity.as_deref());
+                    wear += region_wear(severity);
+                }
+                if hazard_effects
+                    .get(asteroid_idx)
+                    .is_some_and(|effect| *effect != HazardEffect::default())
+                {
+                    wear += ACTIVE_HAZARD_WEAR;
+                }
+            }
+        }
+
+        let max_battery = drone_max_battery.get(i).copied().unwrap_or(0.0);
+        if max_battery > 0.0 && drone_battery[i] / max_battery < DEEP_DISCHARGE_FRACTION {
+            wear += DEEP_DISCHARGE_WEAR;
+        }
+
+        if wear <= 0.0 {
+            continue;
+        }
+        drone_integrity[i] = (drone_integrity[i] - wear * dt).max(0.0);
+        if drone_integrity[i] <= 0.0 {
+            drone_states[i] = DRONE_STATE_DISABLED;
+            disabled_indices.push(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::drone_ai::AsteroidRegionMeta;
+
+    #[test]
+    fn hazardous_mining_wears_hull_until_disabled() {
+        let metadata = vec![AsteroidMetadata {
+            gravity_multiplier: 1.0,
+            regions: vec![AsteroidRegionMeta {
+                hazard_severity: Some("high".to_string()),
+                ..Default::default()
+            }],
+            fracture: None,
+        }];
+        let mut states = vec![DRONE_STATE_MINING, DRONE_STATE_MINING];
+        let mut integrity = vec![10.0, 10.0];
+        let mut disabled = Vec::new();
+
+        sys_wear(
+            &mut DroneWearBuffers {
+                states: &mut states,
+                target_asteroid_index: &[0.0, 0.0],
+                target_region_index: &[0.0, -1.0],
+                battery: &[50.0, 50.0],
+                max_battery: &[100.0, 100.0],
+                integrity: &mut integrity,
+            },
+            &metadata,
+            &[HazardEffect::default()],
+            &mut disabled,
+            4.0,
+        );
+        assert_eq!(integrity, vec![6.0, 10.0]);
+        assert!(disabled.is_empty());
+        assert_eq!(integrity_efficiency(integrity[0]), 0.56);
+
+        sys_wear(
+            &mut DroneWearBuffers {
+                states: &mut states,
+                target_asteroid_index: &[0.0, 0.0],
+                target_region_index: &[0.0, -1.0],
+                battery: &[5.0, 50.0],
+                max_battery: &[100.0, 100.0],
+                integrity: &mut integrity,
+            },
+            &metadata,
+            &[HazardEffect::default()],
+            &mut disabled,
+            5.0,
+        );
+        assert_eq!(integrity[0], 0.0);
+        assert_eq!(states[0], DRONE_STATE_DISABLED);
+        assert_eq!(disabled, vec![0]);
+    }
+}
@@ -53,14 +53,58 @@ impl WasmGameState {
         serde_json::to_string(&result).map_err(|err| JsValue::from_str(&err.to_string()))
     }
 
+    /// Runs the prestige reset; returns the summary JSON, or `null` below the threshold.
+    pub fn prestige(&mut self) -> Result<String, JsValue> {
+        let summary = self.inner.prestige().map_err(to_js_error)?;
+        serde_json::to_string(&summary).map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Reseeds and generates the initial asteroid field; returns how many asteroids were created.
+    pub fn generate_asteroid_field(&mut self, seed: u32) -> Result<u32, JsValue> {
+        let count = self
+            .inner
+            .generate_asteroid_field(seed)
+            .map_err(to_js_error)?;
+        Ok(count as u32)
+    }
+
     pub fn layout_json(&self) -> Result<String, JsValue> {
         serde_json::to_string(&self.inner.layout).map_err(|err| JsValue::from_str(&err.to_string()))
     }
 
+    pub fn layout_descriptor_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.layout.describe())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
     pub fn data_ptr(&self) -> *const u8 {
         self.inner.data.as_ptr() as *const u8
     }
 
+    /// Changes whenever `data` is reallocated or an entity group changes size; the layout and
+    /// every `data_ptr` view must then be re-read.
+    pub fn generation(&self) -> u32 {
+        self.inner.generation()
+    }
+
+    /// Hazard start/end events since the last call, as a JSON array.
+    pub fn drain_hazard_events_json(&mut self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.drain_hazard_events())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Drone orders completed, lost or cancelled since the last call, as a JSON array.
+    pub fn drain_order_events_json(&mut self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.drain_order_events())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Contracts completed, expired or abandoned since the last call, as a JSON array.
+    pub fn drain_contract_events_json(&mut self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.drain_contract_events())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
     pub fn drone_ids_json(&self) -> Result<String, JsValue> {
         serde_json::to_string(self.inner.drone_ids())
             .map_err(|err| JsValue::from_str(&err.to_string()))
@@ -70,6 +114,24 @@ impl WasmGameState {
         serde_json::to_string(self.inner.asteroid_ids())
             .map_err(|err| JsValue::from_str(&err.to_string()))
     }
+
+    /// Current price of every market good, as a JSON object keyed by resource.
+    pub fn market_prices_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.market_prices())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Busy haulers and utilization per factory, as a JSON object keyed by factory id.
+    pub fn hauler_usage_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.hauler_usage())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Transfer id behind each hauler slot, in buffer order.
+    pub fn hauler_transfer_ids_json(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.inner.hauler_transfer_ids())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
 }
 
 #[wasm_bindgen]
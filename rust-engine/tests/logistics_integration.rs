@@ -1,9 +1,12 @@
 use rust_engine::GameState;
+use rust_engine::buffers::FACTORY_RESOURCE_STRIDE;
 use rust_engine::schema::{
     SimulationSnapshot, Resources, Modules, Prestige, SaveMeta, StoreSettings, MetricsSettings,
     FactorySnapshot, LogisticsQueues, PendingTransfer, FactoryResourceSnapshot
 };
 use rust_engine::schema::SCHEMA_VERSION;
+use rust_engine::modifiers::get_resource_modifiers;
+use rust_engine::systems::logistics::sys_logistics;
 use std::collections::BTreeMap;
 
 fn create_base_snapshot() -> SimulationSnapshot {
@@ -31,6 +34,7 @@ fn create_base_snapshot() -> SimulationSnapshot {
             },
             use_rust_sim: false,
             shadow_mode: false,
+            auto_balance_drones: false,
         },
         rng_seed: Some(123),
         drone_flights: vec![],
@@ -41,11 +45,48 @@ fn create_base_snapshot() -> SimulationSnapshot {
         spec_techs: None,
         spec_tech_spent: None,
         prestige_investments: None,
+        asteroid_hazards: Vec::new(),
+        lifetime_stats: None,
+        drone_loadouts: BTreeMap::new(),
+        drone_integrity: BTreeMap::new(),
+        disabled_drones: Default::default(),
+        drone_orders: BTreeMap::new(),
+        assigned_drones: Default::default(),
+        market: Default::default(),
+        contracts: Default::default(),
+        research: Default::default(),
         game_time: 100.0, // Start at 100s
         extra: BTreeMap::new(),
     }
 }
 
+fn factory(id: &str, resources: FactoryResourceSnapshot, x: f32, haulers: i32) -> FactorySnapshot {
+    FactorySnapshot {
+        id: id.to_string(),
+        resources,
+        position: [x, 0.0, 0.0],
+        haulers_assigned: Some(haulers),
+        ..Default::default()
+    }
+}
+
+fn ore(amount: f32) -> FactoryResourceSnapshot {
+    FactoryResourceSnapshot { ore: amount, ..Default::default() }
+}
+
+/// Runs one logistics tick at `game_time`, scheduling new transfers when `run_scheduler` is set.
+fn run_pass(
+    queues: &mut LogisticsQueues,
+    factories: &mut [FactorySnapshot],
+    resources: &mut Resources,
+    modules: &Modules,
+    game_time: f32,
+    run_scheduler: bool,
+) {
+    let modifiers = get_resource_modifiers(resources, 0, None, None, &[]);
+    sys_logistics(queues, factories, resources, modules, &modifiers, game_time, run_scheduler);
+}
+
 #[test]
 fn test_logistics_transfer_completion() {
     let mut snapshot = create_base_snapshot();
@@ -78,6 +119,10 @@ fn test_logistics_transfer_completion() {
         status: "scheduled".to_string(),
         eta: 105.0,
         departed_at: 100.0,
+        pickup_overhead: 1.0,
+        dropoff_overhead: 1.0,
+        hauler: None,
+        carrier_id: None,
     };
 
     if let Some(q) = &mut snapshot.logistics_queues {
@@ -89,9 +134,19 @@ fn test_logistics_transfer_completion() {
     // Step 1: Time 101.0. Transfer should still be there.
     state.step(1.0);
     assert_eq!(state.snapshot().logistics_queues.as_ref().unwrap().pending_transfers.len(), 1);
+    assert_eq!(state.hauler_transfer_ids(), vec!["t1".to_string()]);
+    assert_eq!(state.get_hauler_cargo_mut(), &[10.0]);
+    let usage = state.hauler_usage();
+    assert_eq!((usage["f1"].busy, usage["f1"].utilization), (1, 0.0), "busy without an assigned hauler");
+
+    // Time 102.5: halfway through the flight between pickup and dropoff.
+    state.step(1.5);
+    assert_eq!(state.get_hauler_positions_mut(), &[50.0, 0.0, 0.0]);
 
     // Step 2: Time 106.0. Transfer should be done.
-    state.step(5.0);
+    state.step(3.5);
+    assert!(state.hauler_transfer_ids().is_empty(), "the hauler lands with its transfer");
+    assert!(state.get_hauler_cargo_mut().is_empty());
 
     // Assert transfer removed
     assert_eq!(state.snapshot().logistics_queues.as_ref().unwrap().pending_transfers.len(), 0, "Transfer should be removed");
@@ -105,7 +160,288 @@ fn test_logistics_transfer_completion() {
 
     // Check buffer values via accessors
     let buffer = state.get_factory_resources_mut();
-    // Layout: ore, ice, metals, crystals, organics, bars, credits (7 floats)
+    // Layout: ore, ice, metals, crystals, organics, bars, credits, alloys, coolant
     assert_eq!(buffer[0], 90.0, "Buffer F1 Ore mismatch");
-    assert_eq!(buffer[7], 10.0, "Buffer F2 Ore mismatch");
+    assert_eq!(buffer[FACTORY_RESOURCE_STRIDE], 10.0, "Buffer F2 Ore mismatch");
+}
+
+#[test]
+fn test_haulers_cap_concurrent_transfers_until_they_fly_home() {
+    let mut factories = vec![factory("f1", ore(500.0), 10.0, 1)];
+    let mut queues = LogisticsQueues::default();
+    let mut resources = Resources::default();
+    let tick = |factories: &mut Vec<FactorySnapshot>, queues: &mut LogisticsQueues, resources: &mut Resources, time: f32| {
+        run_pass(queues, factories, resources, &Modules::default(), time, true);
+        let state = factories[0].logistics_state.as_ref().expect("logistics state");
+        (queues.pending_transfers.len(), state.haulers_busy, state.hauler_utilization)
+    };
+
+    // One hauler: one transfer, however much surplus there is.
+    assert_eq!(tick(&mut factories, &mut queues, &mut resources, 0.0), (1, 1, 1.0));
+    // 10 units at speed 1 plus 1s pickup and 1s dropoff: lands at 12, home again at 22.
+    assert_eq!(tick(&mut factories, &mut queues, &mut resources, 12.0), (0, 1, 1.0));
+    assert_eq!(resources.ore, 50.0);
+    assert_eq!(tick(&mut factories, &mut queues, &mut resources, 15.0), (0, 1, 1.0));
+    assert_eq!(tick(&mut factories, &mut queues, &mut resources, 22.5), (1, 1, 1.0));
+
+    factories[0].haulers_assigned = Some(4);
+    assert_eq!(tick(&mut factories, &mut queues, &mut resources, 23.0), (4, 4, 1.0));
+}
+
+#[test]
+fn test_factory_without_haulers_is_supplied_from_the_warehouse() {
+    use rust_engine::schema::FactoryUpgradeRequestSnapshot;
+    use rust_engine::systems::logistics::{transfer_carrier_id, WAREHOUSE_NODE_ID};
+
+    let metals = |amount: f32| FactoryResourceSnapshot { metals: amount, ..Default::default() };
+    let mut factories = vec![
+        // Stocked at its buffer target, so it neither asks for nor ships metals.
+        factory("hub", metals(20.0), 10.0, 2),
+        FactorySnapshot {
+            upgrade_requests: vec![FactoryUpgradeRequestSnapshot {
+                upgrade: "storage".to_string(),
+                resource_needed: metals(40.0),
+                status: "pending".to_string(),
+                ..Default::default()
+            }],
+            ..factory("outpost", FactoryResourceSnapshot::default(), -20.0, 0)
+        },
+    ];
+    let mut queues = LogisticsQueues::default();
+    let mut resources = Resources { metals: 500.0, ..Default::default() };
+
+    run_pass(&mut queues, &mut factories, &mut resources, &Modules::default(), 0.0, true);
+
+    let legs: Vec<(f32, &str)> = queues
+        .pending_transfers
+        .iter()
+        .filter(|transfer| transfer.from_factory_id == WAREHOUSE_NODE_ID && transfer.to_factory_id == "outpost")
+        .map(|transfer| (transfer.amount, transfer_carrier_id(transfer)))
+        .collect();
+    // The metals buffer, then the upgrade request, each flown by one of the hub's haulers.
+    assert_eq!(legs, vec![(20.0, "hub"), (40.0, "hub")]);
+    assert_eq!(factories[0].logistics_state.as_ref().unwrap().haulers_busy, 2);
+}
+
+#[test]
+fn test_hauler_priority_and_mode_steer_the_scheduler() {
+    use rust_engine::SimulationCommand;
+
+    let build = || {
+        let mut snapshot = create_base_snapshot();
+        let bars = |amount: f32| FactoryResourceSnapshot { bars: amount, ..Default::default() };
+        snapshot.factories = vec![
+            factory("src", bars(100.0), 10.0, 1),
+            factory("low", bars(0.0), 20.0, 0),
+            factory("high", bars(0.0), 30.0, 0),
+        ];
+        GameState::from_snapshot(snapshot).expect("Valid snapshot")
+    };
+    let set = |state: &mut GameState, id: &str, mode: Option<&str>, priority: Option<i32>| {
+        state
+            .apply_command(SimulationCommand::SetHaulerConfig {
+                factory_id: id.to_string(),
+                resource_filters: None,
+                mode: mode.map(str::to_string),
+                priority,
+            })
+            .expect("command applies");
+    };
+    let destinations = |state: &GameState| -> Vec<String> {
+        state.snapshot().logistics_queues.as_ref().unwrap().pending_transfers
+            .iter()
+            .map(|transfer| transfer.to_factory_id.clone())
+            .collect()
+    };
+
+    // The only hauler serves the higher-priority need even though the other is nearer.
+    let mut state = build();
+    set(&mut state, "high", None, Some(9));
+    set(&mut state, "src", Some("sideways"), None);
+    assert!(state.snapshot().factories[0].hauler_config.is_none(), "unknown modes are ignored");
+    state.step(2.0);
+    assert_eq!(destinations(&state), vec!["high".to_string()]);
+    assert_eq!(state.snapshot().factories[2].hauler_config.as_ref().unwrap().priority, 9);
+
+    // An export-only factory takes nothing in, and a warehouse-only one skips other factories.
+    let mut state = build();
+    set(&mut state, "high", Some("export-only"), Some(9));
+    set(&mut state, "src", Some("warehouse-only"), None);
+    state.step(2.0);
+    assert_eq!(destinations(&state), vec!["warehouse".to_string()]);
+
+    // Demand-first wins a tie in priority; a manual factory is left out of automatic scheduling.
+    let mut state = build();
+    set(&mut state, "high", Some("demand-first"), None);
+    state.step(2.0);
+    assert_eq!(destinations(&state), vec!["high".to_string()]);
+
+    let mut state = build();
+    set(&mut state, "src", Some("manual"), None);
+    state.step(2.0);
+    assert!(destinations(&state).is_empty());
+    assert_eq!(state.snapshot().factories[0].hauler_config.as_ref().unwrap().mode, "manual");
+}
+
+#[test]
+fn test_routing_protocol_batches_loads_and_shows_in_analytics() {
+    let run = |routing_protocol: i32| {
+        // The sink wants 51 ore (a 75 buffer minus 24 in stock): one routed load, or a plain
+        // load from the near source topped up by a hauler dragged across the map.
+        let mut factories = vec![
+            FactorySnapshot { refine_slots: 3, ..factory("sink", ore(24.0), 100.0, 0) },
+            factory("near", ore(200.0), 90.0, 1),
+            factory("far", ore(300.0), -100.0, 1),
+        ];
+        let mut queues = LogisticsQueues::default();
+        let mut resources = Resources::default();
+        let modules = Modules { routing_protocol, ..Default::default() };
+
+        run_pass(&mut queues, &mut factories, &mut resources, &modules, 0.0, true);
+        let sink_legs: Vec<(String, f32)> = queues
+            .pending_transfers
+            .iter()
+            .filter(|transfer| transfer.to_factory_id == "sink")
+            .map(|transfer| (transfer.from_factory_id.clone(), transfer.amount))
+            .collect();
+        run_pass(&mut queues, &mut factories, &mut resources, &modules, 300.0, false);
+        assert!(queues.pending_transfers.is_empty());
+        (sink_legs, queues.analytics)
+    };
+
+    let (plain_legs, plain) = run(0);
+    let (routed_legs, routed) = run(1);
+    assert_eq!(plain_legs, vec![("near".to_string(), 50.0), ("far".to_string(), 1.0)]);
+    // The bigger routed load fills the sink alone, freeing the far hauler for the warehouse.
+    assert_eq!(routed_legs, vec![("near".to_string(), 51.0)]);
+
+    assert_eq!(plain.transfers_completed, 2);
+    assert_eq!(routed.transfers_completed, 2);
+    assert_eq!(plain.units_delivered, 51.0);
+    assert_eq!(routed.units_delivered, 102.0);
+    assert!(routed.travel_seconds < plain.travel_seconds);
+    assert!(routed.overhead_seconds < plain.overhead_seconds);
+}
+
+#[test]
+fn test_routing_protocol_pairs_a_need_with_its_nearest_surplus() {
+    use rust_engine::schema::HaulerConfig;
+
+    let source_for_sink = |routing_protocol: i32| {
+        // The near source docks slowly, so without routing the far one is the quicker trip.
+        let slow_docks = HaulerConfig {
+            capacity: 50.0,
+            speed: 1.0,
+            pickup_overhead: 6.0,
+            dropoff_overhead: 1.0,
+            resource_filters: Vec::new(),
+            mode: "auto".to_string(),
+            priority: 5,
+        };
+        let mut factories = vec![
+            factory("sink", ore(0.0), 0.0, 0),
+            FactorySnapshot { hauler_config: Some(slow_docks), ..factory("near", ore(200.0), 10.0, 1) },
+            factory("far", ore(200.0), -14.0, 1),
+        ];
+        let mut queues = LogisticsQueues::default();
+        let mut resources = Resources::default();
+        let modules = Modules { routing_protocol, ..Default::default() };
+
+        run_pass(&mut queues, &mut factories, &mut resources, &modules, 0.0, true);
+        queues
+            .pending_transfers
+            .iter()
+            .filter(|transfer| transfer.to_factory_id == "sink")
+            .map(|transfer| transfer.from_factory_id.clone())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(source_for_sink(0), vec!["far".to_string()]);
+    // Trimmed overheads alone still leave the far trip 0.2s quicker at this level.
+    assert_eq!(source_for_sink(8), vec!["near".to_string()]);
+}
+
+#[test]
+fn test_each_idle_hauler_flies_one_load_to_one_destination() {
+    // The near source could cover both needs in one plan, but it has a single hauler.
+    let mut factories = vec![
+        factory("a", ore(0.0), 10.0, 0),
+        factory("b", ore(0.0), 12.0, 0),
+        factory("near", ore(200.0), 11.0, 1),
+        factory("far", ore(200.0), 40.0, 1),
+    ];
+    let mut queues = LogisticsQueues::default();
+    let mut resources = Resources::default();
+
+    run_pass(&mut queues, &mut factories, &mut resources, &Modules::default(), 0.0, true);
+    let mut legs: Vec<(String, String)> = queues
+        .pending_transfers
+        .iter()
+        .map(|transfer| (transfer.to_factory_id.clone(), transfer.from_factory_id.clone()))
+        .collect();
+    legs.sort();
+    assert_eq!(legs.len(), 2);
+    assert_eq!((legs[0].0.as_str(), legs[1].0.as_str()), ("a", "b"), "both needs are served");
+    assert_ne!(legs[0].1, legs[1].1, "by two haulers");
+}
+
+/// 50 factories on a ring: every other one is stocked with all six resources and ships.
+fn ring_network() -> Vec<FactorySnapshot> {
+    (0..50)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::TAU / 50.0;
+            let stock = if i % 2 == 0 { 200.0 + i as f32 } else { 0.0 };
+            FactorySnapshot {
+                id: format!("factory-{i}"),
+                resources: FactoryResourceSnapshot {
+                    ore: stock,
+                    bars: stock,
+                    metals: stock,
+                    crystals: stock,
+                    organics: stock,
+                    ice: stock,
+                    ..Default::default()
+                },
+                position: [angle.cos() * 300.0, 0.0, angle.sin() * 300.0],
+                haulers_assigned: Some(if i % 2 == 0 { 4 } else { 1 }),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_scheduler_pass_respects_trip_limits_on_a_large_network() {
+    let mut factories = ring_network();
+    let mut queues = LogisticsQueues::default();
+    let mut resources = Resources::default();
+
+    run_pass(&mut queues, &mut factories, &mut resources, &Modules::default(), 0.0, true);
+
+    assert!(!queues.pending_transfers.is_empty());
+    for factory in &factories {
+        let trips = queues
+            .pending_transfers
+            .iter()
+            .filter(|transfer| rust_engine::systems::logistics::transfer_carrier_id(transfer) == factory.id)
+            .count();
+        assert!(trips <= factory.haulers_assigned.unwrap_or(0) as usize, "{} overbooked", factory.id);
+    }
+}
+
+/// Wall-clock check, so it only runs on request: `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn test_scheduler_pass_stays_within_the_cadence_on_a_large_network() {
+    use std::time::{Duration, Instant};
+
+    let mut factories = ring_network();
+    let mut queues = LogisticsQueues::default();
+    let mut resources = Resources::default();
+
+    let started = Instant::now();
+    run_pass(&mut queues, &mut factories, &mut resources, &Modules::default(), 0.0, true);
+    let elapsed = started.elapsed();
+    // The scheduler runs every 2 seconds; a pass should take a small slice of that.
+    assert!(elapsed < Duration::from_millis(200), "a scheduler pass took {elapsed:?}");
 }
@@ -10,6 +10,8 @@ export interface RefineProcess {
   id: string;
   /** The type of ore being refined (e.g., 'ore'). */
   oreType: string;
+  /** Recipe id the batch runs, e.g. 'bars' (set by the Rust engine). */
+  recipe?: string;
   /** The amount of ore involved in this batch. */
   amount: number;
   /** Current progress from 0.0 to 1.0. */
@@ -60,6 +62,10 @@ export interface FactoryResources {
   ice: number;
   /** Liquid credits available. */
   credits: number;
+  /** Alloys refined from metals and crystals (Rust engine only). */
+  alloys?: number;
+  /** Coolant refined from ice (Rust engine only). */
+  coolant?: number;
 }
 
 /**
@@ -76,6 +82,10 @@ export interface FactoryUpgrades {
   energy: number;
   /** Level of solar array upgrade (passive generation). */
   solar: number;
+  /** Level of unload rate upgrade (Rust engine: faster cargo hand-over at the dock). */
+  unload?: number;
+  /** Level of cooler upgrade (Rust engine: sheds refinery heat faster). */
+  cooler?: number;
 }
 
 /**
@@ -130,7 +140,7 @@ export interface BuildableFactory {
     /** Allowed resources for transport. */
     resourceFilters: string[];
     /** Logic mode for hauling. */
-    mode: 'auto' | 'manual' | 'demand-first' | 'supply-first';
+    mode: 'auto' | 'manual' | 'demand-first' | 'supply-first' | 'export-only' | 'import-only' | 'warehouse-only';
     /** Priority level for scheduling. */
     priority: number;
   };
@@ -154,6 +164,10 @@ export interface BuildableFactory {
       amount: number;
       eta: number;
     }>;
+    /** Haulers carrying a transfer or flying home; Rust sim only. */
+    haulersBusy?: number;
+    /** `haulersBusy` over assigned haulers, 0 to 1; Rust sim only. */
+    haulerUtilization?: number;
   };
 }
 
@@ -45,6 +45,9 @@ const toDroneState = (value: number): DroneEntity['state'] => {
       return 'returning';
     case 4:
       return 'unloading';
+    // Hull integrity ran out: parked at its factory until repaired.
+    case 5:
+      return 'disabled';
     case 0:
     default:
       return 'idle';
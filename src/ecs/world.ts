@@ -11,7 +11,7 @@ import type { ResourceKey, ResourceWeights } from '@/lib/biomes';
 import { storeApi, type ModuleId } from '@/state/store';
 
 /** Represents the current activity state of a drone. */
-export type DroneState = 'idle' | 'toAsteroid' | 'mining' | 'returning' | 'unloading';
+export type DroneState = 'idle' | 'toAsteroid' | 'mining' | 'returning' | 'unloading' | 'disabled';
 
 /**
  * Data describing an entity's travel path.
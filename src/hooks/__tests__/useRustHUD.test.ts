@@ -5,6 +5,12 @@ import { registerBridge, unregisterBridge } from '@/lib/rustBridgeRegistry';
 import type { RustSimBridge } from '@/lib/wasmSimBridge';
 import { vi, describe, it, expect, beforeEach, afterEach } from 'vitest';
 
+const sectionComponents: Record<string, string[]> = {
+  'globals.resources': ['ore', 'ice', 'metals', 'crystals', 'organics', 'bars', 'energy', 'credits'],
+  'factories.resources': ['ore', 'ice', 'metals', 'crystals', 'organics', 'bars', 'credits', 'alloys', 'coolant'],
+};
+const getSection = (name: string) => ({ components: sectionComponents[name] ?? [] });
+
 describe('useRustHUD', () => {
   beforeEach(() => {
     useStore.setState({
@@ -39,7 +45,8 @@ describe('useRustHUD', () => {
     const mockBridge = {
       isReady: () => true,
       getGlobalResources: () => new Float32Array([10, 20, 30, 40, 50, 60, 70, 80]),
-      getFactoryResources: () => new Float32Array(7),
+      getSection,
+      getFactoryResources: () => new Float32Array(9),
       getFactoryEnergy: () => new Float32Array(1),
       getFactoryHaulersAssigned: () => new Float32Array(1),
     } as unknown as RustSimBridge;
@@ -65,9 +72,10 @@ describe('useRustHUD', () => {
     const mockBridge = {
       isReady: () => true,
       getGlobalResources: () => new Float32Array(8),
+      getSection,
       getFactoryResources: (idx: number) => {
-        if (idx === 0) return new Float32Array([1, 2, 3, 4, 5, 6, 7]);
-        return new Float32Array(7);
+        if (idx === 0) return new Float32Array([1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        return new Float32Array(9);
       },
       getFactoryEnergy: (_idx: number) => new Float32Array([100]),
       getFactoryHaulersAssigned: (_idx: number) => new Float32Array([5]),
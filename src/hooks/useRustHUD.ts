@@ -38,17 +38,18 @@ export function useRustHUD(): RustHUDState {
             try {
                 // Check if buffer is valid
                 const raw = bridge.getGlobalResources();
-                // raw: ore, ice, metals, crystals, organics, bars, energy, credits
-                if (raw.length === 8) {
+                const components = bridge.getSection('globals.resources').components;
+                if (raw.length === components.length) {
+                  const value = (name: string) => raw[components.indexOf(name)] ?? 0;
                   setHudResources({
-                      ore: raw[0],
-                      ice: raw[1],
-                      metals: raw[2],
-                      crystals: raw[3],
-                      organics: raw[4],
-                      bars: raw[5],
-                      energy: raw[6],
-                      credits: raw[7],
+                      ore: value('ore'),
+                      ice: value('ice'),
+                      metals: value('metals'),
+                      crystals: value('crystals'),
+                      organics: value('organics'),
+                      bars: value('bars'),
+                      energy: value('energy'),
+                      credits: value('credits'),
                   });
                   setIsActive(true);
                 } else {
@@ -76,18 +77,20 @@ export function useRustHUD(): RustHUDState {
 
       try {
           const res = bridge.getFactoryResources(index);
+          const components = bridge.getSection('factories.resources').components;
+          const value = (name: string) => res[components.indexOf(name)] ?? 0;
           const energy = bridge.getFactoryEnergy(index)[0];
           const haulers = bridge.getFactoryHaulersAssigned(index)[0];
 
           return {
               resources: {
-                  ore: res[0],
-                  ice: res[1],
-                  metals: res[2],
-                  crystals: res[3],
-                  organics: res[4],
-                  bars: res[5],
-                  credits: res[6],
+                  ore: value('ore'),
+                  ice: value('ice'),
+                  metals: value('metals'),
+                  crystals: value('crystals'),
+                  organics: value('organics'),
+                  bars: value('bars'),
+                  credits: value('credits'),
               },
               energy,
               haulersAssigned: haulers
@@ -35,6 +35,8 @@ export interface FactorySimData {
     organics: number;
     bars: number;
     credits: number;
+    alloys: number;
+    coolant: number;
   };
 }
 
@@ -113,24 +115,32 @@ export function useSimulationData(bridge: RustSimBridge | null): SimulationDataS
           const maxEnergy = bridge.getFactoryMaxEnergy();
           const resources = bridge.getFactoryResources();
 
+          const { stride, components } = bridge.getSection('factories.resources');
+
           const factoryCount = energy.length;
           const result: FactorySimData[] = [];
 
           for (let i = 0; i < factoryCount; i++) {
-            const resBase = i * 7;
+            const resBase = i * stride;
+            const resource = (name: string) => {
+              const offset = components.indexOf(name);
+              return offset >= 0 ? resources[resBase + offset] : 0;
+            };
             result.push({
               id: factories[i]?.id ?? `factory-${i}`,
               position: [positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]],
               energy: energy[i],
               maxEnergy: maxEnergy[i],
               resources: {
-                ore: resources[resBase],
-                ice: resources[resBase + 1],
-                metals: resources[resBase + 2],
-                crystals: resources[resBase + 3],
-                organics: resources[resBase + 4],
-                bars: resources[resBase + 5],
-                credits: resources[resBase + 6],
+                ore: resource('ore'),
+                ice: resource('ice'),
+                metals: resource('metals'),
+                crystals: resource('crystals'),
+                organics: resource('organics'),
+                bars: resource('bars'),
+                credits: resource('credits'),
+                alloys: resource('alloys'),
+                coolant: resource('coolant'),
               },
             });
           }
@@ -147,7 +157,7 @@ export function useSimulationData(bridge: RustSimBridge | null): SimulationDataS
         return gameWorld.droneQuery.entities.map((entity) => ({
           id: entity.id,
           position: [entity.position.x, entity.position.y, entity.position.z],
-          state: ['idle', 'toAsteroid', 'mining', 'returning', 'unloading'].indexOf(entity.state),
+          state: ['idle', 'toAsteroid', 'mining', 'returning', 'unloading', 'disabled'].indexOf(entity.state),
           cargo: entity.cargo ?? 0,
           battery: entity.battery ?? 100,
           maxBattery: entity.maxBattery ?? 100,
@@ -180,6 +190,8 @@ export function useSimulationData(bridge: RustSimBridge | null): SimulationDataS
             organics: factory.resources.organics,
             bars: factory.resources.bars,
             credits: factory.resources.credits,
+            alloys: factory.resources.alloys ?? 0,
+            coolant: factory.resources.coolant ?? 0,
           },
         }));
       },
@@ -29,16 +29,16 @@ export function checkParity(
 
   const tsFactories = tsState.factories;
   const rustFactoryResources = rustBridge.getFactoryResources();
+  const { stride, components } = rustBridge.getSection('factories.resources');
 
-  // 7 resources per factory: ore, bars, metals, crystals, organics, ice, credits
-  if (tsFactories.length * 7 !== rustFactoryResources.length) {
+  if (tsFactories.length * stride !== rustFactoryResources.length) {
      divergences.push(`Factory count mismatch (resources buffer size)`);
   } else {
     // Compare first factory ore
     if (tsFactories.length > 0) {
        const f = tsFactories[0];
        const tsOre = f.resources.ore;
-       const rustOre = rustFactoryResources[0]; // ore is first float
+       const rustOre = rustFactoryResources[components.indexOf('ore')];
 
        if (Math.abs(tsOre - rustOre) > EPSILON) {
          divergences.push(`Factory 0 Ore mismatch: TS=${tsOre.toFixed(3)}, Rust=${rustOre.toFixed(3)}`);
@@ -39,6 +39,7 @@ export const buildRustSnapshotFromTs = (): StoreSnapshot & Record<string, unknow
       position: [entity.position.x, entity.position.y, entity.position.z],
       oreRemaining: entity.oreRemaining,
       maxOre,
+      radius: entity.radius,
       gravityMultiplier: entity.gravityMultiplier,
       resourceProfile: entity.resourceProfile ?? {
         ore: 1,
@@ -56,6 +57,13 @@ export const buildRustSnapshotFromTs = (): StoreSnapshot & Record<string, unknow
             hazard: region.hazard,
           }))
         : null,
+      biome: {
+        biomeId: entity.biome.biomeId,
+        fractureTimer: entity.biome.fractureTimer,
+        fractureSeed: entity.biome.fractureSeed,
+        fractureCount: entity.biome.fractureCount,
+        hazard: entity.biome.hazard,
+      },
     };
   });
 
@@ -67,9 +67,15 @@ vi.mock('../gen/rust_engine', () => {
         total_size_bytes: 220,
       });
     }
+    layout_descriptor_json(): string {
+      return JSON.stringify({ version: 8, total_size_bytes: 220, sections: [] });
+    }
     data_ptr(): number {
       return 0;
     }
+    generation(): number {
+      return 0;
+    }
   }
 
   return {
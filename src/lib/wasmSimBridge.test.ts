@@ -0,0 +1,27 @@
+import { describe, it, expect } from 'vitest';
+import { parseLayoutDescriptor, RUST_LAYOUT_VERSION } from './wasmSimBridge';
+
+describe('parseLayoutDescriptor', () => {
+  const section = {
+    name: 'factories.resources',
+    offset_bytes: 0,
+    length: 9,
+    capacity: 18,
+    dtype: 'f32',
+    stride: 9,
+    components: ['ore', 'ice', 'metals', 'crystals', 'organics', 'bars', 'credits', 'alloys', 'coolant'],
+    units: 'resource_units',
+  };
+
+  it('returns the sections of a matching layout', () => {
+    const json = JSON.stringify({ version: RUST_LAYOUT_VERSION, total_size_bytes: 72, sections: [section] });
+    const descriptor = parseLayoutDescriptor(json);
+    expect(descriptor.sections[0].stride).toBe(9);
+    expect(descriptor.sections[0].components.indexOf('alloys')).toBe(7);
+  });
+
+  it('rejects a layout from a different engine version', () => {
+    const json = JSON.stringify({ version: RUST_LAYOUT_VERSION + 1, total_size_bytes: 72, sections: [section] });
+    expect(() => parseLayoutDescriptor(json)).toThrow(/layout version/);
+  });
+});
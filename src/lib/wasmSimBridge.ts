@@ -1,4 +1,7 @@
-import type { StoreSnapshot, LogisticsQueues } from '../state/types';
+import type { StoreSnapshot, LogisticsQueues, HaulerConfig } from '../state/types';
+
+/** Layout version this bridge understands; must match `LAYOUT_VERSION` in the engine. */
+export const RUST_LAYOUT_VERSION = 8;
 
 export interface BufferSection {
   offset_bytes: number;
@@ -20,6 +23,8 @@ export interface DroneBuffers {
   target_asteroid_index: BufferSection;
   target_region_index: BufferSection;
   charging: BufferSection;
+  speed: BufferSection;
+  integrity: BufferSection;
 }
 
 export interface AsteroidBuffers {
@@ -38,17 +43,61 @@ export interface FactoryBuffers {
   max_energy: BufferSection;
   upgrades: BufferSection;
   refinery_state: BufferSection;
+  refine_recipes: BufferSection;
   haulers_assigned: BufferSection;
+  heat: BufferSection;
+  cooling: BufferSection;
+}
+
+/** One hauler per scheduled transfer, in `pendingTransfers` order. */
+export interface HaulerBuffers {
+  positions: BufferSection;
+  velocities: BufferSection;
+  cargo: BufferSection;
+  /** From/to factory indices; -1 is the warehouse. */
+  route: BufferSection;
 }
 
 export interface GlobalBuffers {
   resources: BufferSection;
 }
 
+/** Metadata for one buffer section, from `layout_descriptor_json`. */
+export interface RustSectionDescriptor {
+  name: string;
+  offset_bytes: number;
+  length: number;
+  capacity: number;
+  dtype: string;
+  /** Values per entity. */
+  stride: number;
+  /** Name of each value within an entity's stride. */
+  components: string[];
+  units: string;
+}
+
+export interface RustLayoutDescriptor {
+  version: number;
+  total_size_bytes: number;
+  sections: RustSectionDescriptor[];
+}
+
+/** Parses the engine's layout descriptor, rejecting layouts this bridge wasn't built for. */
+export function parseLayoutDescriptor(json: string): RustLayoutDescriptor {
+  const descriptor = JSON.parse(json) as RustLayoutDescriptor;
+  if (descriptor.version !== RUST_LAYOUT_VERSION) {
+    throw new Error(
+      `Rust layout version ${descriptor.version} does not match bridge version ${RUST_LAYOUT_VERSION}`
+    );
+  }
+  return descriptor;
+}
+
 export interface RustSimLayout {
   drones: DroneBuffers;
   asteroids: AsteroidBuffers;
   factories: FactoryBuffers;
+  haulers: HaulerBuffers;
   globals: GlobalBuffers;
   total_size_bytes: number;
 }
@@ -65,20 +114,165 @@ export interface OfflineResult {
   snapshotJson: string;
 }
 
+/** What an engine-side prestige reset converted and rebuilt. */
+export interface PrestigeSummary {
+  bars_converted: number;
+  cores_gained: number;
+  total_cores: number;
+  factories_reset: number;
+  drones_reset: number;
+  asteroids_generated: number;
+  run_time: number;
+  lifetime_prestiges: number;
+}
+
 export type SimulationCommand =
   | { type: 'UpdateResources'; payload: StoreSnapshot['resources'] }
   | { type: 'UpdateModules'; payload: StoreSnapshot['modules'] }
   | { type: 'SetSettings'; payload: Partial<StoreSnapshot['settings']> }
-  | { type: 'BuyModule'; payload: { moduleType: string; factoryId?: string } }
+  | {
+      type: 'BuyModule';
+      payload: { moduleType: string; factoryId?: string; costVariant?: 'credits' };
+    }
   | { type: 'DoPrestige'; payload?: undefined }
   | {
       type: 'PurchaseFactoryUpgrade';
       payload: { factoryId: string; upgradeType: string; costVariant?: string };
     }
   | { type: 'AssignHauler'; payload: { factoryId: string; count: number } }
+  | {
+      type: 'SetHaulerConfig';
+      payload: {
+        factoryId: string;
+        resourceFilters?: string[];
+        mode?: HaulerConfig['mode'];
+        priority?: number;
+      };
+    }
   | { type: 'ImportPayload'; payload: { snapshotJson: string } }
   | { type: 'SpawnDrone'; payload: { factoryId: string } }
-  | { type: 'RecycleAsteroid'; payload: { asteroidId: string } };
+  | { type: 'RecycleAsteroid'; payload: { asteroidId: string } }
+  | { type: 'BuildFactory'; payload?: undefined }
+  | { type: 'DecommissionFactory'; payload: { factoryId: string } }
+  | { type: 'PurchaseSpecTech'; payload: { techId: string } }
+  | { type: 'InvestPrestige'; payload: { investmentId: string } }
+  | { type: 'EquipDrone'; payload: { droneId: string; slot: DroneEquipmentSlot; tier: number } }
+  | { type: 'UnequipDrone'; payload: { droneId: string; slot: DroneEquipmentSlot } }
+  | { type: 'OrderDrone'; payload: { droneId: string; order: DroneOrder } }
+  | { type: 'ClearDroneOrder'; payload: { droneId: string } }
+  | { type: 'SetRefineryOverdrive'; payload: { factoryId: string; enabled: boolean } }
+  | { type: 'SetRefineRecipe'; payload: { factoryId: string; slot: number; recipe: string } }
+  | { type: 'Sell'; payload: { resource: string; amount: number; factoryId?: string } }
+  | { type: 'Buy'; payload: { resource: string; amount: number } }
+  | { type: 'SetAutoSell'; payload: { resource: string; reserve: number | null } }
+  | { type: 'AcceptContract'; payload: { contractId: string } }
+  | { type: 'AbandonContract'; payload: { contractId: string } }
+  | { type: 'StartResearch'; payload: { nodeId: string } }
+  | { type: 'FundResearch'; payload: { crystals: number } }
+  | { type: 'ReassignDrone'; payload: { droneId: string; factoryId: string } }
+  | { type: 'UnassignDrone'; payload: { droneId: string } }
+  | {
+      type: 'RebalanceDrones';
+      payload: { fromFactoryId: string; toFactoryId: string; count: number };
+    };
+
+/** Equipment slots a drone can fit; each holds one item tier. */
+export type DroneEquipmentSlot = 'tool' | 'engine' | 'hull' | 'aiModule';
+
+/** Manual override on a drone's automatic targeting, persisted per drone. */
+export type DroneOrder =
+  | { kind: 'goTo'; asteroidId: string; regionId?: string | null }
+  | { kind: 'recall'; factoryId: string }
+  | { kind: 'hold' }
+  | { kind: 'pin'; asteroidId: string };
+
+/** Emitted when an order completes, loses its target, is replaced or is cleared. */
+export interface DroneOrderEvent {
+  droneId: string;
+  kind: DroneOrder['kind'];
+  reason: 'completed' | 'targetLost' | 'replaced' | 'cleared';
+  gameTime: number;
+}
+
+/** One factory's busy haulers and their share of its assigned haulers. */
+export interface HaulerUsage {
+  busy: number;
+  /** 0 to 1; 0 with no haulers assigned. */
+  utilization: number;
+}
+
+/** Equipment tiers fitted to one drone; 0 leaves a slot empty. */
+export interface DroneLoadout {
+  tool: number;
+  engine: number;
+  hull: number;
+  aiModule: number;
+}
+
+/** Hazard cycle on an asteroid, or on one region of a fractured asteroid. */
+export interface AsteroidHazard {
+  asteroidId: string;
+  regionId?: string;
+  kind: string;
+  severity: string;
+  active: boolean;
+  /** Seconds left in the current phase. */
+  remaining: number;
+}
+
+/** Emitted when an asteroid hazard flares up or dies down. */
+export interface HazardEvent {
+  asteroidId: string;
+  kind: string;
+  severity: string;
+  phase: 'started' | 'ended';
+  gameTime: number;
+}
+
+/** Market state carried in the Rust snapshot under `market`. */
+export interface MarketSnapshot {
+  /** Credits per unit; goods missing here trade at their base price. */
+  prices: Record<string, number>;
+  /** Standing auto-sell orders: resource to the warehouse reserve kept back from sale. */
+  autoSell: Record<string, number>;
+}
+
+/** A delivery contract, either on offer or accepted. */
+export interface ContractSnapshot {
+  id: string;
+  resource: string;
+  amount: number;
+  delivered: number;
+  /** Seconds allowed once accepted. */
+  duration: number;
+  /** Game time the offer lapses or, once accepted, the delivery is due. */
+  deadline: number;
+  reward: { bars: number; credits: number; cores: number };
+}
+
+/** Contract board carried in the Rust snapshot under `contracts`. */
+export interface ContractsSnapshot {
+  offers: ContractSnapshot[];
+  active: ContractSnapshot[];
+  nextOfferAt: number;
+  nextId: number;
+}
+
+/** Emitted when an accepted contract leaves the books. */
+export interface ContractEvent {
+  contractId: string;
+  resource: string;
+  outcome: 'completed' | 'expired' | 'abandoned';
+  gameTime: number;
+}
+
+/** Research state carried in the Rust snapshot under `research`. */
+export interface ResearchSnapshot {
+  points: number;
+  /** Ids of finished research nodes. */
+  completed: string[];
+  active: { id: string; progress: number } | null;
+}
 
 // Interface for the wasm-bindgen generated module exports
 export interface WasmSimExports {
@@ -93,11 +287,21 @@ export interface WasmGameState {
   get_logistics_queues(): string;
   step(dt: number): number;
   apply_command(command_json: string): void;
+  prestige(): string;
+  generate_asteroid_field(seed: number): number;
   simulate_offline(seconds: number, step: number): string;
   layout_json(): string;
+  layout_descriptor_json(): string;
   data_ptr(): number;
+  generation(): number;
   drone_ids_json(): string;
   asteroid_ids_json(): string;
+  hauler_transfer_ids_json(): string;
+  market_prices_json(): string;
+  hauler_usage_json(): string;
+  drain_hazard_events_json(): string;
+  drain_order_events_json(): string;
+  drain_contract_events_json(): string;
 }
 
 /**
@@ -114,14 +318,30 @@ export interface RustSimBridge {
   step(dt: number): TickResult;
   applyCommand(cmd: SimulationCommand): void;
   simulateOffline(seconds: number, stepSize: number): OfflineResult;
+  /** Runs the engine's prestige reset; null when the run doesn't qualify. */
+  prestige(): PrestigeSummary | null;
+  /** Reseeds the engine and builds the initial asteroid field; returns the asteroid count. */
+  generateAsteroidField(seed: number): number;
 
   // Snapshots
   exportSnapshot(): StoreSnapshot;
   loadSnapshot(snapshot: StoreSnapshot): void;
   getLogisticsQueues(): LogisticsQueues;
+  /** Current price of every market good, including goods still at their base price. */
+  getMarketPrices(): Record<string, number>;
+  /** Hauler usage keyed by factory id, for factories the logistics system has counted. */
+  getHaulerUsage(): Record<string, HaulerUsage>;
+
+  // Events raised since the last drain
+  drainHazardEvents(): HazardEvent[];
+  drainOrderEvents(): DroneOrderEvent[];
+  drainContractEvents(): ContractEvent[];
 
   // Layout
   getLayout(): RustSimLayout;
+  getLayoutDescriptor(): RustLayoutDescriptor;
+  /** Descriptor for a section such as `factories.resources`; throws if the engine has none. */
+  getSection(name: string): RustSectionDescriptor;
 
   // Stable entity id ordering
   getDroneIds(): string[];
@@ -142,6 +362,8 @@ export interface RustSimBridge {
   getDroneTargetAsteroidIndex(): Float32Array;
   getDroneTargetRegionIndex(): Float32Array;
   getDroneCharging(): Float32Array;
+  getDroneSpeed(): Float32Array;
+  getDroneIntegrity(): Float32Array;
 
   // Asteroid buffer accessors
   getAsteroidPositions(): Float32Array;
@@ -155,13 +377,24 @@ export interface RustSimBridge {
   // Factory buffer accessors
   getFactoryPositions(): Float32Array;
   getFactoryOrientations(): Float32Array;
-  getFactoryActivity(): Uint32Array;
+  getFactoryActivity(): Float32Array;
   getFactoryResources(index?: number): Float32Array;
   getFactoryEnergy(index?: number): Float32Array;
   getFactoryMaxEnergy(): Float32Array;
   getFactoryUpgrades(): Float32Array;
   getFactoryRefineryState(): Float32Array;
+  getFactoryRefineRecipes(): Float32Array;
   getFactoryHaulersAssigned(index?: number): Float32Array;
+  getFactoryHeat(): Float32Array;
+  getFactoryCooling(): Float32Array;
+
+  // Hauler buffer accessors
+  getHaulerPositions(): Float32Array;
+  getHaulerVelocities(): Float32Array;
+  getHaulerCargo(): Float32Array;
+  getHaulerRoutes(): Float32Array;
+  /** Transfer id behind each hauler slot, in buffer order. */
+  getHaulerTransferIds(): string[];
 }
 
 export function buildRustSimBridge(
@@ -171,8 +404,20 @@ export function buildRustSimBridge(
   const json = JSON.stringify(snapshot);
   let gameState: WasmGameState | null = new wasmExports.WasmGameState(json);
   let layout: RustSimLayout = JSON.parse(gameState.layout_json()) as RustSimLayout;
+  let descriptor = parseLayoutDescriptor(gameState.layout_descriptor_json());
+  let layoutGeneration = gameState.generation();
   let gameTime = snapshot.gameTime ?? 0;
 
+  /** Re-reads the layout when the engine reports it changed (growth, added or removed entities). */
+  const refreshLayout = (force = false) => {
+    if (!gameState) return;
+    const generation = gameState.generation();
+    if (!force && generation === layoutGeneration) return;
+    layout = JSON.parse(gameState.layout_json()) as RustSimLayout;
+    descriptor = parseLayoutDescriptor(gameState.layout_descriptor_json());
+    layoutGeneration = generation;
+  };
+
   const getViewF32 = (section: BufferSection) => {
     if (!gameState) throw new Error('Game state not initialized');
     const ptr = gameState.data_ptr();
@@ -183,14 +428,10 @@ export function buildRustSimBridge(
     );
   };
 
-  const getViewU32 = (section: BufferSection) => {
-    if (!gameState) throw new Error('Game state not initialized');
-    const ptr = gameState.data_ptr();
-    return new Uint32Array(
-      wasmExports.memory.buffer,
-      ptr + section.offset_bytes,
-      section.length
-    );
+  const findSection = (name: string) => {
+    const section = descriptor.sections.find((entry) => entry.name === name);
+    if (!section) throw new Error(`Rust layout has no section ${name}`);
+    return section;
   };
 
   return {
@@ -201,7 +442,7 @@ export function buildRustSimBridge(
       }
       const snapshotJson = JSON.stringify(newSnapshot);
       gameState = new wasmExports.WasmGameState(snapshotJson);
-      layout = JSON.parse(gameState.layout_json()) as RustSimLayout;
+      refreshLayout(true);
       gameTime = newSnapshot.gameTime ?? 0;
     },
 
@@ -221,6 +462,8 @@ export function buildRustSimBridge(
       if (!gameState) throw new Error('Game state not initialized');
       const returnedGameTime = gameState.step(dt);
       gameTime = returnedGameTime;
+      // Hauler slots come and go as transfers are scheduled and land.
+      refreshLayout();
       return { dt, gameTime, rngSample: returnedGameTime };
     },
 
@@ -228,6 +471,16 @@ export function buildRustSimBridge(
       if (!gameState) throw new Error('Game state not initialized');
       const commandJson = JSON.stringify(cmd);
       gameState.apply_command(commandJson);
+      // Commands can add or remove drones, factories and asteroids, or grow the buffer.
+      refreshLayout();
+    },
+
+    generateAsteroidField(seed: number): number {
+      if (!gameState) throw new Error('Game state not initialized');
+      const count = gameState.generate_asteroid_field(seed);
+      // New asteroids may have grown the buffer.
+      refreshLayout();
+      return count;
     },
 
     simulateOffline(seconds: number, stepSize: number): OfflineResult {
@@ -238,6 +491,7 @@ export function buildRustSimBridge(
           ? (JSON.parse(raw) as OfflineResult)
           : (raw as OfflineResult);
       gameTime += parsed.elapsed ?? 0;
+      refreshLayout();
       return {
         elapsed: parsed.elapsed ?? 0,
         steps: parsed.steps ?? 0,
@@ -245,6 +499,14 @@ export function buildRustSimBridge(
       };
     },
 
+    prestige(): PrestigeSummary | null {
+      if (!gameState) throw new Error('Game state not initialized');
+      const summary = JSON.parse(gameState.prestige()) as PrestigeSummary | null;
+      // The reset rebuilds factories, drones and the asteroid field.
+      refreshLayout();
+      return summary;
+    },
+
     // Snapshots
     exportSnapshot(): StoreSnapshot {
       if (!gameState) throw new Error('Game state not initialized');
@@ -258,11 +520,36 @@ export function buildRustSimBridge(
       return JSON.parse(json) as LogisticsQueues;
     },
 
+    getMarketPrices() {
+      if (!gameState) throw new Error('Game state not initialized');
+      return JSON.parse(gameState.market_prices_json()) as Record<string, number>;
+    },
+
+    getHaulerUsage() {
+      if (!gameState) throw new Error('Game state not initialized');
+      return JSON.parse(gameState.hauler_usage_json()) as Record<string, HaulerUsage>;
+    },
+
+    drainHazardEvents() {
+      if (!gameState) throw new Error('Game state not initialized');
+      return JSON.parse(gameState.drain_hazard_events_json()) as HazardEvent[];
+    },
+
+    drainOrderEvents() {
+      if (!gameState) throw new Error('Game state not initialized');
+      return JSON.parse(gameState.drain_order_events_json()) as DroneOrderEvent[];
+    },
+
+    drainContractEvents() {
+      if (!gameState) throw new Error('Game state not initialized');
+      return JSON.parse(gameState.drain_contract_events_json()) as ContractEvent[];
+    },
+
     loadSnapshot(newSnapshot: StoreSnapshot): void {
       if (!gameState) throw new Error('Game state not initialized');
       const snapshotJson = JSON.stringify(newSnapshot);
       gameState.load_snapshot(snapshotJson);
-      layout = JSON.parse(gameState.layout_json()) as RustSimLayout;
+      refreshLayout(true);
       gameTime = newSnapshot.gameTime ?? 0;
     },
 
@@ -271,6 +558,14 @@ export function buildRustSimBridge(
       return layout;
     },
 
+    getLayoutDescriptor() {
+      return descriptor;
+    },
+
+    getSection(name: string) {
+      return findSection(name);
+    },
+
     getDroneIds(): string[] {
       if (!gameState) throw new Error('Game state not initialized');
       return JSON.parse(gameState.drone_ids_json()) as string[];
@@ -338,6 +633,14 @@ export function buildRustSimBridge(
       return getViewF32(layout.drones.charging);
     },
 
+    getDroneSpeed() {
+      return getViewF32(layout.drones.speed);
+    },
+
+    getDroneIntegrity() {
+      return getViewF32(layout.drones.integrity);
+    },
+
     // Asteroid buffer accessors
     getAsteroidPositions() {
       return getViewF32(layout.asteroids.positions);
@@ -370,13 +673,14 @@ export function buildRustSimBridge(
     },
 
     getFactoryActivity() {
-      return getViewU32(layout.factories.activity);
+      return getViewF32(layout.factories.activity);
     },
 
     getFactoryResources(index?: number) {
       const view = getViewF32(layout.factories.resources);
       if (index !== undefined) {
-        return view.subarray(index * 7, (index + 1) * 7);
+        const { stride } = findSection('factories.resources');
+        return view.subarray(index * stride, (index + 1) * stride);
       }
       return view;
     },
@@ -401,6 +705,10 @@ export function buildRustSimBridge(
       return getViewF32(layout.factories.refinery_state);
     },
 
+    getFactoryRefineRecipes() {
+      return getViewF32(layout.factories.refine_recipes);
+    },
+
     getFactoryHaulersAssigned(index?: number) {
       const view = getViewF32(layout.factories.haulers_assigned);
       if (index !== undefined) {
@@ -408,5 +716,34 @@ export function buildRustSimBridge(
       }
       return view;
     },
+
+    getFactoryHeat() {
+      return getViewF32(layout.factories.heat);
+    },
+
+    getFactoryCooling() {
+      return getViewF32(layout.factories.cooling);
+    },
+
+    getHaulerPositions() {
+      return getViewF32(layout.haulers.positions);
+    },
+
+    getHaulerVelocities() {
+      return getViewF32(layout.haulers.velocities);
+    },
+
+    getHaulerCargo() {
+      return getViewF32(layout.haulers.cargo);
+    },
+
+    getHaulerRoutes() {
+      return getViewF32(layout.haulers.route);
+    },
+
+    getHaulerTransferIds() {
+      if (!gameState) throw new Error('Game state not initialized');
+      return JSON.parse(gameState.hauler_transfer_ids_json()) as string[];
+    },
   };
 }
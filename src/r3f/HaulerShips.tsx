@@ -18,6 +18,7 @@ import { WAREHOUSE_NODE_ID } from '@/ecs/logistics';
 import { WAREHOUSE_POSITION } from '@/ecs/world';
 import { RESOURCE_COLORS } from '@/r3f/resourceColors';
 import type { BuildableFactory } from '@/ecs/factories';
+import type { RustSimBridge } from '@/lib/wasmSimBridge';
 
 type HaulerStatus = 'scheduled' | 'in-transit';
 
@@ -555,6 +556,217 @@ export const HaulerShips = () => {
   );
 };
 
+const routeLabel = (factories: BuildableFactory[], routeIndex: number) =>
+  routeIndex < 0 ? 'Whse' : factories[routeIndex]?.id.slice(0, 6) ?? '?';
+
+interface RustHaulerShipsProps {
+  bridge: RustSimBridge;
+}
+
+interface RustHaulerHover {
+  index: number;
+  position: [number, number, number];
+  sourceLabel: string;
+  destLabel: string;
+  cargo: number;
+  resource: string;
+  speed: number;
+}
+
+/**
+ * Hauler renderer that reads positions, velocities, cargo and routes from the Rust hauler
+ * buffers. The resource of each hauler comes from the store's copy of its transfer, matched
+ * through the buffer's transfer ids.
+ */
+export const RustHaulerShips = ({ bridge }: RustHaulerShipsProps) => {
+  const logisticsQueues = useStore((state) => state.logisticsQueues);
+  const factories = useStore((state) => state.factories);
+  const hullRef = useRef<InstancedMesh>(null);
+  const noseRef = useRef<InstancedMesh>(null);
+  const engineRef = useRef<InstancedMesh>(null);
+  const hoveredIndexRef = useRef<number | null>(null);
+  const [hovered, setHovered] = useState<RustHaulerHover | null>(null);
+  const resourceById = useMemo(() => {
+    const map = new Map<string, string>();
+    for (const transfer of logisticsQueues.pendingTransfers ?? []) {
+      map.set(transfer.id, transfer.resource);
+    }
+    return map;
+  }, [logisticsQueues.pendingTransfers]);
+
+  useFrame(() => {
+    const hull = hullRef.current;
+    const nose = noseRef.current;
+    const engine = engineRef.current;
+    if (!hull || !nose || !engine) return;
+
+    const positions = bridge.getHaulerPositions();
+    const velocities = bridge.getHaulerVelocities();
+    const cargo = bridge.getHaulerCargo();
+    const routes = bridge.getHaulerRoutes();
+    const count = Math.min(cargo.length, MAX_HAULERS);
+    const transferIds = count > 0 ? bridge.getHaulerTransferIds() : [];
+    let hoveredFound = false;
+
+    for (let i = 0; i < count; i += 1) {
+      position
+        .set(positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2])
+        .addScaledVector(upVector, HEIGHT_OFFSET);
+      tangent.set(velocities[i * 3], velocities[i * 3 + 1], velocities[i * 3 + 2]);
+      const speed = tangent.length();
+      if (speed < 1e-4) {
+        tangent.copy(fallbackDirection);
+      } else {
+        tangent.divideScalar(speed);
+      }
+      orientation.setFromUnitVectors(BASE_FORWARD, tangent);
+      baseMatrix.compose(position, orientation, HULL_SCALE);
+      hull.setMatrixAt(i, baseMatrix);
+      nose.setMatrixAt(i, baseMatrix);
+
+      enginePosition.copy(position).addScaledVector(tangent, -0.35);
+      engineOrientation.copy(orientation).multiply(engineFlip);
+      engineMatrix.compose(enginePosition, engineOrientation, ENGINE_SCALE);
+      engine.setMatrixAt(i, engineMatrix);
+
+      const resource = resourceById.get(transferIds[i] ?? '') ?? '';
+      tempColor.set(RESOURCE_COLORS[resource] ?? '#ffffff');
+      if (hoveredIndexRef.current === i) {
+        tempColor.lerp(WHITE, 0.35);
+        hoveredFound = true;
+        const next: RustHaulerHover = {
+          index: i,
+          position: [position.x, position.y + 0.35, position.z],
+          sourceLabel: routeLabel(factories, routes[i * 2]),
+          destLabel: routeLabel(factories, routes[i * 2 + 1]),
+          cargo: cargo[i],
+          resource,
+          speed,
+        };
+        setHovered((prev) =>
+          prev &&
+          prev.index === next.index &&
+          prev.cargo === next.cargo &&
+          Math.abs(prev.position[0] - next.position[0]) <= 1e-3 &&
+          Math.abs(prev.position[1] - next.position[1]) <= 1e-3 &&
+          Math.abs(prev.position[2] - next.position[2]) <= 1e-3
+            ? prev
+            : next,
+        );
+      }
+      hull.setColorAt(i, tempColor);
+      nose.setColorAt(i, tempColor);
+      engineColor.copy(tempColor).lerp(WHITE, 0.45).multiplyScalar(1.05);
+      engine.setColorAt(i, engineColor);
+    }
+
+    hull.count = count;
+    nose.count = count;
+    engine.count = count;
+    hull.instanceMatrix.needsUpdate = true;
+    nose.instanceMatrix.needsUpdate = true;
+    engine.instanceMatrix.needsUpdate = true;
+    if (hull.instanceColor) hull.instanceColor.needsUpdate = true;
+    if (nose.instanceColor) nose.instanceColor.needsUpdate = true;
+    if (engine.instanceColor) engine.instanceColor.needsUpdate = true;
+
+    if (!hoveredFound) {
+      setHovered((prev) => (prev ? null : prev));
+    }
+  });
+
+  const handlePointerMove = (event: ThreeEvent<PointerEvent>) => {
+    event.stopPropagation();
+    if (typeof event.instanceId !== 'number') return;
+    hoveredIndexRef.current = event.instanceId;
+  };
+
+  const handlePointerOut = (event: ThreeEvent<PointerEvent>) => {
+    event.stopPropagation();
+    hoveredIndexRef.current = null;
+  };
+
+  return (
+    <>
+      <instancedMesh
+        ref={hullRef}
+        args={[undefined as never, undefined as never, MAX_HAULERS]}
+        geometry={hullGeometry}
+        onPointerMove={handlePointerMove}
+        onPointerOver={handlePointerMove}
+        onPointerOut={handlePointerOut}
+      >
+        <meshStandardMaterial
+          vertexColors
+          emissiveIntensity={0.4}
+          roughness={0.35}
+          metalness={0.25}
+        />
+      </instancedMesh>
+      <instancedMesh
+        ref={noseRef}
+        args={[undefined as never, undefined as never, MAX_HAULERS]}
+        geometry={noseGeometry}
+      >
+        <meshStandardMaterial vertexColors emissiveIntensity={0.55} roughness={0.25} metalness={0.35} />
+      </instancedMesh>
+      <instancedMesh
+        ref={engineRef}
+        args={[undefined as never, undefined as never, MAX_HAULERS]}
+        geometry={engineGeometry}
+      >
+        {/* eslint-disable react/no-unknown-property */}
+        <meshBasicMaterial
+          vertexColors
+          transparent
+          opacity={0.55}
+          depthWrite={false}
+          blending={AdditiveBlending}
+        />
+        {/* eslint-enable react/no-unknown-property */}
+      </instancedMesh>
+      {hovered ? (
+        <Html
+          position={hovered.position}
+          center
+          style={{
+            padding: 0,
+            border: 'none',
+            background: 'transparent',
+            pointerEvents: 'none',
+          }}
+        >
+          <div
+            style={{
+              background: 'rgba(11, 16, 31, 0.92)',
+              color: '#f8fafc',
+              border: '1px solid rgba(96, 165, 250, 0.45)',
+              borderRadius: 8,
+              padding: '6px 10px',
+              fontSize: 12,
+              lineHeight: 1.4,
+              boxShadow: '0 6px 18px rgba(15, 23, 42, 0.55)',
+              minWidth: 140,
+              textAlign: 'center',
+              backdropFilter: 'blur(6px)',
+            }}
+          >
+            <div style={{ fontWeight: 600, marginBottom: 2 }}>
+              {hovered.sourceLabel} → {hovered.destLabel}
+            </div>
+            <div style={{ fontWeight: 600, marginBottom: 2 }}>
+              {Math.round(hovered.cargo)} {hovered.resource}
+            </div>
+            <div style={{ fontSize: 11, opacity: 0.85 }}>
+              Speed: {hovered.speed.toFixed(2)} u/s
+            </div>
+          </div>
+        </Html>
+      ) : null}
+    </>
+  );
+};
+
 export const computeHaulerProgress = (
   transfer: Pick<PendingTransfer, 'departedAt' | 'eta'>,
   time: number,
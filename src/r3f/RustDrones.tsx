@@ -15,13 +15,14 @@ const color = new Color();
 const position = new Vector3();
 
 // Map Rust float states to string states expected by colorForState
-// 0: Idle, 1: ToAsteroid, 2: Mining, 3: Returning, 4: Unloading
+// 0: Idle, 1: ToAsteroid, 2: Mining, 3: Returning, 4: Unloading, 5: Disabled
 const STATE_MAP: Record<number, string> = {
   0: 'idle',
   1: 'to-asteroid',
   2: 'mining',
   3: 'returning',
   4: 'unloading',
+  5: 'disabled',
 };
 
 interface RustDronesProps {
@@ -20,7 +20,7 @@ import { Asteroids } from '@/r3f/Asteroids';
 import { Drones } from '@/r3f/Drones';
 import { DroneTrails } from '@/r3f/DroneTrails';
 import { TransferLines } from '@/r3f/TransferLines';
-import { HaulerShips } from '@/r3f/HaulerShips';
+import { HaulerShips, RustHaulerShips } from '@/r3f/HaulerShips';
 import { Warehouse } from '@/r3f/Warehouse';
 import { useFactoryAutofit } from '@/hooks/useFactoryAutofit';
 import { useCameraReset } from '@/hooks/useCameraReset';
@@ -140,6 +140,12 @@ export const Scene = () => {
         bridge.step(step);
         if (useRustSim) {
           storeApi.setState((state) => ({ gameTime: state.gameTime + step }));
+          // Engine events queue up until drained, so drain them every tick.
+          storeApi.getState().recordEngineEvents([
+            ...bridge.drainHazardEvents().map((event) => ({ source: 'hazard' as const, ...event })),
+            ...bridge.drainOrderEvents().map((event) => ({ source: 'order' as const, ...event })),
+            ...bridge.drainContractEvents().map((event) => ({ source: 'contract' as const, ...event })),
+          ]);
 
           // Sync state from Rust bridge every 6 ticks (approx 100ms at 60Hz)
           if (frameCount.current % 6 === 0) {
@@ -148,16 +154,18 @@ export const Scene = () => {
               storeApi.getState().syncLogisticsQueues(queues);
 
               const rawResources = bridge.getGlobalResources();
-              if (rawResources.length === 8) {
+              const globalComponents = bridge.getSection('globals.resources').components;
+              if (rawResources.length === globalComponents.length) {
+                const globalResource = (name: string) => rawResources[globalComponents.indexOf(name)] ?? 0;
                 storeApi.getState().syncResources({
-                  ore: rawResources[0],
-                  ice: rawResources[1],
-                  metals: rawResources[2],
-                  crystals: rawResources[3],
-                  organics: rawResources[4],
-                  bars: rawResources[5],
-                  energy: rawResources[6],
-                  credits: rawResources[7],
+                  ore: globalResource('ore'),
+                  ice: globalResource('ice'),
+                  metals: globalResource('metals'),
+                  crystals: globalResource('crystals'),
+                  organics: globalResource('organics'),
+                  bars: globalResource('bars'),
+                  energy: globalResource('energy'),
+                  credits: globalResource('credits'),
                 });
               }
 
@@ -181,12 +189,14 @@ export const Scene = () => {
                 facHaulers = bridge.getFactoryHaulersAssigned();
               }
 
-              if (facResources && facResources.length >= 7) {
+              if (facResources && facResources.length > 0) {
                 storeApi.getState().syncFactoriesFromRust({
                   resources: facResources,
+                  resourceComponents: bridge.getSection('factories.resources').components,
                   energy: facEnergy,
                   maxEnergy: facMaxEnergy,
                   haulers: facHaulers,
+                  haulerUsage: bridge.getHaulerUsage(),
                 });
               }
 
@@ -307,7 +317,11 @@ export const Scene = () => {
 
         {rustDronesReady ? <RustDrones bridge={bridge} /> : <Drones />}
         {showTrails ? <DroneTrails /> : null}
-        {showHaulerShips ? <HaulerShips /> : <TransferLines />}
+        {showHaulerShips ? (
+          canUseRust && bridge ? <RustHaulerShips bridge={bridge} /> : <HaulerShips />
+        ) : (
+          <TransferLines />
+        )}
       </Suspense>
     </>
   );
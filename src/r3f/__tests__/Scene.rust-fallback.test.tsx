@@ -26,7 +26,10 @@ vi.mock('@/r3f/RustDrones', () => ({
 vi.mock('@/r3f/Factory', () => ({ Factory: () => <div>FACTORY</div> }));
 vi.mock('@/r3f/Warehouse', () => ({ Warehouse: () => <div>WAREHOUSE</div> }));
 vi.mock('@/r3f/DroneTrails', () => ({ DroneTrails: () => <div>TRAILS</div> }));
-vi.mock('@/r3f/HaulerShips', () => ({ HaulerShips: () => <div>HAULERS</div> }));
+vi.mock('@/r3f/HaulerShips', () => ({
+  HaulerShips: () => <div>HAULERS</div>,
+  RustHaulerShips: () => <div>RUST_HAULERS</div>,
+}));
 vi.mock('@/r3f/TransferLines', () => ({ TransferLines: () => <div>LINES</div> }));
 
 vi.mock('@/hooks/useFactoryAutofit', () => ({ useFactoryAutofit: () => undefined }));
@@ -5,6 +5,7 @@ const idleColor = new Color('#94a3b8');
 const miningColor = new Color('#f97316');
 const returningColor = new Color('#22d3ee');
 const unloadingColor = new Color('#cbd5f5');
+const disabledColor = new Color('#7f1d1d');
 
 export const colorForState = (state: DroneState) => {
   switch (state) {
@@ -14,9 +15,11 @@ export const colorForState = (state: DroneState) => {
       return returningColor;
     case 'unloading':
       return unloadingColor;
+    case 'disabled':
+      return disabledColor;
     default:
       return idleColor;
   }
 };
 
-export { idleColor, miningColor, returningColor, unloadingColor };
+export { idleColor, miningColor, returningColor, unloadingColor, disabledColor };
@@ -29,6 +29,8 @@ export const BASE_ENERGY_CAP = 100;
 export const ENERGY_PER_SOLAR = 25;
 export const SOLAR_BASE_GEN = 7;
 export const DRONE_ENERGY_COST = 0.9;
+/** Engine events kept in the store for the UI; older ones are dropped. */
+export const ENGINE_EVENT_LIMIT = 100;
 // Solar Collector upgrade (factory-local)
 export {
   FACTORY_SOLAR_BASE_REGEN,
@@ -323,6 +325,7 @@ export interface PrestigeInvestmentDefinition {
   baseCost: number;
   growthFactor: number;
   bonusPerTier: number;
+  maxTier: number;
 }
 
 export const prestigeInvestmentDefinitions: Record<
@@ -337,6 +340,7 @@ export const prestigeInvestmentDefinitions: Record<
     baseCost: 1_000,
     growthFactor: 1.5,
     bonusPerTier: 0.02,
+    maxTier: 20,
   },
   asteroidAbundance: {
     id: 'asteroidAbundance',
@@ -346,6 +350,7 @@ export const prestigeInvestmentDefinitions: Record<
     baseCost: 1_000,
     growthFactor: 1.5,
     bonusPerTier: 0.02,
+    maxTier: 20,
   },
   refineryMastery: {
     id: 'refineryMastery',
@@ -355,6 +360,7 @@ export const prestigeInvestmentDefinitions: Record<
     baseCost: 1_000,
     growthFactor: 1.5,
     bonusPerTier: 0.01,
+    maxTier: 20,
   },
   offlineEfficiency: {
     id: 'offlineEfficiency',
@@ -364,5 +370,6 @@ export const prestigeInvestmentDefinitions: Record<
     baseCost: 1_000,
     growthFactor: 1.5,
     bonusPerTier: 0.03,
+    maxTier: 20,
   },
 };
@@ -11,6 +11,7 @@ import {
 } from '@/state/store';
 import { migrateSnapshot } from '@/state/migrations';
 import { computeOfflineSeconds, simulateOfflineProgress } from '@/lib/offline';
+import { getBridge, isBridgeReady } from '@/lib/rustBridgeRegistry';
 
 export const SAVE_KEY = 'space-factory-save';
 
@@ -64,6 +65,11 @@ export const createPersistenceManager = (
     if (!hasStorage()) return;
     const now = Date.now();
     store.getState().setLastSave(now);
+    // The Rust engine owns contracts and the like while it runs.
+    const bridge = store.getState().settings.useRustSim && isBridgeReady() ? getBridge() : null;
+    if (bridge) {
+      store.getState().syncEngineState(bridge.exportSnapshot());
+    }
     const snapshot = serializeStore(store.getState());
     snapshot.save.lastSave = now;
     snapshot.save.version ??= saveVersion;
@@ -38,6 +38,7 @@ export {
   normalizePerformanceProfile,
   normalizeSettings,
   normalizeSnapshot,
+  normalizeEngineState,
   serializeStore,
   stringifySnapshot,
   parseSnapshot,
@@ -81,6 +81,7 @@ export const normalizeRefineSnapshot = (value: unknown): RefineProcessSnapshot |
   return {
     id: raw.id,
     oreType: raw.oreType,
+    ...(typeof raw.recipe === 'string' && raw.recipe.length > 0 ? { recipe: raw.recipe } : {}),
     amount,
     progress,
     timeTotal,
@@ -98,6 +99,7 @@ const copyRefineProcessShape = <T extends RefineProcess | RefineProcessSnapshot>
   ({
     id: source.id,
     oreType: source.oreType,
+    ...(source.recipe !== undefined ? { recipe: source.recipe } : {}),
     amount: source.amount,
     progress: source.progress,
     timeTotal: source.timeTotal,
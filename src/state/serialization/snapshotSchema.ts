@@ -35,6 +35,8 @@ export const StoreSnapshotSchema = {
         haulerDepot: { type: 'integer', minimum: 0 },
         logisticsHub: { type: 'integer', minimum: 0 },
         routingProtocol: { type: 'integer', minimum: 0 },
+        lab: { type: 'integer', minimum: 0 },
+        dataCore: { type: 'integer', minimum: 0 },
       },
       required: ['droneBay', 'refinery', 'storage', 'solar', 'scanner', 'haulerDepot', 'logisticsHub', 'routingProtocol'],
       additionalProperties: true,
@@ -12,8 +12,20 @@ import type {
   SpecTechSpentState,
   PrestigeInvestmentState,
   PendingTransfer,
+  HaulerFlight,
   LogisticsQueues,
+  LogisticsAnalytics,
+  EngineState,
 } from '../types';
+import type {
+  AsteroidHazard,
+  ContractSnapshot,
+  ContractsSnapshot,
+  DroneLoadout,
+  DroneOrder,
+  MarketSnapshot,
+  ResearchSnapshot,
+} from '@/lib/wasmSimBridge';
 import {
   SAVE_VERSION,
   SCHEMA_VERSION,
@@ -28,6 +40,7 @@ import {
   specTechDefinitions,
 } from '../constants';
 import { coerceNumber } from './types';
+import { normalizeVectorTuple } from './vectors';
 import Ajv from 'ajv';
 import { StoreSnapshotSchema } from './snapshotSchema';
 
@@ -80,6 +93,8 @@ export const normalizeModules = (snapshot?: Partial<Modules>): Modules => ({
     0,
     Math.floor(coerceNumber(snapshot?.routingProtocol, initialModules.routingProtocol)),
   ),
+  lab: Math.max(0, Math.floor(coerceNumber(snapshot?.lab, 0))),
+  dataCore: Math.max(0, Math.floor(coerceNumber(snapshot?.dataCore, 0))),
 });
 
 export const normalizePrestige = (snapshot?: Partial<Prestige>): Prestige => ({
@@ -111,6 +126,24 @@ export const normalizeSpecTechSpent = (snapshot?: Partial<SpecTechSpentState>):
   ice: Math.max(0, Math.floor(coerceNumber(snapshot?.ice, initialSpecTechSpent.ice))),
 });
 
+const normalizeHaulerFlight = (value: unknown): HaulerFlight | null => {
+  if (!value || typeof value !== 'object') {
+    return null;
+  }
+  const raw = value as Partial<Record<keyof HaulerFlight, unknown>>;
+  const position = normalizeVectorTuple(raw.position);
+  if (!position) {
+    return null;
+  }
+  return {
+    position,
+    velocity: normalizeVectorTuple(raw.velocity) ?? [0, 0, 0],
+    speed: Math.max(0, coerceNumber(raw.speed, 0)),
+    movedAt: coerceNumber(raw.movedAt, 0),
+    arrivedAt: typeof raw.arrivedAt === 'number' ? coerceNumber(raw.arrivedAt, 0) : null,
+  };
+};
+
 const normalizePendingTransfer = (
   transfer?: Partial<PendingTransfer> | null,
 ): PendingTransfer | null => {
@@ -139,6 +172,7 @@ const normalizePendingTransfer = (
     transfer.status === 'in-transit' || transfer.status === 'completed'
       ? transfer.status
       : 'scheduled';
+  const hauler = normalizeHaulerFlight(transfer.hauler);
 
   return {
     id,
@@ -149,19 +183,204 @@ const normalizePendingTransfer = (
     status,
     eta,
     departedAt,
+    ...(typeof transfer.pickupOverhead === 'number'
+      ? { pickupOverhead: Math.max(0, coerceNumber(transfer.pickupOverhead, 0)) }
+      : {}),
+    ...(typeof transfer.dropoffOverhead === 'number'
+      ? { dropoffOverhead: Math.max(0, coerceNumber(transfer.dropoffOverhead, 0)) }
+      : {}),
+    ...(hauler ? { hauler } : {}),
+    ...(typeof transfer.carrierId === 'string' ? { carrierId: transfer.carrierId } : {}),
+  };
+};
+
+const normalizeLogisticsAnalytics = (
+  analytics?: Partial<LogisticsAnalytics>,
+): LogisticsAnalytics | undefined => {
+  if (!analytics || typeof analytics !== 'object') {
+    return undefined;
+  }
+  return {
+    transfersCompleted: Math.max(0, Math.floor(coerceNumber(analytics.transfersCompleted, 0))),
+    unitsDelivered: Math.max(0, coerceNumber(analytics.unitsDelivered, 0)),
+    travelSeconds: Math.max(0, coerceNumber(analytics.travelSeconds, 0)),
+    overheadSeconds: Math.max(0, coerceNumber(analytics.overheadSeconds, 0)),
   };
 };
 
 const normalizeLogisticsQueues = (queues?: Partial<LogisticsQueues>): LogisticsQueues => {
+  const analytics = normalizeLogisticsAnalytics(queues?.analytics);
   if (!queues || !Array.isArray(queues.pendingTransfers)) {
-    return { pendingTransfers: [] };
+    return analytics ? { pendingTransfers: [], analytics } : { pendingTransfers: [] };
   }
 
   const normalized = queues.pendingTransfers
     .map((entry) => normalizePendingTransfer(entry))
     .filter((entry): entry is PendingTransfer => entry !== null);
 
-  return { pendingTransfers: normalized };
+  return analytics ? { pendingTransfers: normalized, analytics } : { pendingTransfers: normalized };
+};
+
+const isRecord = (value: unknown): value is Record<string, unknown> =>
+  typeof value === 'object' && value !== null && !Array.isArray(value);
+
+const normalizeStringList = (value: unknown): string[] =>
+  Array.isArray(value) ? value.filter((entry): entry is string => typeof entry === 'string') : [];
+
+const normalizeNumberRecord = (value: unknown): Record<string, number> => {
+  const normalized: Record<string, number> = {};
+  if (!isRecord(value)) return normalized;
+  for (const [key, entry] of Object.entries(value)) {
+    if (typeof entry === 'number' && Number.isFinite(entry)) {
+      normalized[key] = entry;
+    }
+  }
+  return normalized;
+};
+
+const normalizeAsteroidHazard = (value: unknown): AsteroidHazard | null => {
+  if (!isRecord(value)) return null;
+  const { asteroidId, regionId, kind, severity } = value;
+  if (typeof asteroidId !== 'string' || typeof kind !== 'string' || typeof severity !== 'string') {
+    return null;
+  }
+  return {
+    asteroidId,
+    ...(typeof regionId === 'string' ? { regionId } : {}),
+    kind,
+    severity,
+    active: value.active === true,
+    remaining: Math.max(0, coerceNumber(value.remaining, 0)),
+  };
+};
+
+const normalizeDroneLoadout = (value: unknown): DroneLoadout => {
+  const loadout = isRecord(value) ? value : {};
+  const tier = (entry: unknown) => Math.max(0, Math.floor(coerceNumber(entry, 0)));
+  return {
+    tool: tier(loadout.tool),
+    engine: tier(loadout.engine),
+    hull: tier(loadout.hull),
+    aiModule: tier(loadout.aiModule),
+  };
+};
+
+const normalizeDroneOrder = (value: unknown): DroneOrder | null => {
+  if (!isRecord(value)) return null;
+  switch (value.kind) {
+    case 'goTo':
+      return typeof value.asteroidId === 'string'
+        ? {
+            kind: 'goTo',
+            asteroidId: value.asteroidId,
+            regionId: typeof value.regionId === 'string' ? value.regionId : null,
+          }
+        : null;
+    case 'recall':
+      return typeof value.factoryId === 'string'
+        ? { kind: 'recall', factoryId: value.factoryId }
+        : null;
+    case 'hold':
+      return { kind: 'hold' };
+    case 'pin':
+      return typeof value.asteroidId === 'string'
+        ? { kind: 'pin', asteroidId: value.asteroidId }
+        : null;
+    default:
+      return null;
+  }
+};
+
+const normalizeMarket = (value: unknown): MarketSnapshot => {
+  const market = isRecord(value) ? value : {};
+  return {
+    prices: normalizeNumberRecord(market.prices),
+    autoSell: normalizeNumberRecord(market.autoSell),
+  };
+};
+
+const normalizeContract = (value: unknown): ContractSnapshot | null => {
+  if (!isRecord(value) || typeof value.id !== 'string' || typeof value.resource !== 'string') {
+    return null;
+  }
+  const reward = isRecord(value.reward) ? value.reward : {};
+  return {
+    id: value.id,
+    resource: value.resource,
+    amount: Math.max(0, coerceNumber(value.amount, 0)),
+    delivered: Math.max(0, coerceNumber(value.delivered, 0)),
+    duration: Math.max(0, coerceNumber(value.duration, 0)),
+    deadline: coerceNumber(value.deadline, 0),
+    reward: {
+      bars: Math.max(0, coerceNumber(reward.bars, 0)),
+      credits: Math.max(0, coerceNumber(reward.credits, 0)),
+      cores: Math.max(0, Math.floor(coerceNumber(reward.cores, 0))),
+    },
+  };
+};
+
+const normalizeContracts = (value: unknown): ContractsSnapshot => {
+  const contracts = isRecord(value) ? value : {};
+  const list = (entries: unknown) =>
+    Array.isArray(entries)
+      ? entries
+          .map((entry) => normalizeContract(entry))
+          .filter((entry): entry is ContractSnapshot => entry !== null)
+      : [];
+  return {
+    offers: list(contracts.offers),
+    active: list(contracts.active),
+    nextOfferAt: Math.max(0, coerceNumber(contracts.nextOfferAt, 0)),
+    nextId: Math.max(0, Math.floor(coerceNumber(contracts.nextId, 0))),
+  };
+};
+
+const normalizeResearch = (value: unknown): ResearchSnapshot => {
+  const research = isRecord(value) ? value : {};
+  const active = isRecord(research.active) ? research.active : null;
+  return {
+    points: Math.max(0, coerceNumber(research.points, 0)),
+    completed: normalizeStringList(research.completed),
+    active:
+      active && typeof active.id === 'string'
+        ? { id: active.id, progress: Math.max(0, coerceNumber(active.progress, 0)) }
+        : null,
+  };
+};
+
+/**
+ * Normalizes the Rust-only snapshot sections. Always returns fresh objects, so it doubles as a
+ * deep copy of an `EngineState`.
+ */
+export const normalizeEngineState = (snapshot: Partial<StoreSnapshot>): EngineState => {
+  const droneLoadouts: Record<string, DroneLoadout> = {};
+  if (isRecord(snapshot.droneLoadouts)) {
+    for (const [droneId, loadout] of Object.entries(snapshot.droneLoadouts)) {
+      droneLoadouts[droneId] = normalizeDroneLoadout(loadout);
+    }
+  }
+  const droneOrders: Record<string, DroneOrder> = {};
+  if (isRecord(snapshot.droneOrders)) {
+    for (const [droneId, order] of Object.entries(snapshot.droneOrders)) {
+      const normalized = normalizeDroneOrder(order);
+      if (normalized) droneOrders[droneId] = normalized;
+    }
+  }
+  return {
+    asteroidHazards: Array.isArray(snapshot.asteroidHazards)
+      ? snapshot.asteroidHazards
+          .map((entry) => normalizeAsteroidHazard(entry))
+          .filter((entry): entry is AsteroidHazard => entry !== null)
+      : [],
+    droneLoadouts,
+    droneIntegrity: normalizeNumberRecord(snapshot.droneIntegrity),
+    disabledDrones: normalizeStringList(snapshot.disabledDrones),
+    droneOrders,
+    assignedDrones: normalizeStringList(snapshot.assignedDrones),
+    market: normalizeMarket(snapshot.market),
+    contracts: normalizeContracts(snapshot.contracts),
+    research: normalizeResearch(snapshot.research),
+  };
 };
 
 export const normalizePrestigeInvestments = (
@@ -307,6 +526,7 @@ export const normalizeSnapshot = (snapshot: Partial<StoreSnapshot>): StoreSnapsh
         : undefined,
     logisticsQueues: normalizeLogisticsQueues(snapshot.logisticsQueues),
     gameTime: coerceNumber(snapshot.gameTime, 0),
+    ...normalizeEngineState(snapshot),
   };
 };
 
@@ -371,8 +591,14 @@ export const serializeStore = (state: StoreState): StoreSnapshot => ({
   factories: state.factories.map(factoryToSnapshot),
   selectedFactoryId: state.selectedFactoryId,
   droneOwners: { ...state.droneOwners },
-  logisticsQueues: { pendingTransfers: [...state.logisticsQueues.pendingTransfers] },
+  logisticsQueues: {
+    pendingTransfers: [...state.logisticsQueues.pendingTransfers],
+    ...(state.logisticsQueues.analytics
+      ? { analytics: { ...state.logisticsQueues.analytics } }
+      : {}),
+  },
   gameTime: state.gameTime,
+  ...normalizeEngineState(state.engineState),
 });
 
 export const stringifySnapshot = (snapshot: StoreSnapshot) => JSON.stringify(snapshot);
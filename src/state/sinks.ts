@@ -92,3 +92,6 @@ export const getPrestigeInvestmentLevel = (
 ) => state[investmentId] ?? 0;
 
 export const getSpecTechMaxLevel = (techId: SpecTechId) => specTechDefinitions[techId].maxLevel;
+
+export const getPrestigeInvestmentMaxTier = (investmentId: PrestigeInvestmentId) =>
+  prestigeInvestmentDefinitions[investmentId].maxTier;
@@ -135,6 +135,24 @@ export const createResourceSlice: StateCreator<
   },
 
   purchaseSpecTech: (techId) => {
+    const current = get();
+    if (current.settings.useRustSim && isBridgeReady()) {
+      const bridge = getBridge();
+      if (bridge) {
+        const before = getSpecTechLevel(current.specTechs, techId);
+        bridge.applyCommand({ type: 'PurchaseSpecTech', payload: { techId } });
+        // Rust owns spec tech levels; mirror them back into the store
+        const snapshot = bridge.exportSnapshot();
+        const specTechs: SpecTechState = { ...current.specTechs, ...snapshot.specTechs };
+        set({
+          resources: snapshot.resources,
+          specTechs,
+          specTechSpent: { ...current.specTechSpent, ...snapshot.specTechSpent },
+        });
+        return getSpecTechLevel(specTechs, techId) > before;
+      }
+    }
+
     let purchased = false;
     set((state) => {
       const definition = specTechDefinitions[techId];
@@ -174,6 +192,26 @@ export const createResourceSlice: StateCreator<
   },
 
   investPrestige: (investmentId) => {
+    const current = get();
+    if (current.settings.useRustSim && isBridgeReady()) {
+      const bridge = getBridge();
+      if (bridge) {
+        const before = current.prestigeInvestments[investmentId] ?? 0;
+        bridge.applyCommand({ type: 'InvestPrestige', payload: { investmentId } });
+        const snapshot = bridge.exportSnapshot();
+        const prestigeInvestments: PrestigeInvestmentState = {
+          ...current.prestigeInvestments,
+          ...snapshot.prestigeInvestments,
+        };
+        set({
+          resources: snapshot.resources,
+          prestigeInvestments,
+          specTechSpent: { ...current.specTechSpent, ...snapshot.specTechSpent },
+        });
+        return (prestigeInvestments[investmentId] ?? 0) > before;
+      }
+    }
+
     let invested = false;
     set((state) => {
       const definition = prestigeInvestmentDefinitions[investmentId];
@@ -181,6 +219,9 @@ export const createResourceSlice: StateCreator<
         return state;
       }
       const currentLevel = state.prestigeInvestments[investmentId] ?? 0;
+      if (currentLevel >= definition.maxTier) {
+        return state;
+      }
       const cost = getPrestigeInvestmentCost(investmentId, currentLevel);
       const resourceKey = definition.resource;
       const available = state.resources[resourceKey] ?? 0;
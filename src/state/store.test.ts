@@ -76,6 +76,18 @@ describe('state/store', () => {
     expect(afterPrestige.specTechSpent.metals).toBe(0);
   });
 
+  it('refuses prestige investments at the max tier', () => {
+    const store = createStoreInstance();
+    store.setState((state) => ({
+      resources: { ...state.resources, metals: 1e9 },
+      prestigeInvestments: { ...state.prestigeInvestments, droneVelocity: 20 },
+    }));
+
+    expect(store.getState().investPrestige('droneVelocity')).toBe(false);
+    expect(store.getState().prestigeInvestments.droneVelocity).toBe(20);
+    expect(store.getState().resources.metals).toBe(1e9);
+  });
+
   it('converts ore into bars using refinery and prestige multipliers', () => {
     const store = createStoreInstance();
     const base = store.getState();
@@ -311,6 +323,62 @@ describe('state/store', () => {
     expect(seededStore.getState().rngSeed).toBe(987654321);
   });
 
+  it('round-trips Rust engine sections through save and load', () => {
+    const engineSections = {
+      asteroidHazards: [
+        { asteroidId: 'a-1', kind: 'ionStorm', severity: 'high', active: true, remaining: 12 },
+        { asteroidId: 'a-2', regionId: 'r-0', kind: 'sporeBurst', severity: 'low', active: false, remaining: 40 },
+      ],
+      droneLoadouts: { 'drone-0': { tool: 2, engine: 1, hull: 0, aiModule: 3 } },
+      droneIntegrity: { 'drone-0': 35 },
+      disabledDrones: ['drone-1'],
+      droneOrders: {
+        'drone-0': { kind: 'goTo' as const, asteroidId: 'a-1', regionId: null },
+        'drone-2': { kind: 'recall' as const, factoryId: 'factory-0' },
+      },
+      assignedDrones: ['drone-0'],
+      market: { prices: { ore: 0.8, bars: 14 }, autoSell: { ore: 25 } },
+      contracts: {
+        offers: [],
+        active: [
+          {
+            id: 'contract-3',
+            resource: 'bars',
+            amount: 50,
+            delivered: 20,
+            duration: 300,
+            deadline: 480,
+            reward: { bars: 0, credits: 900, cores: 1 },
+          },
+        ],
+        nextOfferAt: 600,
+        nextId: 4,
+      },
+      research: { points: 42, completed: ['haulerDepot'], active: { id: 'lab', progress: 7 } },
+    };
+    const store = createStoreInstance();
+    const base = serializeStore(store.getState());
+    store.getState().applySnapshot({ ...base, ...engineSections });
+
+    const loaded = createStoreInstance();
+    expect(loaded.getState().importState(store.getState().exportState())).toBe(true);
+    const saved = serializeStore(loaded.getState());
+    expect(saved).toMatchObject(engineSections);
+  });
+
+  it('round-trips logistics analytics through save and load', () => {
+    const analytics = { transfersCompleted: 3, unitsDelivered: 120, travelSeconds: 40, overheadSeconds: 9 };
+    const store = createStoreInstance();
+    store.getState().applySnapshot({
+      ...serializeStore(store.getState()),
+      logisticsQueues: { pendingTransfers: [], analytics },
+    });
+
+    const loaded = createStoreInstance();
+    expect(loaded.getState().importState(store.getState().exportState())).toBe(true);
+    expect(serializeStore(loaded.getState()).logisticsQueues?.analytics).toEqual(analytics);
+  });
+
   it('records and clears drone flight snapshots', () => {
     const store = createStoreInstance();
     const api = store.getState();
@@ -21,6 +21,7 @@ import {
 } from './metrics';
 import {
   normalizeSnapshot,
+  normalizeEngineState,
   snapshotToFactory,
   normalizeDroneOwners,
   serializeStore,
@@ -41,6 +42,7 @@ import {
   initialSpecTechs,
   initialSpecTechSpent,
   initialPrestigeInvestments,
+  ENGINE_EVENT_LIMIT,
 } from './constants';
 
 // Re-export all types and utilities
@@ -82,6 +84,8 @@ export type {
   PrestigeInvestmentState,
   SpecTechId,
   PrestigeInvestmentId,
+  EngineEvent,
+  EngineState,
   MetricsState,
   MetricSample,
   FactoryMetricSeries,
@@ -182,6 +186,8 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
     logisticsQueues: { pendingTransfers: [] },
     metrics: createMetricsState(),
     highlightedFactories: { sourceId: null, destId: null },
+    engineEvents: [],
+    engineState: normalizeEngineState({}),
 
     // Game loop tick orchestrator
     tick: (dt) => {
@@ -326,6 +332,8 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
           selectedFactoryId,
           droneOwners: normalizeDroneOwners(normalized.droneOwners ?? {}),
           highlightedFactories: { sourceId: null, destId: null },
+          engineEvents: [],
+          engineState: normalizeEngineState(normalized),
           metrics: resetMetricsState(),
         };
       }),
@@ -370,6 +378,8 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
           selectedFactoryId,
           droneOwners: {},
           highlightedFactories: { sourceId: null, destId: null },
+          engineEvents: [],
+          engineState: normalizeEngineState({}),
           metrics: resetMetricsState(),
         };
       });
@@ -400,6 +410,17 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
       set({ resources });
     },
 
+    syncEngineState: (snapshot) => {
+      set({ engineState: normalizeEngineState(snapshot) });
+    },
+
+    recordEngineEvents: (events) => {
+      if (events.length === 0) return;
+      set((state) => ({
+        engineEvents: [...state.engineEvents, ...events].slice(-ENGINE_EVENT_LIMIT),
+      }));
+    },
+
     // Sync per-factory buffers coming from Rust. This updates only the
     // fields that are represented by the bridge (resources, energy, energyCapacity, haulersAssigned)
     syncFactoriesFromRust: (buffers) => {
@@ -408,6 +429,8 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
         if (!factories || factories.length === 0) return current;
 
         const { resources: resBuf, energy: energyBuf, maxEnergy: maxEnergyBuf, haulers: haulersBuf } = buffers || {};
+        const resourceComponents = buffers?.resourceComponents ?? [];
+        const resourceStride = resourceComponents.length;
 
         const resourcesArray: Float32Array | null = Array.isArray(resBuf) ? new Float32Array(resBuf) : resBuf instanceof Float32Array ? resBuf : null;
         const energyArray: Float32Array | null = Array.isArray(energyBuf) ? new Float32Array(energyBuf) : energyBuf instanceof Float32Array ? energyBuf : null;
@@ -418,18 +441,25 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
           let changed = false;
           const clone = { ...factory };
 
-          // Resources buffer expected as 7 floats per factory
-          if (resourcesArray && resourcesArray.length >= (idx * 7 + 7)) {
-            const base = idx * 7;
+          // Resources buffer holds one value per descriptor component for each factory
+          if (resourcesArray && resourceStride > 0 && resourcesArray.length >= (idx + 1) * resourceStride) {
+            const base = idx * resourceStride;
             const currentRes = clone.resources;
+            const read = (name: keyof typeof currentRes) => {
+              const offset = resourceComponents.indexOf(name);
+              const value = offset >= 0 ? resourcesArray[base + offset] : Number.NaN;
+              return Number.isFinite(value) ? value : (currentRes[name] ?? 0);
+            };
             const newRes = {
-              ore: Number.isFinite(resourcesArray[base]) ? resourcesArray[base] : currentRes.ore,
-              ice: Number.isFinite(resourcesArray[base + 1]) ? resourcesArray[base + 1] : currentRes.ice,
-              metals: Number.isFinite(resourcesArray[base + 2]) ? resourcesArray[base + 2] : currentRes.metals,
-              crystals: Number.isFinite(resourcesArray[base + 3]) ? resourcesArray[base + 3] : currentRes.crystals,
-              organics: Number.isFinite(resourcesArray[base + 4]) ? resourcesArray[base + 4] : currentRes.organics,
-              bars: Number.isFinite(resourcesArray[base + 5]) ? resourcesArray[base + 5] : currentRes.bars,
-              credits: Number.isFinite(resourcesArray[base + 6]) ? resourcesArray[base + 6] : currentRes.credits,
+              ore: read('ore'),
+              ice: read('ice'),
+              metals: read('metals'),
+              crystals: read('crystals'),
+              organics: read('organics'),
+              bars: read('bars'),
+              credits: read('credits'),
+              alloys: read('alloys'),
+              coolant: read('coolant'),
             };
             clone.resources = { ...clone.resources, ...newRes };
             changed = true;
@@ -453,6 +483,16 @@ const storeCreator: StateCreator<StoreState> = (set, get) => {
             }
           }
 
+          const usage = buffers?.haulerUsage?.[factory.id];
+          if (usage) {
+            clone.logisticsState = {
+              ...(clone.logisticsState ?? { outboundReservations: {}, inboundSchedules: [] }),
+              haulersBusy: usage.busy,
+              haulerUtilization: usage.utilization,
+            };
+            changed = true;
+          }
+
           return changed ? clone : factory;
         });
 
@@ -11,6 +11,18 @@ import type {
   haulerModuleDefinitions,
   factoryHaulerUpgradeDefinitions,
 } from './constants';
+import type {
+  AsteroidHazard,
+  ContractEvent,
+  ContractsSnapshot,
+  DroneLoadout,
+  DroneOrder,
+  DroneOrderEvent,
+  HaulerUsage,
+  HazardEvent,
+  MarketSnapshot,
+  ResearchSnapshot,
+} from '@/lib/wasmSimBridge';
 
 export type { FactoryResources, FactoryUpgrades };
 
@@ -44,6 +56,8 @@ export interface RefineProcessSnapshot {
   id: string;
   /** Type of ore being refined. */
   oreType: string;
+  /** Recipe id the batch runs, e.g. 'bars', 'alloys' or 'coolant' (Rust engine only). */
+  recipe?: string;
   /** Amount of input ore. */
   amount: number;
   /** Progress towards completion (0-1). */
@@ -74,6 +88,10 @@ export interface FactoryResourceSnapshot {
   ice: number;
   /** Local credits (if applicable). */
   credits: number;
+  /** Alloys refined from metals and crystals (Rust engine only). */
+  alloys?: number;
+  /** Coolant refined from ice (Rust engine only). */
+  coolant?: number;
 }
 
 /**
@@ -90,6 +108,10 @@ export interface FactoryUpgradeSnapshot {
   energy: number;
   /** Solar array level. */
   solar: number;
+  /** Unload rate level (Rust engine only). */
+  unload?: number;
+  /** Cooler level (Rust engine only). */
+  cooler?: number;
 }
 
 /**
@@ -134,6 +156,8 @@ export interface FactorySnapshot {
   queuedDrones: string[];
   /** Active refining processes. */
   activeRefines: RefineProcessSnapshot[];
+  /** Recipe id per refine slot, e.g. 'bars', 'alloys' or 'coolant' (Rust engine only). */
+  refineRecipes?: string[];
   /** Whether factory is pinned in UI. */
   pinned: boolean;
   /** Current energy stored. */
@@ -154,6 +178,26 @@ export interface FactorySnapshot {
   haulerUpgrades?: FactoryHaulerUpgrades;
   /** Internal logistics state. */
   logisticsState?: FactoryLogisticsState;
+  /** Refinery heat (Rust engine only). */
+  heat?: number;
+  /** Whether the refinery is paused to cool down after overheating (Rust engine only). */
+  cooling?: boolean;
+  /** Whether refinery overdrive is enabled (Rust engine only). */
+  overdrive?: boolean;
+  /** Dock throughput and wait (Rust engine only). */
+  docking?: DockingMetrics;
+}
+
+/** Per-factory dock metrics reported by the Rust engine's unload system. */
+export interface DockingMetrics {
+  /** Cargo unloaded per second, smoothed over the last few seconds. */
+  unloadRate: number;
+  /** Cargo unloaded since the factory was built. */
+  unitsUnloaded: number;
+  /** Drones queued beyond the docking capacity. */
+  waiting: number;
+  /** Longest the wait has been. */
+  peakWaiting: number;
 }
 
 /**
@@ -171,7 +215,7 @@ export interface HaulerConfig {
   /** Allowed resources to transport. */
   resourceFilters: string[];
   /** Dispatching mode logic. */
-  mode: 'auto' | 'manual' | 'demand-first' | 'supply-first';
+  mode: 'auto' | 'manual' | 'demand-first' | 'supply-first' | 'export-only' | 'import-only' | 'warehouse-only';
   /** Scheduler priority. */
   priority: number;
 }
@@ -201,6 +245,12 @@ export interface FactoryLogisticsState {
     amount: number;
     eta: number;
   }>;
+  /** Game time each empty hauler flying home gets back; Rust sim only. */
+  returningHaulers?: number[];
+  /** Haulers carrying a transfer or flying home; Rust sim only. */
+  haulersBusy?: number;
+  /** `haulersBusy` over assigned haulers, 0 to 1; Rust sim only. */
+  haulerUtilization?: number;
 }
 
 /**
@@ -223,6 +273,28 @@ export interface PendingTransfer {
   eta: number;
   /** Departure time (game time). */
   departedAt: number;
+  /** Seconds spent loading before the hauler leaves; Rust sim only. */
+  pickupOverhead?: number;
+  /** Seconds spent unloading before `eta`; Rust sim only. */
+  dropoffOverhead?: number;
+  /** The hauler's flight once it has left the source; Rust sim only. */
+  hauler?: HaulerFlight | null;
+  /** Factory that lent the hauler when neither end flies it; Rust sim only. */
+  carrierId?: string;
+}
+
+/**
+ * A transfer's hauler in flight, advanced by the Rust logistics system.
+ */
+export interface HaulerFlight {
+  position: VectorTuple;
+  velocity: VectorTuple;
+  /** Cruise speed fixed at launch. */
+  speed: number;
+  /** Game time the position was last advanced to. */
+  movedAt: number;
+  /** When the hauler reached the destination and began unloading. */
+  arrivedAt: number | null;
 }
 
 /**
@@ -231,8 +303,30 @@ export interface PendingTransfer {
 export interface LogisticsQueues {
   /** List of all active/pending transfers. */
   pendingTransfers: PendingTransfer[];
+  /** Totals over completed transfers; Rust sim only. */
+  analytics?: LogisticsAnalytics;
+}
+
+/**
+ * Running totals over every completed transfer.
+ */
+export interface LogisticsAnalytics {
+  /** Number of transfers that arrived. */
+  transfersCompleted: number;
+  /** Units delivered across all transfers. */
+  unitsDelivered: number;
+  /** Seconds haulers spent flying loaded. */
+  travelSeconds: number;
+  /** Seconds haulers spent loading and unloading. */
+  overheadSeconds: number;
 }
 
+/** An event drained from the Rust engine, tagged with the stream it came from. */
+export type EngineEvent =
+  | ({ source: 'hazard' } & HazardEvent)
+  | ({ source: 'order' } & DroneOrderEvent)
+  | ({ source: 'contract' } & ContractEvent);
+
 /**
  * State for highlighting factory interactions in the UI.
  */
@@ -281,8 +375,11 @@ export interface MetricsState {
   accumulatorMs: number;
 }
 
-/** Current phase of a drone's flight. */
-export type DroneFlightPhase = 'toAsteroid' | 'returning';
+/**
+ * Current phase of a drone's flight. `queued` is a Rust-engine leg to a holding spot
+ * beside a factory whose docks are full.
+ */
+export type DroneFlightPhase = 'toAsteroid' | 'returning' | 'queued';
 
 /**
  * Persistent state of a drone's flight.
@@ -330,6 +427,10 @@ export interface Modules {
   haulerDepot: number;
   logisticsHub: number;
   routingProtocol: number;
+  /** Produces research points; Rust sim only. */
+  lab?: number;
+  /** Boosts lab output once `researchNetwork` is researched; Rust sim only. */
+  dataCore?: number;
 }
 
 /**
@@ -377,6 +478,8 @@ export interface StoreSettings {
   useRustSim: boolean;
   /** Run Rust simulation in background to verify parity */
   shadowMode: boolean;
+  /** Let the Rust engine shift drone ownership toward under-served factories. */
+  autoBalanceDrones?: boolean;
   /** Graphics quality profile. */
   performanceProfile: PerformanceProfile;
   /** Whether the inspector panel is collapsed. */
@@ -418,6 +521,34 @@ export interface StoreSnapshot {
   specTechSpent?: SpecTechSpentState;
   prestigeInvestments?: PrestigeInvestmentState;
   gameTime?: number;
+  asteroidHazards?: AsteroidHazard[];
+  droneLoadouts?: Record<string, DroneLoadout>;
+  droneIntegrity?: Record<string, number>;
+  disabledDrones?: string[];
+  droneOrders?: Record<string, DroneOrder>;
+  assignedDrones?: string[];
+  market?: MarketSnapshot;
+  contracts?: ContractsSnapshot;
+  research?: ResearchSnapshot;
+}
+
+/**
+ * Snapshot sections only the Rust engine reads or writes. The store keeps them so saves made
+ * in either mode carry them through.
+ */
+export interface EngineState {
+  asteroidHazards: AsteroidHazard[];
+  droneLoadouts: Record<string, DroneLoadout>;
+  /** Hull integrity of damaged drones; drones missing here are at full integrity. */
+  droneIntegrity: Record<string, number>;
+  /** Drones parked by hull damage until repaired. */
+  disabledDrones: string[];
+  droneOrders: Record<string, DroneOrder>;
+  /** Drones whose owner was set by command. */
+  assignedDrones: string[];
+  market: MarketSnapshot;
+  contracts: ContractsSnapshot;
+  research: ResearchSnapshot;
 }
 
 /**
@@ -448,6 +579,9 @@ export interface StoreState {
   selectedFactoryId: string | null;
   droneOwners: Record<string, string | null>;
   highlightedFactories: HighlightedFactories;
+  /** Most recent engine events, oldest first; capped at `ENGINE_EVENT_LIMIT`. */
+  engineEvents: EngineEvent[];
+  engineState: EngineState;
 
   // Actions
   /** Adds resources to the global inventory. */
@@ -567,12 +701,20 @@ export interface StoreState {
   /** Syncs logistics queues from an external source (e.g. Rust). */
   syncLogisticsQueues(this: void, queues: LogisticsQueues): void;
   syncResources(this: void, resources: Resources): void;
+  /** Appends events drained from the Rust engine, dropping the oldest past the cap. */
+  recordEngineEvents(this: void, events: EngineEvent[]): void;
+  /** Replaces the Rust-only snapshot sections, e.g. from an engine export before saving. */
+  syncEngineState(this: void, snapshot: StoreSnapshot): void;
   /** Syncs per-factory buffers coming from Rust into the store. */
   syncFactoriesFromRust(this: void, buffers: {
     resources?: Float32Array | number[] | null;
+    /** Component names of the resources section, from the Rust layout descriptor. */
+    resourceComponents?: readonly string[];
     energy?: Float32Array | number[] | null;
     maxEnergy?: Float32Array | number[] | null;
     haulers?: Float32Array | number[] | null;
+    /** Busy haulers and utilization keyed by factory id. */
+    haulerUsage?: Record<string, HaulerUsage> | null;
   }): void;
 }
 
@@ -16,11 +16,21 @@ declare module '@/gen/rust_engine' {
     export_snapshot(): string;
     step(_: number): number;
     apply_command(_: string): void;
+    prestige(): string;
+    generate_asteroid_field(_: number): number;
     layout_json(): string;
+    layout_descriptor_json(): string;
     drone_ids_json(): string;
     asteroid_ids_json(): string;
+    hauler_transfer_ids_json(): string;
+    market_prices_json(): string;
+    hauler_usage_json(): string;
+    drain_hazard_events_json(): string;
+    drain_order_events_json(): string;
+    drain_contract_events_json(): string;
     get_logistics_queues(): string;
     data_ptr(): number;
+    generation(): number;
     constructor(json: string);
   }
   export default function __wbg_init(...args: unknown[]): Promise<InitOutput>;
@@ -34,11 +44,21 @@ declare module '../gen/rust_engine' {
     export_snapshot(): string;
     step(_: number): number;
     apply_command(_: string): void;
+    prestige(): string;
+    generate_asteroid_field(_: number): number;
     layout_json(): string;
+    layout_descriptor_json(): string;
     drone_ids_json(): string;
     asteroid_ids_json(): string;
+    hauler_transfer_ids_json(): string;
+    market_prices_json(): string;
+    hauler_usage_json(): string;
+    drain_hazard_events_json(): string;
+    drain_order_events_json(): string;
+    drain_contract_events_json(): string;
     get_logistics_queues(): string;
     data_ptr(): number;
+    generation(): number;
     constructor(json: string);
   }
   export default function __wbg_init(...args: unknown[]): Promise<InitOutput>;
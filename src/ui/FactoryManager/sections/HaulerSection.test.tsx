@@ -0,0 +1,32 @@
+import { describe, it, expect } from 'vitest';
+import { render } from '@testing-library/react';
+import { HaulerSection } from './HaulerSection';
+import { createMockFactory } from './testHelpers';
+
+describe('HaulerSection', () => {
+  it('shows busy haulers and utilization reported by the Rust sim', () => {
+    const factory = createMockFactory({
+      haulersAssigned: 4,
+      logisticsState: {
+        outboundReservations: {},
+        inboundSchedules: [],
+        haulersBusy: 3,
+        haulerUtilization: 0.75,
+      },
+    });
+
+    const { container } = render(<HaulerSection factory={factory} onAssignHaulers={() => true} />);
+
+    expect(container.querySelector('.hauler-utilization')?.textContent).toBe(
+      'Busy: 3 of 4 · Utilization: 75%',
+    );
+  });
+
+  it('hides utilization until the logistics system has counted the haulers', () => {
+    const factory = createMockFactory({ haulersAssigned: 2 });
+
+    const { container } = render(<HaulerSection factory={factory} onAssignHaulers={() => true} />);
+
+    expect(container.querySelector('.hauler-utilization')).toBeNull();
+  });
+});
@@ -72,6 +72,15 @@ export const HaulerSection = ({ factory, onAssignHaulers }: HaulerSectionProps)
               This factory has {factory.haulersAssigned} hauler
               {factory.haulersAssigned === 1 ? '' : 's'} assigned.
             </p>
+            {factory.logisticsState?.haulersBusy !== undefined ? (
+              <p className="hauler-utilization">
+                Busy: <strong>{factory.logisticsState.haulersBusy}</strong> of{' '}
+                {factory.haulersAssigned} · Utilization:{' '}
+                <strong>
+                  {Math.round((factory.logisticsState.haulerUtilization ?? 0) * 100)}%
+                </strong>
+              </p>
+            ) : null}
             <p className="next-cost">Next: {Math.ceil(nextCost)} bars</p>
           </div>
         ) : (
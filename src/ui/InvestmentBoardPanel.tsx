@@ -7,6 +7,7 @@ import {
 } from '@/state/store';
 import {
   getPrestigeInvestmentCost,
+  getPrestigeInvestmentMaxTier,
   getSinkBonuses,
 } from '@/state/sinks';
 import { formatInteger, formatPercent } from '@/lib/formatters';
@@ -17,10 +18,12 @@ interface InvestmentEntry {
   description: string;
   resource: keyof SpecTechSpentState;
   level: number;
+  maxTier: number;
   cost: number;
   bonusPerTier: number;
   totalBonus: number;
   canAfford: boolean;
+  maxed: boolean;
 }
 
 export const InvestmentBoardPanel = () => {
@@ -41,16 +44,20 @@ export const InvestmentBoardPanel = () => {
       const resource = definition.resource;
       const available = resources[resource] ?? 0;
       const totalBonus = level * definition.bonusPerTier;
+      const maxTier = getPrestigeInvestmentMaxTier(id);
+      const maxed = level >= maxTier;
       return {
         id,
         name: definition.label,
         description: definition.description,
         resource,
         level,
+        maxTier,
         cost,
         bonusPerTier: definition.bonusPerTier,
         totalBonus,
-        canAfford: available >= cost,
+        canAfford: available >= cost && !maxed,
+        maxed,
       };
     });
   }, [prestigeInvestments, resources]);
@@ -80,7 +87,7 @@ export const InvestmentBoardPanel = () => {
               <div className="warehouse-panel__investment-title">
                 <span className="warehouse-panel__investment-name">{investment.name}</span>
                 <span className="warehouse-panel__investment-level">
-                  Tier {investment.level}
+                  Tier {investment.level} / {investment.maxTier}
                 </span>
               </div>
               <span className="warehouse-panel__investment-bonus">
@@ -95,7 +102,9 @@ export const InvestmentBoardPanel = () => {
                 disabled={!investment.canAfford}
                 onClick={() => investPrestige(investment.id)}
               >
-                Invest {formatInteger(investment.cost)} {investment.resource}
+                {investment.maxed
+                  ? 'Maxed'
+                  : `Invest ${formatInteger(investment.cost)} ${investment.resource}`}
               </button>
               <span className="warehouse-panel__investment-effect">
                 +{formatPercent(investment.bonusPerTier)} per tier